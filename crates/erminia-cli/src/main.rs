@@ -1,7 +1,44 @@
-use erminia::syntax::Parser;
+use erminia::diagnostics::format::{to_json, to_sarif};
+use erminia::diagnostics::{
+    Catalog, Code, DiagnosticAccumulator, MessageFormat, SourceMap, apply_suggestions, catalog,
+};
+use erminia::lint::{LintConfig, LintLevel};
+use erminia::loader::Loader;
+use erminia::syntax::parse;
 use std::io::{self, Write};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
+// How diagnostics are printed, from the command line flags
+#[derive(Clone, Copy)]
+struct Output {
+    format: MessageFormat,
+    color: bool,
+}
+
+// Rendered diagnostics go to stderr, machine readable ones to stdout for tools to pick up
+fn report(diagnostics: &DiagnosticAccumulator, source_map: &SourceMap, output: Output) {
+    let sorted = diagnostics.sorted();
+
+    match output.format {
+        MessageFormat::Human => {
+            for diag in &sorted {
+                eprintln!("{}", diag.render_with(source_map, output.color));
+            }
+
+            if let Some(summary) = diagnostics.summary() {
+                eprintln!("{}", summary);
+            }
+        }
+        MessageFormat::Json => {
+            for diag in &sorted {
+                println!("{}", to_json(diag, source_map));
+            }
+        }
+        MessageFormat::Sarif => println!("{:#}", to_sarif(&sorted, source_map)),
+    }
+}
+
+fn repl(output: Output) -> io::Result<()> {
     let stdin = io::stdin();
 
     let mut stdout = io::stdout();
@@ -15,24 +52,252 @@ fn main() -> io::Result<()> {
         input.clear();
         stdin.read_line(&mut input)?;
 
-        let mut parser = Parser::new(&input);
-
-        let program = parser.parse();
-
-        if program.is_err() {
-            for diag in parser
-                .get_diagnostics()
-                .get(erminia::config::CompilerPass::Parser)
-            {
-                eprintln!("{}", diag);
+        match parse(&input) {
+            Ok(parsed) if parsed.has_errors() => {
+                report(&parsed.diagnostics, &parsed.source_map, output);
             }
-            continue;
+            Ok(parsed) => println!("{:?}", parsed.ast),
+            // The diagnostics in a fatal error head themselves, no `error:` needed
+            Err(err) => eprint!("{}", err.render(output.color)),
         }
 
-        println!("{:?}", program);
-
         // parse to AST Tree
         // check semantics
         // generate json
     }
 }
+
+// erminia-cli [--no-color] [--message-format <human|json|sarif>] [--error-limit <n>]
+//             [--messages <catalog>] [-I <dir>]... [-W|-D|-A <lint>]... <file>
+fn check_file(
+    path: &str,
+    search_paths: &[String],
+    lints: &LintConfig,
+    error_limit: Option<usize>,
+    output: Output,
+) -> ExitCode {
+    let mut loader = Loader::new();
+
+    for dir in search_paths {
+        loader.add_search_path(dir);
+    }
+
+    loader.set_lint_config(lints.clone());
+
+    if let Some(limit) = error_limit {
+        loader.set_error_limit(limit);
+    }
+
+    if let Err(err) = loader.load(path) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let diagnostics = loader.get_diagnostics();
+
+    report(diagnostics, loader.get_source_map(), output);
+
+    // Warnings are reported, but only errors fail the check
+    if diagnostics.error_count() > 0 || diagnostics.suppressed() > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// erminia-cli fix [-I <dir>]... [-W|-D|-A <lint>]... <file>
+//
+// Applies the machine-applicable suggestions of every diagnostic to the files they point
+// into, included files too. Other suggestions are left for the user to look at.
+fn fix_file(path: &str, search_paths: &[String], lints: &LintConfig) -> ExitCode {
+    let mut loader = Loader::new();
+
+    for dir in search_paths {
+        loader.add_search_path(dir);
+    }
+
+    loader.set_lint_config(lints.clone());
+
+    if let Err(err) = loader.load(path) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let diagnostics = loader
+        .get_diagnostics()
+        .get(erminia::config::CompilerPass::ALL);
+
+    let mut status = ExitCode::SUCCESS;
+
+    for file in loader.get_source_map().files() {
+        let suggestions: Vec<_> = diagnostics
+            .iter()
+            .flat_map(|diag| &diag.suggestions)
+            .filter(|s| s.is_machine_applicable() && s.span.file() == file.id)
+            .cloned()
+            .collect();
+
+        if suggestions.is_empty() {
+            continue;
+        }
+
+        let (fixed, applied) = apply_suggestions(&file.content, &suggestions);
+
+        match std::fs::write(&file.path, fixed) {
+            Ok(()) => eprintln!("Fixed {} ({} fixes)", file.path.display(), applied),
+            Err(err) => {
+                eprintln!("error: cannot write {}: {}", file.path.display(), err);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    status
+}
+
+// erminia-cli --explain <code>
+fn explain(code: &str) -> ExitCode {
+    match code.to_uppercase().parse::<Code>() {
+        Ok(code) => {
+            println!("{}", code.explanation());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// -W, -D and -A set the level of a lint, named after them or in the next argument
+fn lint_level_flag(arg: &str) -> Option<(LintLevel, &str)> {
+    let level = match arg.get(..2)? {
+        "-W" => LintLevel::Warn,
+        "-D" => LintLevel::Deny,
+        "-A" => LintLevel::Allow,
+        _ => return None,
+    };
+
+    Some((level, &arg[2..]))
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+
+    let mut search_paths: Vec<String> = vec![];
+    let mut lints = LintConfig::new();
+    let mut error_limit: Option<usize> = None;
+    let mut file: Option<String> = None;
+    let mut fix = false;
+    let mut output = Output {
+        format: MessageFormat::Human,
+        color: true,
+    };
+
+    while let Some(arg) = args.next() {
+        if arg == "--explain" {
+            return match args.next() {
+                Some(code) => explain(&code),
+                None => {
+                    eprintln!("error: '--explain' expects an error code");
+                    ExitCode::FAILURE
+                }
+            };
+        } else if arg == "fix" && file.is_none() && !fix {
+            fix = true;
+        } else if arg == "--no-color" {
+            output.color = false;
+        } else if arg == "--message-format" || arg.starts_with("--message-format=") {
+            let value = match arg.strip_prefix("--message-format=") {
+                Some(value) => Some(value.to_string()),
+                None => args.next(),
+            };
+
+            match value.map(|v| v.parse::<MessageFormat>()) {
+                Some(Ok(format)) => output.format = format,
+                Some(Err(err)) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+                None => {
+                    eprintln!("error: '--message-format' expects a format");
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else if arg == "--error-limit" || arg.starts_with("--error-limit=") {
+            let value = match arg.strip_prefix("--error-limit=") {
+                Some(value) => Some(value.to_string()),
+                None => args.next(),
+            };
+
+            match value.map(|v| v.parse::<usize>()) {
+                Some(Ok(limit)) if limit > 0 => error_limit = Some(limit),
+                _ => {
+                    eprintln!("error: '--error-limit' expects a positive number");
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else if arg == "--messages" || arg.starts_with("--messages=") {
+            let value = match arg.strip_prefix("--messages=") {
+                Some(value) => Some(value.to_string()),
+                None => args.next(),
+            };
+
+            match value.map(Catalog::load) {
+                Some(Ok(catalog)) => catalog::install(catalog),
+                Some(Err(err)) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+                None => {
+                    eprintln!("error: '--messages' expects a catalog file");
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else if arg == "-I" {
+            match args.next() {
+                Some(dir) => search_paths.push(dir),
+                None => {
+                    eprintln!("error: '-I' expects a directory");
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else if let Some(dir) = arg.strip_prefix("-I") {
+            search_paths.push(dir.to_string());
+        } else if let Some((level, name)) = lint_level_flag(&arg) {
+            let name = match name {
+                "" => match args.next() {
+                    Some(name) => name,
+                    None => {
+                        eprintln!("error: '{}' expects a lint name", arg);
+                        return ExitCode::FAILURE;
+                    }
+                },
+                name => name.to_string(),
+            };
+
+            if let Err(err) = lints.set(&name, level) {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        } else {
+            file = Some(arg);
+        }
+    }
+
+    match file {
+        Some(path) if fix => fix_file(&path, &search_paths, &lints),
+        None if fix => {
+            eprintln!("error: 'fix' expects a file");
+            ExitCode::FAILURE
+        }
+        Some(path) => check_file(&path, &search_paths, &lints, error_limit, output),
+        None => match repl(output) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
@@ -1,12 +1,18 @@
+use crate::ast::decl::Declare;
+use crate::ast::eval::Evaluate;
 use crate::ast::expr::*;
+use crate::ast::node::NodeId;
 use crate::ast::printon::*;
 use crate::ast::stmt::*;
+use crate::ast::visit::{walk_ast, AsNode, Visitor};
+use crate::config::CompilerPass;
 use crate::diag;
 use crate::diagnostics::{DiagnosticAccumulator, Span};
 use crate::lexer::lex::Lexer;
 
+pub use crate::error::ast_error::ASTError;
+
 pub type BoxAST<'a> = Box<dyn AST<'a> + 'a>;
-pub type ASTError = String;
 
 #[derive(Debug)]
 pub enum ASTResult<'a> {
@@ -38,13 +44,15 @@ impl<'a> ASTResult<'a> {
     }
 }
 
-pub trait AST<'a>: 'a + PrettyPrinting {
+pub trait AST<'a>: 'a + PrettyPrinting + Evaluate + Declare + AsNode<'a> {
     fn sem(&self /*, Semantic Table */) -> Result<bool, ASTError>;
     fn is_err(&self) -> bool;
     fn is_ok(&self) -> bool;
-    fn get_ast_id(&self) -> u32;
+    fn get_ast_id(&self) -> NodeId;
+    fn get_span(&self) -> Span;
+    /// The nodes directly below this one, in source order.
+    fn children(&self) -> Vec<&BoxAST<'a>>;
     fn to_string(&self) -> String;
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator);
 }
 
 impl<'a> std::fmt::Debug for dyn AST<'a> {
@@ -55,6 +63,45 @@ impl<'a> std::fmt::Debug for dyn AST<'a> {
     }
 }
 
+// ==================================================================================== //
+//  Poisoning                                                                           //
+// ==================================================================================== //
+
+struct PoisonReport<'t, 'input> {
+    tokens: &'t mut Lexer<'input>,
+    diag: &'t mut DiagnosticAccumulator,
+}
+
+impl<'a> Visitor<'a> for PoisonReport<'_, '_> {
+    fn visit_ast(&mut self, node: &BoxAST<'a>) {
+        if node.is_err() {
+            // Poisoned by an error the parser reported, as is everything below it
+            if self
+                .diag
+                .has_error_within(CompilerPass::AST, &node.get_span())
+            {
+                return;
+            }
+
+            diag!(
+                AST,
+                E0004,
+                ExpectedASTNode(node.to_string(), node.get_ast_id().to_string()),
+                self.tokens,
+                self.diag,
+                node.get_span()
+            );
+        }
+
+        walk_ast(self, node)
+    }
+}
+
+/// Reports every node of `program` that failed to parse.
+pub fn check_poisoning(program: &BoxAST<'_>, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
+    PoisonReport { tokens, diag }.visit_ast(program);
+}
+
 // ==================================================================================== //
 //  Implementations                                                                     //
 // ==================================================================================== //
@@ -76,21 +123,16 @@ impl<'a> AST<'a> for GenericTupleOption {
         "GenericTupleOption".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
-        0
+    fn get_ast_id(&self) -> NodeId {
+        NodeId::UNASSIGNED
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        Span::default()
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
 
@@ -111,25 +153,16 @@ impl<'a> AST<'a> for ProblemExample<'a> {
         "ProblemExample".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for stmt in &self.stmts {
-            stmt.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.stmts.iter().collect()
     }
 }
 
@@ -150,25 +183,16 @@ impl<'a> AST<'a> for ProblemSolution<'a> {
         "ProblemSolution".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for stmt in &self.stmts {
-            stmt.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.stmts.iter().collect()
     }
 }
 
@@ -189,25 +213,18 @@ impl<'a> AST<'a> for ProblemInput<'a> {
         "ProblemInput".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for stmt in &self.stmts {
-            stmt.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        std::iter::once(&self.tuple)
+            .chain(self.stmts.iter())
+            .collect()
     }
 }
 
@@ -228,25 +245,108 @@ impl<'a> AST<'a> for ProblemOutput<'a> {
         "ProblemOutput".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for stmt in &self.stmts {
-            stmt.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        std::iter::once(&self.tuple)
+            .chain(self.stmts.iter())
+            .collect()
+    }
+}
+
+impl<'a> AST<'a> for FuncDecl<'a> {
+    fn sem(&self) -> Result<bool, ASTError> {
+        todo!()
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "FuncDecl".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.stmts.iter().collect()
+    }
+}
+
+impl<'a> AST<'a> for ProblemDecl<'a> {
+    fn sem(&self) -> Result<bool, ASTError> {
+        todo!()
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "ProblemDecl".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.stmts.iter().collect()
+    }
+}
+
+impl<'a> AST<'a> for Include {
+    fn sem(&self) -> Result<bool, ASTError> {
+        Err("includes are resolved by the loader".into())
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "Include".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
 
@@ -267,29 +367,20 @@ impl<'a> AST<'a> for Program<'a> {
         "Program".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for stmt in &self.stmts {
-            stmt.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.items.iter().collect()
     }
 }
 
-impl<'a> AST<'a> for Range {
+impl<'a> AST<'a> for Range<'a> {
     fn sem(&self) -> Result<bool, ASTError> {
         todo!()
     }
@@ -306,21 +397,49 @@ impl<'a> AST<'a> for Range {
         "Range".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        std::iter::once(&self.left)
+            .chain(std::iter::once(&self.right))
+            .chain(self.step.iter())
+            .collect()
+    }
+}
+
+impl<'a> AST<'a> for RangeUnion<'a> {
+    fn sem(&self) -> Result<bool, ASTError> {
+        Err("range unions are checked by the semantics pass".into())
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "RangeUnion".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.ranges.iter().collect()
     }
 }
 
@@ -341,23 +460,16 @@ impl<'a> AST<'a> for TupleIterator<'a> {
         "TupleIterator".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.range.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.range]
     }
 }
 
@@ -378,25 +490,19 @@ impl<'a> AST<'a> for TupleComprehension<'a> {
         "TupleComprehension".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for iter in &self.iter_pair {
-            iter.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        std::iter::once(&self.tuple)
+            .chain(self.generators.iter())
+            .chain(self.predicates.iter())
+            .collect()
     }
 }
 
@@ -417,24 +523,16 @@ impl<'a> AST<'a> for GenericTuple<'a> {
         "GenericTuple".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.left.check_poisoning(tokens, diag);
-        let _ = &self.right.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.left, &self.right]
     }
 }
 
@@ -455,21 +553,16 @@ impl<'a> AST<'a> for Tuple {
         "Tuple".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
 
@@ -490,23 +583,16 @@ impl<'a> AST<'a> for Shape<'a> {
         "Shape".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.values.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.values]
     }
 }
 
@@ -527,25 +613,16 @@ impl<'a> AST<'a> for ObjectShape<'a> {
         "ObjectShape".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for shape in &self.shape {
-            shape.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.shape.iter().collect()
     }
 }
 
@@ -566,21 +643,16 @@ impl<'a> AST<'a> for ObjectColor {
         "ObjectColor".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
 
@@ -601,24 +673,16 @@ impl<'a> AST<'a> for ObjectDesc<'a> {
         "ObjectDesc".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.shape.check_poisoning(tokens, diag);
-        let _ = &self.color.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.shape, &self.color]
     }
 }
 
@@ -639,23 +703,16 @@ impl<'a> AST<'a> for ObjectDecl<'a> {
         "ObjectDecl".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.desc.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.desc]
     }
 }
 
@@ -676,23 +733,16 @@ impl<'a> AST<'a> for VarDef<'a> {
         "VarDef".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        let _ = &self.expr.check_poisoning(tokens, diag);
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.expr]
     }
 }
 
@@ -713,29 +763,20 @@ impl<'a> AST<'a> for FuncCall<'a> {
         "FuncCall".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        for expr in &self.exprs {
-            expr.check_poisoning(tokens, diag);
-        }
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        self.exprs.iter().collect()
     }
 }
 
-impl<'a> AST<'a> for ObjectCall<'a> {
+impl<'a> AST<'a> for ObjectCall {
     fn sem(&self) -> Result<bool, ASTError> {
         todo!()
     }
@@ -752,25 +793,76 @@ impl<'a> AST<'a> for ObjectCall<'a> {
         "ObjectCall".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
+    }
+}
+
+impl<'a> AST<'a> for BinaryExpr<'a> {
+    fn sem(&self) -> Result<bool, ASTError> {
+        Err("binary expressions are checked by the semantics pass".into())
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "BinaryExpr".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
 
-        if let Some(t) = &self.tuple {
-            t.check_poisoning(tokens, diag);
-        };
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.left, &self.right]
+    }
+}
+
+impl<'a> AST<'a> for UnaryExpr<'a> {
+    fn sem(&self) -> Result<bool, ASTError> {
+        Err("unary expressions are checked by the semantics pass".into())
+    }
+
+    fn is_err(&self) -> bool {
+        self.is_poisoned
+    }
+
+    fn is_ok(&self) -> bool {
+        !self.is_poisoned
+    }
+
+    fn to_string(&self) -> String {
+        "UnaryExpr".to_string()
+    }
+
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![&self.operand]
     }
 }
 
@@ -791,21 +883,16 @@ impl<'a> AST<'a> for RValue {
         "RValue".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
-        0
+    fn get_ast_id(&self) -> NodeId {
+        self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        if self.is_err() {
-            diag!(
-                AST,
-                E0004,
-                ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
-        }
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
 
@@ -815,29 +902,26 @@ impl<'a> AST<'a> for PoisonedStmt {
     }
 
     fn is_err(&self) -> bool {
-        false
+        true
     }
 
     fn is_ok(&self) -> bool {
-        true
+        false
     }
 
     fn to_string(&self) -> String {
         "PoisonedStmt".to_string()
     }
 
-    fn get_ast_id(&self) -> u32 {
+    fn get_ast_id(&self) -> NodeId {
         self.unique_ast_id
     }
 
-    fn check_poisoning(&self, tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) {
-        diag!(
-            AST,
-            E0004,
-            ExpectedASTNode(self.to_string(), self.get_ast_id().to_string()),
-            tokens,
-            diag,
-            Span::default()
-        );
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn children(&self) -> Vec<&BoxAST<'a>> {
+        vec![]
     }
 }
@@ -0,0 +1,145 @@
+use crate::ast::expr::*;
+use crate::ast::stmt::*;
+use crate::diagnostics::Span;
+use crate::types::ErminiaType;
+
+// ==================================================================================== //
+//  Structs                                                                             //
+// ==================================================================================== //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclKind {
+    Object,
+    Func,
+    Problem,
+}
+
+/// A name introduced at the top level of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+    pub kind: DeclKind,
+    pub name: String,
+    pub span: Span,
+}
+
+/// The module named by an `include` or `use`, e.g. `std::shapes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModulePath {
+    pub segments: Vec<String>,
+    pub span: Span,
+}
+
+impl DeclKind {
+    /// Objects and functions can be used by the files that include this one.
+    pub fn is_exported(&self) -> bool {
+        matches!(self, DeclKind::Object | DeclKind::Func)
+    }
+
+    /// What a declaration of this kind is called in messages, e.g. "function".
+    pub fn describe(&self) -> &'static str {
+        match self {
+            DeclKind::Object => "object",
+            DeclKind::Func => "function",
+            DeclKind::Problem => "problem",
+        }
+    }
+}
+
+impl std::fmt::Display for ModulePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.segments.join("::"))
+    }
+}
+
+// ==================================================================================== //
+//  Traits                                                                              //
+// ==================================================================================== //
+
+pub trait Declare {
+    fn declarations(&self) -> Vec<Declaration> {
+        vec![]
+    }
+
+    fn includes(&self) -> Vec<ModulePath> {
+        vec![]
+    }
+}
+
+// ==================================================================================== //
+//  Implementations                                                                     //
+// ==================================================================================== //
+
+fn declaration(kind: DeclKind, id: &ErminiaType, span: Span) -> Vec<Declaration> {
+    match id {
+        ErminiaType::Ident(name) => vec![Declaration {
+            kind,
+            name: name.to_string(),
+            span,
+        }],
+        _ => vec![],
+    }
+}
+
+impl Declare for ObjectDecl<'_> {
+    fn declarations(&self) -> Vec<Declaration> {
+        declaration(DeclKind::Object, &self.id, self.span)
+    }
+}
+
+impl Declare for FuncDecl<'_> {
+    fn declarations(&self) -> Vec<Declaration> {
+        declaration(DeclKind::Func, &self.id, self.span)
+    }
+}
+
+impl Declare for ProblemDecl<'_> {
+    fn declarations(&self) -> Vec<Declaration> {
+        declaration(DeclKind::Problem, &self.id, self.span)
+    }
+}
+
+impl Declare for Include {
+    fn includes(&self) -> Vec<ModulePath> {
+        if self.is_poisoned {
+            return vec![];
+        }
+
+        vec![ModulePath {
+            segments: self.path.iter().map(|p| p.to_id()).collect(),
+            span: self.span,
+        }]
+    }
+}
+
+impl Declare for Program<'_> {
+    fn declarations(&self) -> Vec<Declaration> {
+        self.items.iter().flat_map(|i| i.declarations()).collect()
+    }
+
+    fn includes(&self) -> Vec<ModulePath> {
+        self.items.iter().flat_map(|i| i.includes()).collect()
+    }
+}
+
+impl Declare for RValue {}
+impl Declare for GenericTupleOption {}
+impl Declare for BinaryExpr<'_> {}
+impl Declare for UnaryExpr<'_> {}
+impl Declare for Range<'_> {}
+impl Declare for RangeUnion<'_> {}
+impl Declare for TupleIterator<'_> {}
+impl Declare for FuncCall<'_> {}
+impl Declare for ObjectCall {}
+impl Declare for PoisonedStmt {}
+impl Declare for VarDef<'_> {}
+impl Declare for GenericTuple<'_> {}
+impl Declare for Tuple {}
+impl Declare for TupleComprehension<'_> {}
+impl Declare for Shape<'_> {}
+impl Declare for ObjectShape<'_> {}
+impl Declare for ObjectColor {}
+impl Declare for ObjectDesc<'_> {}
+impl Declare for ProblemExample<'_> {}
+impl Declare for ProblemSolution<'_> {}
+impl Declare for ProblemInput<'_> {}
+impl Declare for ProblemOutput<'_> {}
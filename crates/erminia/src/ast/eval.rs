@@ -0,0 +1,355 @@
+use crate::ast::expr::*;
+use crate::ast::stmt::*;
+use crate::ast::visit::NodeRef;
+use crate::types::ErminiaType;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Values bound to the comprehension variables (`x <- ...`) while a shape is evaluated.
+pub type Env = HashMap<String, i32>;
+
+/// The cells a shape covers, as `(x, y)`.
+pub type Cells = BTreeSet<(i32, i32)>;
+
+/// How many values of its ranges a comprehension binds its variables to before it is given
+/// up on as too large to evaluate.
+pub const MAX_BINDINGS: usize = 100_000;
+
+// ==================================================================================== //
+//  Traits                                                                              //
+// ==================================================================================== //
+
+pub trait Evaluate {
+    fn eval(&self, _env: &Env) -> Option<i32> {
+        None
+    }
+
+    fn eval_range(&self, _env: &Env) -> Option<RangeSet> {
+        None
+    }
+
+    fn eval_cells(&self, _env: &Env) -> Option<Cells> {
+        None
+    }
+}
+
+// ==================================================================================== //
+//  Range Algebra                                                                       //
+// ==================================================================================== //
+
+/// A single arithmetic progression `first, first + step, ...` that never goes past `last`.
+/// Both ends are inclusive and `step` carries the direction, so descending ranges have a
+/// negative step. A range whose `first` already lies past `last` is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntRange {
+    pub first: i32,
+    pub last: i32,
+    pub step: i32,
+}
+
+/// The union of several `IntRange`s, as written with `U` in the source.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RangeSet {
+    pub ranges: Vec<IntRange>,
+}
+
+impl IntRange {
+    pub const EMPTY: IntRange = IntRange {
+        first: 1,
+        last: 0,
+        step: 1,
+    };
+
+    /// Builds the range for `[left..right by step]`, where the inclusivity flags tell
+    /// whether `left` and `right` themselves belong to it. Ranges run downwards when
+    /// `left > right`. `step` is a magnitude and has to be positive.
+    pub fn new(
+        left: i32,
+        right: i32,
+        left_inclusive: bool,
+        right_inclusive: bool,
+        step: i32,
+    ) -> Option<Self> {
+        if step <= 0 {
+            return None;
+        }
+
+        let direction = if left <= right { 1 } else { -1 };
+
+        // A left out left end starts the range one step further in. Either end can only be
+        // moved past the limits of `i32` when no value is left between them, which leaves
+        // the range empty
+        let first = if left_inclusive {
+            Some(left)
+        } else {
+            step.checked_mul(direction)
+                .and_then(|delta| left.checked_add(delta))
+        };
+
+        let last = if right_inclusive {
+            Some(right)
+        } else {
+            right.checked_sub(direction)
+        };
+
+        match (first, last) {
+            (Some(first), Some(last)) => Some(IntRange {
+                first,
+                last,
+                step: step * direction,
+            }),
+            _ => Some(IntRange::EMPTY),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let distance = (self.last as i64 - self.first as i64) * self.step.signum() as i64;
+
+        if distance < 0 {
+            return 0;
+        }
+
+        (distance / self.step.abs() as i64) as usize + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, value: i32) -> bool {
+        let offset = (value as i64 - self.first as i64) * self.step.signum() as i64;
+
+        offset >= 0
+            && offset % self.step.abs() as i64 == 0
+            && (offset / self.step.abs() as i64) < self.len() as i64
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i32> + '_ {
+        (0..self.len()).map(move |i| (self.first as i64 + i as i64 * self.step as i64) as i32)
+    }
+}
+
+impl RangeSet {
+    pub fn new(ranges: Vec<IntRange>) -> Self {
+        RangeSet { ranges }
+    }
+
+    pub fn union(mut self, other: RangeSet) -> Self {
+        self.ranges.extend(other.ranges);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().all(|r| r.is_empty())
+    }
+
+    pub fn contains(&self, value: i32) -> bool {
+        self.ranges.iter().any(|r| r.contains(value))
+    }
+
+    /// Every value of the union once, in the order the ranges were written. Values are
+    /// only worked out as they are asked for, so huge ranges can be given up on early.
+    pub fn iter(&self) -> impl Iterator<Item = i32> + '_ {
+        let mut seen: HashSet<i32> = HashSet::new();
+
+        self.ranges
+            .iter()
+            .flat_map(IntRange::iter)
+            .filter(move |value| seen.insert(*value))
+    }
+
+    pub fn values(&self) -> Vec<i32> {
+        self.iter().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+// ==================================================================================== //
+//  Implementations                                                                     //
+// ==================================================================================== //
+
+impl Evaluate for RValue {
+    fn eval(&self, env: &Env) -> Option<i32> {
+        match &self.value {
+            RValueKind::Int(i) => Some(*i),
+            RValueKind::Id(id) => env.get(id).copied(),
+        }
+    }
+}
+
+impl Evaluate for GenericTupleOption {
+    fn eval(&self, env: &Env) -> Option<i32> {
+        match self {
+            GenericTupleOption::Int(i) => Some(*i),
+            GenericTupleOption::Id(id) => env.get(id).copied(),
+            GenericTupleOption::None | GenericTupleOption::Poisoned => None,
+        }
+    }
+}
+
+impl Evaluate for BinaryExpr<'_> {
+    fn eval(&self, env: &Env) -> Option<i32> {
+        let left = self.left.eval(env)?;
+        let right = self.right.eval(env)?;
+
+        match self.op {
+            BinaryOp::Add => left.checked_add(right),
+            BinaryOp::Sub => left.checked_sub(right),
+            BinaryOp::Mul => left.checked_mul(right),
+            BinaryOp::Div => left.checked_div(right),
+            BinaryOp::FlatDiv => left.checked_div_euclid(right),
+            BinaryOp::Mod => left.checked_rem_euclid(right),
+            BinaryOp::Eq => Some((left == right) as i32),
+            BinaryOp::NotEq => Some((left != right) as i32),
+            BinaryOp::Lt => Some((left < right) as i32),
+            BinaryOp::Gt => Some((left > right) as i32),
+            BinaryOp::LtEq => Some((left <= right) as i32),
+            BinaryOp::GtEq => Some((left >= right) as i32),
+            BinaryOp::And => Some((left != 0 && right != 0) as i32),
+            BinaryOp::Or => Some((left != 0 || right != 0) as i32),
+        }
+    }
+}
+
+impl Evaluate for UnaryExpr<'_> {
+    fn eval(&self, env: &Env) -> Option<i32> {
+        let operand = self.operand.eval(env)?;
+
+        match self.op {
+            UnaryOp::Neg => operand.checked_neg(),
+            UnaryOp::Not => Some((operand == 0) as i32),
+        }
+    }
+}
+
+impl Evaluate for Range<'_> {
+    fn eval_range(&self, env: &Env) -> Option<RangeSet> {
+        let left_inclusive = match self.left_inclusive {
+            ErminiaType::Bool(b) => b,
+            _ => return None,
+        };
+
+        let right_inclusive = match self.right_inclusive {
+            ErminiaType::Bool(b) => b,
+            _ => return None,
+        };
+
+        let step = match &self.step {
+            Some(step) => step.eval(env)?,
+            None => 1,
+        };
+
+        let range = IntRange::new(
+            self.left.eval(env)?,
+            self.right.eval(env)?,
+            left_inclusive,
+            right_inclusive,
+            step,
+        )?;
+
+        Some(RangeSet::new(vec![range]))
+    }
+}
+
+impl Evaluate for RangeUnion<'_> {
+    fn eval_range(&self, env: &Env) -> Option<RangeSet> {
+        let mut set = RangeSet::default();
+
+        for range in &self.ranges {
+            set = set.union(range.eval_range(env)?);
+        }
+
+        Some(set)
+    }
+}
+
+impl Evaluate for TupleIterator<'_> {
+    fn eval_range(&self, env: &Env) -> Option<RangeSet> {
+        self.range.eval_range(env)
+    }
+}
+
+impl Evaluate for Tuple {
+    fn eval_cells(&self, _env: &Env) -> Option<Cells> {
+        match (&self.left, &self.right) {
+            (ErminiaType::Integer(x), ErminiaType::Integer(y)) => Some(Cells::from([(*x, *y)])),
+            _ => None,
+        }
+    }
+}
+
+impl Evaluate for GenericTuple<'_> {
+    fn eval_cells(&self, env: &Env) -> Option<Cells> {
+        Some(Cells::from([(self.left.eval(env)?, self.right.eval(env)?)]))
+    }
+}
+
+impl Evaluate for TupleComprehension<'_> {
+    fn eval_cells(&self, env: &Env) -> Option<Cells> {
+        let mut cells = Cells::new();
+        let mut budget = MAX_BINDINGS;
+
+        self.bind(0, env, &mut cells, &mut budget)?;
+
+        Some(cells)
+    }
+}
+
+impl TupleComprehension<'_> {
+    // Binds the generators from `index` on in every way, adding the tuple of each binding
+    // that all predicates hold for
+    fn bind(&self, index: usize, env: &Env, cells: &mut Cells, budget: &mut usize) -> Option<()> {
+        let Some(generator) = self.generators.get(index) else {
+            *budget = budget.checked_sub(1)?;
+
+            for predicate in &self.predicates {
+                if predicate.eval(env)? == 0 {
+                    return Some(());
+                }
+            }
+
+            cells.extend(self.tuple.eval_cells(env)?);
+
+            return Some(());
+        };
+
+        let NodeRef::TupleIterator(iterator) = generator.as_node() else {
+            return None;
+        };
+
+        let ErminiaType::Ident(name) = &iterator.id else {
+            return None;
+        };
+
+        for value in generator.eval_range(env)?.iter() {
+            *budget = budget.checked_sub(1)?;
+
+            let mut inner = env.clone();
+            inner.insert(name.clone(), value);
+
+            self.bind(index + 1, &inner, cells, budget)?;
+        }
+
+        Some(())
+    }
+}
+
+impl Evaluate for FuncCall<'_> {}
+impl Evaluate for ObjectCall {}
+impl Evaluate for PoisonedStmt {}
+impl Evaluate for VarDef<'_> {}
+impl Evaluate for Shape<'_> {}
+impl Evaluate for ObjectShape<'_> {}
+impl Evaluate for ObjectColor {}
+impl Evaluate for ObjectDesc<'_> {}
+impl Evaluate for ObjectDecl<'_> {}
+impl Evaluate for ProblemExample<'_> {}
+impl Evaluate for ProblemSolution<'_> {}
+impl Evaluate for ProblemInput<'_> {}
+impl Evaluate for ProblemOutput<'_> {}
+impl Evaluate for FuncDecl<'_> {}
+impl Evaluate for ProblemDecl<'_> {}
+impl Evaluate for Include {}
+impl Evaluate for Program<'_> {}
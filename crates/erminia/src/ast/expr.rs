@@ -1,6 +1,9 @@
 use crate::ast::ast::{ASTError, BoxAST, AST};
+use crate::ast::node::NodeId;
 use crate::diagnostics::location::Span;
+use crate::lexer::token::{SyntaxToken, TokenKind};
 use crate::types::ErminiaType;
+use std::fmt;
 
 pub type BoxExpr<'a> = Box<dyn ExprTrait<'a> + 'a>;
 
@@ -12,6 +15,34 @@ pub trait ExprTrait<'a>: AST<'a> {
     fn eval(&self) -> Result<u32, ASTError>;
 }
 
+// ==================================================================================== //
+//  Enums                                                                               //
+// ==================================================================================== //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FlatDiv,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
 // ==================================================================================== //
 //  Structs                                                                             //
 // ==================================================================================== //
@@ -21,37 +52,226 @@ pub struct FuncCall<'a> {
     pub exprs: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
-pub struct ObjectCall<'a> {
+pub struct ObjectCall {
     pub id: ErminiaType,
-    pub tuple: Option<BoxAST<'a>>,
+    pub offset_x: ErminiaType,
+    pub offset_y: ErminiaType,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
-#[derive(Debug)]
-pub enum RValue {
+pub struct BinaryExpr<'a> {
+    pub op: BinaryOp,
+    pub left: BoxAST<'a>,
+    pub right: BoxAST<'a>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+pub struct UnaryExpr<'a> {
+    pub op: UnaryOp,
+    pub operand: BoxAST<'a>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RValueKind {
     Int(i32),
     Id(String),
 }
 
+#[derive(Debug)]
+pub struct RValue {
+    pub value: RValueKind,
+    pub span: Span,
+    pub unique_ast_id: NodeId,
+}
+
 // ==================================================================================== //
 //  Implementations                                                                     //
 // ==================================================================================== //
 
+impl BinaryOp {
+    pub const ALL: [BinaryOp; 14] = [
+        BinaryOp::Add,
+        BinaryOp::Sub,
+        BinaryOp::Mul,
+        BinaryOp::Div,
+        BinaryOp::FlatDiv,
+        BinaryOp::Mod,
+        BinaryOp::Eq,
+        BinaryOp::NotEq,
+        BinaryOp::Lt,
+        BinaryOp::Gt,
+        BinaryOp::LtEq,
+        BinaryOp::GtEq,
+        BinaryOp::And,
+        BinaryOp::Or,
+    ];
+
+    pub fn from_kind(kind: TokenKind) -> Option<Self> {
+        let op = match kind {
+            TokenKind::Plus => BinaryOp::Add,
+            TokenKind::Minus => BinaryOp::Sub,
+            TokenKind::Multi => BinaryOp::Mul,
+            TokenKind::Div => BinaryOp::Div,
+            TokenKind::FlatDiv => BinaryOp::FlatDiv,
+            TokenKind::Mod => BinaryOp::Mod,
+            TokenKind::DoubleEquals => BinaryOp::Eq,
+            TokenKind::NotEquals => BinaryOp::NotEq,
+            TokenKind::Lesser => BinaryOp::Lt,
+            TokenKind::Greater => BinaryOp::Gt,
+            TokenKind::LesserEquals => BinaryOp::LtEq,
+            TokenKind::GreaterEquals => BinaryOp::GtEq,
+            TokenKind::And => BinaryOp::And,
+            TokenKind::Or => BinaryOp::Or,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    pub fn to_kind(&self) -> TokenKind {
+        match self {
+            BinaryOp::Add => TokenKind::Plus,
+            BinaryOp::Sub => TokenKind::Minus,
+            BinaryOp::Mul => TokenKind::Multi,
+            BinaryOp::Div => TokenKind::Div,
+            BinaryOp::FlatDiv => TokenKind::FlatDiv,
+            BinaryOp::Mod => TokenKind::Mod,
+            BinaryOp::Eq => TokenKind::DoubleEquals,
+            BinaryOp::NotEq => TokenKind::NotEquals,
+            BinaryOp::Lt => TokenKind::Lesser,
+            BinaryOp::Gt => TokenKind::Greater,
+            BinaryOp::LtEq => TokenKind::LesserEquals,
+            BinaryOp::GtEq => TokenKind::GreaterEquals,
+            BinaryOp::And => TokenKind::And,
+            BinaryOp::Or => TokenKind::Or,
+        }
+    }
+
+    /// Binding power used by the precedence climbing in `parse_binary_expr`. Higher binds
+    /// tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq | BinaryOp::NotEq => 3,
+            BinaryOp::Lt | BinaryOp::Gt | BinaryOp::LtEq | BinaryOp::GtEq => 4,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::FlatDiv | BinaryOp::Mod => 6,
+        }
+    }
+}
+
+impl UnaryOp {
+    pub fn from_kind(kind: TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::Minus => Some(UnaryOp::Neg),
+            TokenKind::Not => Some(UnaryOp::Not),
+            _ => None,
+        }
+    }
+
+    pub fn to_kind(&self) -> TokenKind {
+        match self {
+            UnaryOp::Neg => TokenKind::Minus,
+            UnaryOp::Not => TokenKind::Not,
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_kind(), f)
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_kind(), f)
+    }
+}
+
+impl<'a> BinaryExpr<'a> {
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        op: BinaryOp,
+        left: BoxAST<'a>,
+        right: BoxAST<'a>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
+
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if left.is_err() || right.is_err() {
+            is_poisoned = true;
+        }
+
+        Box::new(BinaryExpr {
+            op,
+            left,
+            right,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
+impl<'a> UnaryExpr<'a> {
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        op: UnaryOp,
+        operand: BoxAST<'a>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
+
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if operand.is_err() {
+            is_poisoned = true;
+        }
+
+        Box::new(UnaryExpr {
+            op,
+            operand,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
 impl<'a> FuncCall<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         exprs: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -77,24 +297,23 @@ impl<'a> FuncCall<'a> {
     }
 }
 
-impl<'a> ObjectCall<'a> {
+impl<'a> ObjectCall {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
-        tuple: Option<BoxAST<'a>>,
+        offset_x: ErminiaType,
+        offset_y: ErminiaType,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
             is_poisoned = true;
         }
 
-        if let Some(t) = &tuple {
-            if t.is_err() {
-                is_poisoned = true;
-            }
+        if offset_x.is_poisoned() || offset_y.is_poisoned() {
+            is_poisoned = true;
         }
 
         if id.is_poisoned() {
@@ -103,21 +322,38 @@ impl<'a> ObjectCall<'a> {
 
         Box::new(ObjectCall {
             id,
-            tuple,
+            offset_x,
+            offset_y,
             span,
             is_poisoned,
             unique_ast_id,
             syntax,
         }) as BoxAST<'a>
     }
+
+    /// The span of the name called, which the parser puts first, or of the whole call.
+    pub fn name_span(&self) -> Span {
+        self.syntax
+            .first()
+            .filter(|s| s.kind == TokenKind::Ident)
+            .map_or(self.span, |s| s.span)
+    }
 }
 
 impl<'a> RValue {
-    pub fn boxed_int(value: i32) -> BoxAST<'a> {
-        Box::new(RValue::Int(value))
+    pub fn boxed_int(unique_ast_id: NodeId, value: i32, span: Span) -> BoxAST<'a> {
+        Box::new(RValue {
+            value: RValueKind::Int(value),
+            span,
+            unique_ast_id,
+        })
     }
 
-    pub fn boxed_id(name: String) -> BoxAST<'a> {
-        Box::new(RValue::Id(name))
+    pub fn boxed_id(unique_ast_id: NodeId, name: String, span: Span) -> BoxAST<'a> {
+        Box::new(RValue {
+            value: RValueKind::Id(name),
+            span,
+            unique_ast_id,
+        })
     }
 }
@@ -1,5 +1,9 @@
 #[allow(clippy::module_inception)]
 pub mod ast;
+pub mod decl;
+pub mod eval;
 pub mod expr;
+pub mod node;
 pub mod printon;
 pub mod stmt;
+pub mod visit;
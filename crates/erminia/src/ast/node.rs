@@ -0,0 +1,136 @@
+use crate::ast::ast::{BoxAST, AST};
+use crate::diagnostics::Span;
+
+use derive_more::Display;
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+/// Identifies a node within the tree it was parsed into. Ids are handed out while parsing,
+/// children before their parents, starting at 1. Plain values such as identifiers and
+/// integer constants are not nodes and keep the unassigned id 0.
+#[derive(Default, Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub u32);
+
+#[derive(Default, Debug, Clone)]
+pub struct NodeIdAllocator {
+    next: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeEntry {
+    /// What kind of node it is, e.g. `VarDef`.
+    pub name: String,
+    pub span: Span,
+    pub parent: Option<NodeId>,
+}
+
+/// Every node of a tree indexed by its id, together with its parent. Built once per parsed
+/// file, for the passes after parsing to look spans and parents up in rather than walk the
+/// tree for them.
+#[derive(Debug, Default, Clone)]
+pub struct NodeTable {
+    entries: Vec<Option<NodeEntry>>,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl NodeId {
+    pub const UNASSIGNED: NodeId = NodeId(0);
+
+    pub fn is_assigned(&self) -> bool {
+        *self != NodeId::UNASSIGNED
+    }
+}
+
+impl NodeIdAllocator {
+    pub fn new() -> Self {
+        NodeIdAllocator::default()
+    }
+
+    pub fn fresh(&mut self) -> NodeId {
+        self.next += 1;
+        NodeId(self.next)
+    }
+}
+
+impl NodeTable {
+    pub fn build<'a>(root: &BoxAST<'a>) -> Self {
+        let mut table = NodeTable::default();
+
+        table.insert(root.as_ref(), None);
+
+        table
+    }
+
+    fn insert<'a>(&mut self, node: &(dyn AST<'a> + 'a), parent: Option<NodeId>) {
+        let id = node.get_ast_id();
+
+        // Unassigned nodes are skipped, their children hang off the closest assigned node
+        let parent_of_children = if id.is_assigned() {
+            let index = id.0 as usize;
+
+            if self.entries.len() <= index {
+                self.entries.resize_with(index + 1, || None);
+            }
+
+            self.entries[index] = Some(NodeEntry {
+                name: node.to_string(),
+                span: node.get_span(),
+                parent,
+            });
+
+            Some(id)
+        } else {
+            parent
+        };
+
+        for child in node.children() {
+            self.insert(child.as_ref(), parent_of_children);
+        }
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&NodeEntry> {
+        self.entries.get(id.0 as usize).and_then(|e| e.as_ref())
+    }
+
+    pub fn get_name(&self, id: NodeId) -> Option<&str> {
+        self.get(id).map(|e| e.name.as_str())
+    }
+
+    pub fn get_span(&self, id: NodeId) -> Option<Span> {
+        self.get(id).map(|e| e.span)
+    }
+
+    pub fn get_parent(&self, id: NodeId) -> Option<NodeId> {
+        self.get(id).and_then(|e| e.parent)
+    }
+
+    /// The nodes whose parent is `id`, in source order.
+    pub fn get_children(&self, id: NodeId) -> Vec<NodeId> {
+        self.ids()
+            .into_iter()
+            .filter(|c| self.get_parent(*c) == Some(id))
+            .collect()
+    }
+
+    pub fn ids(&self) -> Vec<NodeId> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_some())
+            .map(|(i, _)| NodeId(i as u32))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -112,14 +112,44 @@ impl PrettyPrinting for ProblemOutput<'_> {
     }
 }
 
-impl PrettyPrinting for Program<'_> {
+impl PrettyPrinting for FuncDecl<'_> {
+    fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
+        todo!("to_json not implemented yet");
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| format!("{} {}", p.data_type, p.id))
+            .collect();
+        let s = format!(
+            "<#{} FuncDecl id: {:?}, params: [{}], return_type: {}>",
+            self.unique_ast_id,
+            self.id,
+            params.join(", "),
+            self.return_type
+        );
+        writeln!(opts.f, "{}", s)?;
+        for stmt in &self.stmts {
+            stmt.print_on(opts)?;
+        }
+        Ok(())
+    }
+}
+
+impl PrettyPrinting for ProblemDecl<'_> {
     fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
         todo!("to_json not implemented yet");
     }
 
     fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
         let s = format!(
-            "<#{} Program id: {:?}, int_const: {}>",
+            "<#{} ProblemDecl id: {:?}, int_const: {}>",
             self.unique_ast_id, self.id, self.int_const
         );
         writeln!(opts.f, "{}", s)?;
@@ -130,7 +160,41 @@ impl PrettyPrinting for Program<'_> {
     }
 }
 
-impl PrettyPrinting for Range {
+impl PrettyPrinting for Include {
+    fn to_json(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        let path: Vec<String> = self.path.iter().map(|p| p.to_id()).collect();
+        write!(opts.f, "{}", serde_json::json!({ "include": path }))
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
+        let path: Vec<String> = self.path.iter().map(|p| p.to_id()).collect();
+        let s = format!(
+            "<#{} Include path: {}>",
+            self.unique_ast_id,
+            path.join("::")
+        );
+        writeln!(opts.f, "{}", s)
+    }
+}
+
+impl PrettyPrinting for Program<'_> {
+    fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
+        todo!("to_json not implemented yet");
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        let s = format!("<#{} Program>", self.unique_ast_id);
+        writeln!(opts.f, "{}", s)?;
+        for item in &self.items {
+            item.print_on(opts)?;
+        }
+        Ok(())
+    }
+}
+
+impl PrettyPrinting for Range<'_> {
     fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
         todo!("to_json not implemented yet");
     }
@@ -139,10 +203,33 @@ impl PrettyPrinting for Range {
         opts.depth += 1;
         print_tabs(opts)?;
         let s = format!(
-            "<#{} Range left_inclusive: {}, right_inclusive: {}, left: {}, right: {}>",
-            self.unique_ast_id, self.left_inclusive, self.right_inclusive, self.left, self.right
+            "<#{} Range left_inclusive: {}, right_inclusive: {}>",
+            self.unique_ast_id, self.left_inclusive, self.right_inclusive
         );
         writeln!(opts.f, "{}", s)?;
+        let _ = &self.left.print_on(opts)?;
+        let _ = &self.right.print_on(opts)?;
+        if let Some(s) = &self.step {
+            s.print_on(opts)?;
+        };
+        Ok(())
+    }
+}
+
+impl PrettyPrinting for RangeUnion<'_> {
+    // Ranges have no JSON form yet, so neither has their union
+    fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
+        let s = format!("<#{} RangeUnion>", self.unique_ast_id);
+        writeln!(opts.f, "{}", s)?;
+        for range in &self.ranges {
+            range.print_on(opts)?;
+        }
         Ok(())
     }
 }
@@ -173,8 +260,11 @@ impl PrettyPrinting for TupleComprehension<'_> {
         let s = format!("<#{} TupleComprehension>", self.unique_ast_id);
         writeln!(opts.f, "{}", s)?;
         let _ = &self.tuple.print_on(opts)?;
-        for iter in &self.iter_pair {
-            iter.print_on(opts)?;
+        for generator in &self.generators {
+            generator.print_on(opts)?;
+        }
+        for predicate in &self.predicates {
+            predicate.print_on(opts)?;
         }
         Ok(())
     }
@@ -330,7 +420,7 @@ impl PrettyPrinting for FuncCall<'_> {
     }
 }
 
-impl PrettyPrinting for ObjectCall<'_> {
+impl PrettyPrinting for ObjectCall {
     fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
         todo!("to_json not implemented yet");
     }
@@ -338,11 +428,44 @@ impl PrettyPrinting for ObjectCall<'_> {
     fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
         opts.depth += 1;
         print_tabs(opts)?;
-        let s = format!("<#{} ObjectCall id: {:?}>", self.unique_ast_id, self.id);
+        let s = format!(
+            "<#{} ObjectCall id: {:?}, offset_x: {}, offset_y: {}>",
+            self.unique_ast_id, self.id, self.offset_x, self.offset_y
+        );
         writeln!(opts.f, "{}", s)?;
-        if let Some(t) = &self.tuple {
-            t.print_on(opts)?;
-        };
+        Ok(())
+    }
+}
+
+impl PrettyPrinting for BinaryExpr<'_> {
+    // Operands have no JSON form yet, so neither has the expression
+    fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
+        let s = format!("<#{} BinaryExpr op: {}>", self.unique_ast_id, self.op);
+        writeln!(opts.f, "{}", s)?;
+        let _ = &self.left.print_on(opts)?;
+        let _ = &self.right.print_on(opts)?;
+        Ok(())
+    }
+}
+
+impl PrettyPrinting for UnaryExpr<'_> {
+    // Operands have no JSON form yet, so neither has the expression
+    fn to_json(&self, _: &mut PrintOpt) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+
+    fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
+        opts.depth += 1;
+        print_tabs(opts)?;
+        let s = format!("<#{} UnaryExpr op: {}>", self.unique_ast_id, self.op);
+        writeln!(opts.f, "{}", s)?;
+        let _ = &self.operand.print_on(opts)?;
         Ok(())
     }
 }
@@ -355,7 +478,7 @@ impl PrettyPrinting for RValue {
     fn print_on(&self, opts: &mut PrintOpt) -> std::fmt::Result {
         opts.depth += 1;
         print_tabs(opts)?;
-        let s = format!("<RValue {:?}>", self);
+        let s = format!("<RValue {:?}>", self.value);
         writeln!(opts.f, "{}", s)?;
         Ok(())
     }
@@ -1,5 +1,7 @@
 use crate::ast::ast::{ASTError, BoxAST, AST};
+use crate::ast::node::NodeId;
 use crate::diagnostics::location::Span;
+use crate::lexer::token::{SyntaxToken, TokenKind};
 use crate::types::ErminiaType;
 
 pub type BoxStmt<'a> = Box<dyn StmtTrait<'a> + 'a>;
@@ -39,7 +41,7 @@ pub enum ShapeType {
 pub struct PoisonedStmt {
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
+    pub unique_ast_id: NodeId,
 }
 
 #[derive(Debug)]
@@ -49,8 +51,8 @@ pub struct VarDef<'a> {
     pub expr: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -59,8 +61,8 @@ pub struct GenericTuple<'a> {
     pub right: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -69,20 +71,30 @@ pub struct Tuple {
     pub right: ErminiaType,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
-pub struct Range {
+pub struct Range<'a> {
     pub left_inclusive: ErminiaType,
     pub right_inclusive: ErminiaType,
-    pub left: ErminiaType,
-    pub right: ErminiaType,
+    pub left: BoxAST<'a>,
+    pub right: BoxAST<'a>,
+    pub step: Option<BoxAST<'a>>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+#[derive(Debug)]
+pub struct RangeUnion<'a> {
+    pub ranges: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -91,18 +103,19 @@ pub struct TupleIterator<'a> {
     pub range: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
 pub struct TupleComprehension<'a> {
     pub tuple: BoxAST<'a>,
-    pub iter_pair: Vec<BoxAST<'a>>,
+    pub generators: Vec<BoxAST<'a>>,
+    pub predicates: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -111,8 +124,8 @@ pub struct Shape<'a> {
     pub values: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -120,8 +133,8 @@ pub struct ObjectShape<'a> {
     pub shape: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -129,8 +142,8 @@ pub struct ObjectColor {
     pub color: ErminiaType,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -139,8 +152,8 @@ pub struct ObjectDesc<'a> {
     pub color: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -149,8 +162,8 @@ pub struct ObjectDecl<'a> {
     pub desc: BoxAST<'a>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -160,8 +173,8 @@ pub struct ProblemExample<'a> {
     pub stmts: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -171,8 +184,8 @@ pub struct ProblemSolution<'a> {
     pub stmts: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -182,8 +195,8 @@ pub struct ProblemInput<'a> {
     pub stmts: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
@@ -193,19 +206,55 @@ pub struct ProblemOutput<'a> {
     pub stmts: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 #[derive(Debug)]
-pub struct Program<'a> {
+pub struct FuncParam {
+    pub data_type: ErminiaType,
+    pub id: ErminiaType,
+}
+
+#[derive(Debug)]
+pub struct FuncDecl<'a> {
+    pub id: ErminiaType,
+    pub params: Vec<FuncParam>,
+    pub return_type: ErminiaType,
+    pub stmts: Vec<BoxAST<'a>>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+#[derive(Debug)]
+pub struct ProblemDecl<'a> {
     pub id: ErminiaType,
     pub int_const: ErminiaType,
     pub stmts: Vec<BoxAST<'a>>,
     pub span: Span,
     pub is_poisoned: bool,
-    pub unique_ast_id: u32,
-    pub syntax: Vec<ErminiaType>,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+#[derive(Debug)]
+pub struct Include {
+    pub path: Vec<ErminiaType>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
+}
+
+#[derive(Debug)]
+pub struct Program<'a> {
+    pub items: Vec<BoxAST<'a>>,
+    pub span: Span,
+    pub is_poisoned: bool,
+    pub unique_ast_id: NodeId,
+    pub syntax: Vec<SyntaxToken>,
 }
 
 // ==================================================================================== //
@@ -236,16 +285,18 @@ impl<'a> GenericTupleOption {
     }
 }
 
-impl<'a> Range {
+impl<'a> Range<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn boxed(
+        unique_ast_id: NodeId,
         left_inclusive: ErminiaType,
         right_inclusive: ErminiaType,
-        left: ErminiaType,
-        right: ErminiaType,
+        left: BoxAST<'a>,
+        right: BoxAST<'a>,
+        step: Option<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -254,17 +305,24 @@ impl<'a> Range {
 
         if left_inclusive.is_poisoned()
             || right_inclusive.is_poisoned()
-            || left.is_poisoned()
-            || right.is_poisoned()
+            || left.is_err()
+            || right.is_err()
         {
             is_poisoned = true;
         }
 
+        if let Some(s) = &step {
+            if s.is_err() {
+                is_poisoned = true;
+            }
+        }
+
         Box::new(Range {
             left_inclusive,
             right_inclusive,
             left,
             right,
+            step,
             span,
             is_poisoned,
             unique_ast_id,
@@ -273,14 +331,41 @@ impl<'a> Range {
     }
 }
 
+impl<'a> RangeUnion<'a> {
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        ranges: Vec<BoxAST<'a>>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
+
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if ranges.iter().any(|r| r.is_err()) {
+            is_poisoned = true;
+        }
+
+        Box::new(RangeUnion {
+            ranges,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
 impl<'a> TupleIterator<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         range: BoxAST<'a>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -304,15 +389,20 @@ impl<'a> TupleIterator<'a> {
 
 impl<'a> TupleComprehension<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         tuple: BoxAST<'a>,
-        iter_pair: Vec<BoxAST<'a>>,
+        generators: Vec<BoxAST<'a>>,
+        predicates: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
-        if iter_pair.iter().any(|s| s.is_err()) {
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if generators.iter().any(|s| s.is_err()) || predicates.iter().any(|s| s.is_err()) {
             is_poisoned = true;
         }
 
@@ -322,7 +412,8 @@ impl<'a> TupleComprehension<'a> {
 
         Box::new(TupleComprehension {
             tuple,
-            iter_pair,
+            generators,
+            predicates,
             span,
             is_poisoned,
             unique_ast_id,
@@ -333,12 +424,12 @@ impl<'a> TupleComprehension<'a> {
 
 impl<'a> GenericTuple<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         left: BoxAST<'a>,
         right: BoxAST<'a>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -362,12 +453,12 @@ impl<'a> GenericTuple<'a> {
 
 impl<'a> Tuple {
     pub fn boxed(
+        unique_ast_id: NodeId,
         left: ErminiaType,
         right: ErminiaType,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -391,13 +482,13 @@ impl<'a> Tuple {
 
 impl<'a> VarDef<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         data_type: ErminiaType,
         expr: BoxAST<'a>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -421,9 +512,7 @@ impl<'a> VarDef<'a> {
 }
 
 impl<'a> Shape<'a> {
-    pub fn boxed_none(span: Span, syntax: Vec<ErminiaType>) -> BoxAST<'a> {
-        let unique_ast_id = 0;
-
+    pub fn boxed_none(unique_ast_id: NodeId, span: Span, syntax: Vec<SyntaxToken>) -> BoxAST<'a> {
         Box::new(Shape {
             shape_type: ShapeType::ShapeTuple,
             values: GenericTupleOption::boxed_none(),
@@ -436,8 +525,12 @@ impl<'a> Shape<'a> {
 }
 
 impl<'a> ObjectShape<'a> {
-    pub fn boxed(shape: Vec<BoxAST<'a>>, span: Span, syntax: Vec<ErminiaType>) -> BoxAST<'a> {
-        let unique_ast_id = 0;
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        shape: Vec<BoxAST<'a>>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -459,8 +552,12 @@ impl<'a> ObjectShape<'a> {
 }
 
 impl<'a> ObjectColor {
-    pub fn boxed(color: ErminiaType, span: Span, syntax: Vec<ErminiaType>) -> BoxAST<'a> {
-        let unique_ast_id = 0;
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        color: ErminiaType,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -483,12 +580,12 @@ impl<'a> ObjectColor {
 
 impl<'a> ObjectDesc<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         shape: BoxAST<'a>,
         color: BoxAST<'a>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -512,12 +609,12 @@ impl<'a> ObjectDesc<'a> {
 
 impl<'a> ObjectDecl<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         desc: BoxAST<'a>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -537,17 +634,25 @@ impl<'a> ObjectDecl<'a> {
             syntax,
         }) as BoxAST<'a>
     }
+
+    /// The span of the declared name, or of the whole declaration if it has none.
+    pub fn name_span(&self) -> Span {
+        self.syntax
+            .iter()
+            .find(|s| s.kind == TokenKind::Ident)
+            .map_or(self.span, |s| s.span)
+    }
 }
 
 impl<'a> ProblemExample<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         int_const: ErminiaType,
         stmts: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -572,13 +677,13 @@ impl<'a> ProblemExample<'a> {
 
 impl<'a> ProblemSolution<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         int_const: ErminiaType,
         stmts: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -603,13 +708,13 @@ impl<'a> ProblemSolution<'a> {
 
 impl<'a> ProblemInput<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         tuple: BoxAST<'a>,
         stmts: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -634,13 +739,13 @@ impl<'a> ProblemInput<'a> {
 
 impl<'a> ProblemOutput<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         tuple: BoxAST<'a>,
         stmts: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -663,15 +768,58 @@ impl<'a> ProblemOutput<'a> {
     }
 }
 
-impl<'a> Program<'a> {
+impl FuncParam {
+    pub fn is_poisoned(&self) -> bool {
+        self.data_type.is_poisoned() || self.id.is_poisoned()
+    }
+}
+
+impl<'a> FuncDecl<'a> {
     pub fn boxed(
+        unique_ast_id: NodeId,
+        id: ErminiaType,
+        params: Vec<FuncParam>,
+        return_type: ErminiaType,
+        stmts: Vec<BoxAST<'a>>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
+
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if stmts.iter().any(|s| s.is_err())
+            || params.iter().any(|p| p.is_poisoned())
+            || id.is_poisoned()
+            || return_type.is_poisoned()
+        {
+            is_poisoned = true;
+        }
+
+        Box::new(FuncDecl {
+            id,
+            params,
+            return_type,
+            stmts,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
+impl<'a> ProblemDecl<'a> {
+    pub fn boxed(
+        unique_ast_id: NodeId,
         id: ErminiaType,
         int_const: ErminiaType,
         stmts: Vec<BoxAST<'a>>,
         span: Span,
-        syntax: Vec<ErminiaType>,
+        syntax: Vec<SyntaxToken>,
     ) -> BoxAST<'a> {
-        let unique_ast_id = 0;
         let mut is_poisoned = false;
 
         if syntax.iter().any(|s| s.is_poisoned()) {
@@ -682,7 +830,7 @@ impl<'a> Program<'a> {
             is_poisoned = true;
         }
 
-        Box::new(Program {
+        Box::new(ProblemDecl {
             id,
             int_const,
             stmts,
@@ -694,10 +842,58 @@ impl<'a> Program<'a> {
     }
 }
 
-impl<'a> PoisonedStmt {
-    pub fn boxed(span: Span) -> BoxAST<'a> {
-        let unique_ast_id = 0;
+impl<'a> Include {
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        path: Vec<ErminiaType>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
+
+        if syntax.iter().any(|s| s.is_poisoned()) || path.iter().any(|p| p.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        Box::new(Include {
+            path,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
+impl<'a> Program<'a> {
+    pub fn boxed(
+        unique_ast_id: NodeId,
+        items: Vec<BoxAST<'a>>,
+        span: Span,
+        syntax: Vec<SyntaxToken>,
+    ) -> BoxAST<'a> {
+        let mut is_poisoned = false;
 
+        if syntax.iter().any(|s| s.is_poisoned()) {
+            is_poisoned = true;
+        }
+
+        if items.iter().any(|i| i.is_err()) {
+            is_poisoned = true;
+        }
+
+        Box::new(Program {
+            items,
+            span,
+            is_poisoned,
+            unique_ast_id,
+            syntax,
+        }) as BoxAST<'a>
+    }
+}
+
+impl<'a> PoisonedStmt {
+    pub fn boxed(unique_ast_id: NodeId, span: Span) -> BoxAST<'a> {
         Box::new(PoisonedStmt {
             span,
             is_poisoned: true,
@@ -0,0 +1,894 @@
+use crate::ast::ast::BoxAST;
+use crate::ast::expr::*;
+use crate::ast::stmt::*;
+
+// ==================================================================================== //
+//  Node References                                                                     //
+// ==================================================================================== //
+
+/// A node of the parsed tree as its concrete type. Passes that match exhaustively over the
+/// kinds of node do so on this, over the one tree the parser builds.
+pub enum NodeRef<'n, 'a> {
+    Program(&'n Program<'a>),
+    Include(&'n Include),
+    ProblemDecl(&'n ProblemDecl<'a>),
+    FuncDecl(&'n FuncDecl<'a>),
+    ObjectDecl(&'n ObjectDecl<'a>),
+    ObjectDesc(&'n ObjectDesc<'a>),
+    ObjectShape(&'n ObjectShape<'a>),
+    ObjectColor(&'n ObjectColor),
+    Shape(&'n Shape<'a>),
+    Tuple(&'n Tuple),
+    GenericTuple(&'n GenericTuple<'a>),
+    GenericTupleOption(&'n GenericTupleOption),
+    Range(&'n Range<'a>),
+    RangeUnion(&'n RangeUnion<'a>),
+    TupleIterator(&'n TupleIterator<'a>),
+    TupleComprehension(&'n TupleComprehension<'a>),
+    VarDef(&'n VarDef<'a>),
+    ProblemExample(&'n ProblemExample<'a>),
+    ProblemSolution(&'n ProblemSolution<'a>),
+    ProblemInput(&'n ProblemInput<'a>),
+    ProblemOutput(&'n ProblemOutput<'a>),
+    Poisoned(&'n PoisonedStmt),
+    FuncCall(&'n FuncCall<'a>),
+    ObjectCall(&'n ObjectCall),
+    BinaryExpr(&'n BinaryExpr<'a>),
+    UnaryExpr(&'n UnaryExpr<'a>),
+    RValue(&'n RValue),
+}
+
+/// Like `NodeRef`, but mutable.
+pub enum NodeMut<'n, 'a> {
+    Program(&'n mut Program<'a>),
+    Include(&'n mut Include),
+    ProblemDecl(&'n mut ProblemDecl<'a>),
+    FuncDecl(&'n mut FuncDecl<'a>),
+    ObjectDecl(&'n mut ObjectDecl<'a>),
+    ObjectDesc(&'n mut ObjectDesc<'a>),
+    ObjectShape(&'n mut ObjectShape<'a>),
+    ObjectColor(&'n mut ObjectColor),
+    Shape(&'n mut Shape<'a>),
+    Tuple(&'n mut Tuple),
+    GenericTuple(&'n mut GenericTuple<'a>),
+    GenericTupleOption(&'n mut GenericTupleOption),
+    Range(&'n mut Range<'a>),
+    RangeUnion(&'n mut RangeUnion<'a>),
+    TupleIterator(&'n mut TupleIterator<'a>),
+    TupleComprehension(&'n mut TupleComprehension<'a>),
+    VarDef(&'n mut VarDef<'a>),
+    ProblemExample(&'n mut ProblemExample<'a>),
+    ProblemSolution(&'n mut ProblemSolution<'a>),
+    ProblemInput(&'n mut ProblemInput<'a>),
+    ProblemOutput(&'n mut ProblemOutput<'a>),
+    Poisoned(&'n mut PoisonedStmt),
+    FuncCall(&'n mut FuncCall<'a>),
+    ObjectCall(&'n mut ObjectCall),
+    BinaryExpr(&'n mut BinaryExpr<'a>),
+    UnaryExpr(&'n mut UnaryExpr<'a>),
+    RValue(&'n mut RValue),
+}
+
+/// Gives access to the concrete type behind a `BoxAST`.
+pub trait AsNode<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a>;
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a>;
+}
+
+// ==================================================================================== //
+//  Visitors                                                                            //
+// ==================================================================================== //
+
+/// Walks the tree in source order. Every `visit_*` method walks the children of its node
+/// by default, so a visitor only overrides the nodes it cares about and calls the matching
+/// `walk_*` function to keep descending. `visit_ast` is called for every node before it is
+/// dispatched on its type.
+pub trait Visitor<'a>: Sized {
+    fn visit_ast(&mut self, node: &BoxAST<'a>) {
+        walk_ast(self, node)
+    }
+
+    fn visit_program(&mut self, node: &Program<'a>) {
+        walk_program(self, node)
+    }
+
+    fn visit_include(&mut self, _node: &Include) {}
+
+    fn visit_problem_decl(&mut self, node: &ProblemDecl<'a>) {
+        walk_problem_decl(self, node)
+    }
+
+    fn visit_func_decl(&mut self, node: &FuncDecl<'a>) {
+        walk_func_decl(self, node)
+    }
+
+    fn visit_object_decl(&mut self, node: &ObjectDecl<'a>) {
+        walk_object_decl(self, node)
+    }
+
+    fn visit_object_desc(&mut self, node: &ObjectDesc<'a>) {
+        walk_object_desc(self, node)
+    }
+
+    fn visit_object_shape(&mut self, node: &ObjectShape<'a>) {
+        walk_object_shape(self, node)
+    }
+
+    fn visit_object_color(&mut self, _node: &ObjectColor) {}
+
+    fn visit_shape(&mut self, node: &Shape<'a>) {
+        walk_shape(self, node)
+    }
+
+    fn visit_tuple(&mut self, _node: &Tuple) {}
+
+    fn visit_generic_tuple(&mut self, node: &GenericTuple<'a>) {
+        walk_generic_tuple(self, node)
+    }
+
+    fn visit_generic_tuple_option(&mut self, _node: &GenericTupleOption) {}
+
+    fn visit_range(&mut self, node: &Range<'a>) {
+        walk_range(self, node)
+    }
+
+    fn visit_range_union(&mut self, node: &RangeUnion<'a>) {
+        walk_range_union(self, node)
+    }
+
+    fn visit_tuple_iterator(&mut self, node: &TupleIterator<'a>) {
+        walk_tuple_iterator(self, node)
+    }
+
+    fn visit_tuple_comprehension(&mut self, node: &TupleComprehension<'a>) {
+        walk_tuple_comprehension(self, node)
+    }
+
+    fn visit_var_def(&mut self, node: &VarDef<'a>) {
+        walk_var_def(self, node)
+    }
+
+    fn visit_problem_example(&mut self, node: &ProblemExample<'a>) {
+        walk_problem_example(self, node)
+    }
+
+    fn visit_problem_solution(&mut self, node: &ProblemSolution<'a>) {
+        walk_problem_solution(self, node)
+    }
+
+    fn visit_problem_input(&mut self, node: &ProblemInput<'a>) {
+        walk_problem_input(self, node)
+    }
+
+    fn visit_problem_output(&mut self, node: &ProblemOutput<'a>) {
+        walk_problem_output(self, node)
+    }
+
+    fn visit_poisoned(&mut self, _node: &PoisonedStmt) {}
+
+    fn visit_func_call(&mut self, node: &FuncCall<'a>) {
+        walk_func_call(self, node)
+    }
+
+    fn visit_object_call(&mut self, _node: &ObjectCall) {}
+
+    fn visit_binary_expr(&mut self, node: &BinaryExpr<'a>) {
+        walk_binary_expr(self, node)
+    }
+
+    fn visit_unary_expr(&mut self, node: &UnaryExpr<'a>) {
+        walk_unary_expr(self, node)
+    }
+
+    fn visit_rvalue(&mut self, _node: &RValue) {}
+}
+
+/// Like `Visitor`, but nodes can be changed in place. `visit_ast_mut` gets the box itself,
+/// so a node can also be replaced by a different one.
+pub trait VisitorMut<'a>: Sized {
+    fn visit_ast_mut(&mut self, node: &mut BoxAST<'a>) {
+        walk_ast_mut(self, node)
+    }
+
+    fn visit_program_mut(&mut self, node: &mut Program<'a>) {
+        walk_program_mut(self, node)
+    }
+
+    fn visit_include_mut(&mut self, _node: &mut Include) {}
+
+    fn visit_problem_decl_mut(&mut self, node: &mut ProblemDecl<'a>) {
+        walk_problem_decl_mut(self, node)
+    }
+
+    fn visit_func_decl_mut(&mut self, node: &mut FuncDecl<'a>) {
+        walk_func_decl_mut(self, node)
+    }
+
+    fn visit_object_decl_mut(&mut self, node: &mut ObjectDecl<'a>) {
+        walk_object_decl_mut(self, node)
+    }
+
+    fn visit_object_desc_mut(&mut self, node: &mut ObjectDesc<'a>) {
+        walk_object_desc_mut(self, node)
+    }
+
+    fn visit_object_shape_mut(&mut self, node: &mut ObjectShape<'a>) {
+        walk_object_shape_mut(self, node)
+    }
+
+    fn visit_object_color_mut(&mut self, _node: &mut ObjectColor) {}
+
+    fn visit_shape_mut(&mut self, node: &mut Shape<'a>) {
+        walk_shape_mut(self, node)
+    }
+
+    fn visit_tuple_mut(&mut self, _node: &mut Tuple) {}
+
+    fn visit_generic_tuple_mut(&mut self, node: &mut GenericTuple<'a>) {
+        walk_generic_tuple_mut(self, node)
+    }
+
+    fn visit_generic_tuple_option_mut(&mut self, _node: &mut GenericTupleOption) {}
+
+    fn visit_range_mut(&mut self, node: &mut Range<'a>) {
+        walk_range_mut(self, node)
+    }
+
+    fn visit_range_union_mut(&mut self, node: &mut RangeUnion<'a>) {
+        walk_range_union_mut(self, node)
+    }
+
+    fn visit_tuple_iterator_mut(&mut self, node: &mut TupleIterator<'a>) {
+        walk_tuple_iterator_mut(self, node)
+    }
+
+    fn visit_tuple_comprehension_mut(&mut self, node: &mut TupleComprehension<'a>) {
+        walk_tuple_comprehension_mut(self, node)
+    }
+
+    fn visit_var_def_mut(&mut self, node: &mut VarDef<'a>) {
+        walk_var_def_mut(self, node)
+    }
+
+    fn visit_problem_example_mut(&mut self, node: &mut ProblemExample<'a>) {
+        walk_problem_example_mut(self, node)
+    }
+
+    fn visit_problem_solution_mut(&mut self, node: &mut ProblemSolution<'a>) {
+        walk_problem_solution_mut(self, node)
+    }
+
+    fn visit_problem_input_mut(&mut self, node: &mut ProblemInput<'a>) {
+        walk_problem_input_mut(self, node)
+    }
+
+    fn visit_problem_output_mut(&mut self, node: &mut ProblemOutput<'a>) {
+        walk_problem_output_mut(self, node)
+    }
+
+    fn visit_poisoned_mut(&mut self, _node: &mut PoisonedStmt) {}
+
+    fn visit_func_call_mut(&mut self, node: &mut FuncCall<'a>) {
+        walk_func_call_mut(self, node)
+    }
+
+    fn visit_object_call_mut(&mut self, _node: &mut ObjectCall) {}
+
+    fn visit_binary_expr_mut(&mut self, node: &mut BinaryExpr<'a>) {
+        walk_binary_expr_mut(self, node)
+    }
+
+    fn visit_unary_expr_mut(&mut self, node: &mut UnaryExpr<'a>) {
+        walk_unary_expr_mut(self, node)
+    }
+
+    fn visit_rvalue_mut(&mut self, _node: &mut RValue) {}
+}
+
+// ==================================================================================== //
+//  Walkers                                                                             //
+// ==================================================================================== //
+
+pub fn walk_ast<'a, V: Visitor<'a>>(visitor: &mut V, node: &BoxAST<'a>) {
+    match node.as_node() {
+        NodeRef::Program(n) => visitor.visit_program(n),
+        NodeRef::Include(n) => visitor.visit_include(n),
+        NodeRef::ProblemDecl(n) => visitor.visit_problem_decl(n),
+        NodeRef::FuncDecl(n) => visitor.visit_func_decl(n),
+        NodeRef::ObjectDecl(n) => visitor.visit_object_decl(n),
+        NodeRef::ObjectDesc(n) => visitor.visit_object_desc(n),
+        NodeRef::ObjectShape(n) => visitor.visit_object_shape(n),
+        NodeRef::ObjectColor(n) => visitor.visit_object_color(n),
+        NodeRef::Shape(n) => visitor.visit_shape(n),
+        NodeRef::Tuple(n) => visitor.visit_tuple(n),
+        NodeRef::GenericTuple(n) => visitor.visit_generic_tuple(n),
+        NodeRef::GenericTupleOption(n) => visitor.visit_generic_tuple_option(n),
+        NodeRef::Range(n) => visitor.visit_range(n),
+        NodeRef::RangeUnion(n) => visitor.visit_range_union(n),
+        NodeRef::TupleIterator(n) => visitor.visit_tuple_iterator(n),
+        NodeRef::TupleComprehension(n) => visitor.visit_tuple_comprehension(n),
+        NodeRef::VarDef(n) => visitor.visit_var_def(n),
+        NodeRef::ProblemExample(n) => visitor.visit_problem_example(n),
+        NodeRef::ProblemSolution(n) => visitor.visit_problem_solution(n),
+        NodeRef::ProblemInput(n) => visitor.visit_problem_input(n),
+        NodeRef::ProblemOutput(n) => visitor.visit_problem_output(n),
+        NodeRef::Poisoned(n) => visitor.visit_poisoned(n),
+        NodeRef::FuncCall(n) => visitor.visit_func_call(n),
+        NodeRef::ObjectCall(n) => visitor.visit_object_call(n),
+        NodeRef::BinaryExpr(n) => visitor.visit_binary_expr(n),
+        NodeRef::UnaryExpr(n) => visitor.visit_unary_expr(n),
+        NodeRef::RValue(n) => visitor.visit_rvalue(n),
+    }
+}
+
+pub fn walk_program<'a, V: Visitor<'a>>(visitor: &mut V, node: &Program<'a>) {
+    for child in &node.items {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_problem_decl<'a, V: Visitor<'a>>(visitor: &mut V, node: &ProblemDecl<'a>) {
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_func_decl<'a, V: Visitor<'a>>(visitor: &mut V, node: &FuncDecl<'a>) {
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_object_decl<'a, V: Visitor<'a>>(visitor: &mut V, node: &ObjectDecl<'a>) {
+    visitor.visit_ast(&node.desc);
+}
+
+pub fn walk_object_desc<'a, V: Visitor<'a>>(visitor: &mut V, node: &ObjectDesc<'a>) {
+    visitor.visit_ast(&node.shape);
+
+    visitor.visit_ast(&node.color);
+}
+
+pub fn walk_object_shape<'a, V: Visitor<'a>>(visitor: &mut V, node: &ObjectShape<'a>) {
+    for child in &node.shape {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_shape<'a, V: Visitor<'a>>(visitor: &mut V, node: &Shape<'a>) {
+    visitor.visit_ast(&node.values);
+}
+
+pub fn walk_generic_tuple<'a, V: Visitor<'a>>(visitor: &mut V, node: &GenericTuple<'a>) {
+    visitor.visit_ast(&node.left);
+
+    visitor.visit_ast(&node.right);
+}
+
+pub fn walk_range<'a, V: Visitor<'a>>(visitor: &mut V, node: &Range<'a>) {
+    visitor.visit_ast(&node.left);
+
+    visitor.visit_ast(&node.right);
+
+    if let Some(child) = &node.step {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_range_union<'a, V: Visitor<'a>>(visitor: &mut V, node: &RangeUnion<'a>) {
+    for child in &node.ranges {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_tuple_iterator<'a, V: Visitor<'a>>(visitor: &mut V, node: &TupleIterator<'a>) {
+    visitor.visit_ast(&node.range);
+}
+
+pub fn walk_tuple_comprehension<'a, V: Visitor<'a>>(
+    visitor: &mut V,
+    node: &TupleComprehension<'a>,
+) {
+    visitor.visit_ast(&node.tuple);
+
+    for child in &node.generators {
+        visitor.visit_ast(child);
+    }
+
+    for child in &node.predicates {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_var_def<'a, V: Visitor<'a>>(visitor: &mut V, node: &VarDef<'a>) {
+    visitor.visit_ast(&node.expr);
+}
+
+pub fn walk_problem_example<'a, V: Visitor<'a>>(visitor: &mut V, node: &ProblemExample<'a>) {
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_problem_solution<'a, V: Visitor<'a>>(visitor: &mut V, node: &ProblemSolution<'a>) {
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_problem_input<'a, V: Visitor<'a>>(visitor: &mut V, node: &ProblemInput<'a>) {
+    visitor.visit_ast(&node.tuple);
+
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_problem_output<'a, V: Visitor<'a>>(visitor: &mut V, node: &ProblemOutput<'a>) {
+    visitor.visit_ast(&node.tuple);
+
+    for child in &node.stmts {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_func_call<'a, V: Visitor<'a>>(visitor: &mut V, node: &FuncCall<'a>) {
+    for child in &node.exprs {
+        visitor.visit_ast(child);
+    }
+}
+
+pub fn walk_binary_expr<'a, V: Visitor<'a>>(visitor: &mut V, node: &BinaryExpr<'a>) {
+    visitor.visit_ast(&node.left);
+
+    visitor.visit_ast(&node.right);
+}
+
+pub fn walk_unary_expr<'a, V: Visitor<'a>>(visitor: &mut V, node: &UnaryExpr<'a>) {
+    visitor.visit_ast(&node.operand);
+}
+
+pub fn walk_ast_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut BoxAST<'a>) {
+    match node.as_node_mut() {
+        NodeMut::Program(n) => visitor.visit_program_mut(n),
+        NodeMut::Include(n) => visitor.visit_include_mut(n),
+        NodeMut::ProblemDecl(n) => visitor.visit_problem_decl_mut(n),
+        NodeMut::FuncDecl(n) => visitor.visit_func_decl_mut(n),
+        NodeMut::ObjectDecl(n) => visitor.visit_object_decl_mut(n),
+        NodeMut::ObjectDesc(n) => visitor.visit_object_desc_mut(n),
+        NodeMut::ObjectShape(n) => visitor.visit_object_shape_mut(n),
+        NodeMut::ObjectColor(n) => visitor.visit_object_color_mut(n),
+        NodeMut::Shape(n) => visitor.visit_shape_mut(n),
+        NodeMut::Tuple(n) => visitor.visit_tuple_mut(n),
+        NodeMut::GenericTuple(n) => visitor.visit_generic_tuple_mut(n),
+        NodeMut::GenericTupleOption(n) => visitor.visit_generic_tuple_option_mut(n),
+        NodeMut::Range(n) => visitor.visit_range_mut(n),
+        NodeMut::RangeUnion(n) => visitor.visit_range_union_mut(n),
+        NodeMut::TupleIterator(n) => visitor.visit_tuple_iterator_mut(n),
+        NodeMut::TupleComprehension(n) => visitor.visit_tuple_comprehension_mut(n),
+        NodeMut::VarDef(n) => visitor.visit_var_def_mut(n),
+        NodeMut::ProblemExample(n) => visitor.visit_problem_example_mut(n),
+        NodeMut::ProblemSolution(n) => visitor.visit_problem_solution_mut(n),
+        NodeMut::ProblemInput(n) => visitor.visit_problem_input_mut(n),
+        NodeMut::ProblemOutput(n) => visitor.visit_problem_output_mut(n),
+        NodeMut::Poisoned(n) => visitor.visit_poisoned_mut(n),
+        NodeMut::FuncCall(n) => visitor.visit_func_call_mut(n),
+        NodeMut::ObjectCall(n) => visitor.visit_object_call_mut(n),
+        NodeMut::BinaryExpr(n) => visitor.visit_binary_expr_mut(n),
+        NodeMut::UnaryExpr(n) => visitor.visit_unary_expr_mut(n),
+        NodeMut::RValue(n) => visitor.visit_rvalue_mut(n),
+    }
+}
+
+pub fn walk_program_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut Program<'a>) {
+    for child in &mut node.items {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_problem_decl_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut ProblemDecl<'a>) {
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_func_decl_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut FuncDecl<'a>) {
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_object_decl_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut ObjectDecl<'a>) {
+    visitor.visit_ast_mut(&mut node.desc);
+}
+
+pub fn walk_object_desc_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut ObjectDesc<'a>) {
+    visitor.visit_ast_mut(&mut node.shape);
+
+    visitor.visit_ast_mut(&mut node.color);
+}
+
+pub fn walk_object_shape_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut ObjectShape<'a>) {
+    for child in &mut node.shape {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_shape_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut Shape<'a>) {
+    visitor.visit_ast_mut(&mut node.values);
+}
+
+pub fn walk_generic_tuple_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut GenericTuple<'a>) {
+    visitor.visit_ast_mut(&mut node.left);
+
+    visitor.visit_ast_mut(&mut node.right);
+}
+
+pub fn walk_range_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut Range<'a>) {
+    visitor.visit_ast_mut(&mut node.left);
+
+    visitor.visit_ast_mut(&mut node.right);
+
+    if let Some(child) = &mut node.step {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_range_union_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut RangeUnion<'a>) {
+    for child in &mut node.ranges {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_tuple_iterator_mut<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    node: &mut TupleIterator<'a>,
+) {
+    visitor.visit_ast_mut(&mut node.range);
+}
+
+pub fn walk_tuple_comprehension_mut<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    node: &mut TupleComprehension<'a>,
+) {
+    visitor.visit_ast_mut(&mut node.tuple);
+
+    for child in &mut node.generators {
+        visitor.visit_ast_mut(child);
+    }
+
+    for child in &mut node.predicates {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_var_def_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut VarDef<'a>) {
+    visitor.visit_ast_mut(&mut node.expr);
+}
+
+pub fn walk_problem_example_mut<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    node: &mut ProblemExample<'a>,
+) {
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_problem_solution_mut<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    node: &mut ProblemSolution<'a>,
+) {
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_problem_input_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut ProblemInput<'a>) {
+    visitor.visit_ast_mut(&mut node.tuple);
+
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_problem_output_mut<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    node: &mut ProblemOutput<'a>,
+) {
+    visitor.visit_ast_mut(&mut node.tuple);
+
+    for child in &mut node.stmts {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_func_call_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut FuncCall<'a>) {
+    for child in &mut node.exprs {
+        visitor.visit_ast_mut(child);
+    }
+}
+
+pub fn walk_binary_expr_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut BinaryExpr<'a>) {
+    visitor.visit_ast_mut(&mut node.left);
+
+    visitor.visit_ast_mut(&mut node.right);
+}
+
+pub fn walk_unary_expr_mut<'a, V: VisitorMut<'a>>(visitor: &mut V, node: &mut UnaryExpr<'a>) {
+    visitor.visit_ast_mut(&mut node.operand);
+}
+
+// ==================================================================================== //
+//  Implementations                                                                     //
+// ==================================================================================== //
+
+impl<'a> AsNode<'a> for Program<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Program(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Program(self)
+    }
+}
+
+impl<'a> AsNode<'a> for Include {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Include(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Include(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ProblemDecl<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ProblemDecl(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ProblemDecl(self)
+    }
+}
+
+impl<'a> AsNode<'a> for FuncDecl<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::FuncDecl(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::FuncDecl(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ObjectDecl<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ObjectDecl(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ObjectDecl(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ObjectDesc<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ObjectDesc(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ObjectDesc(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ObjectShape<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ObjectShape(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ObjectShape(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ObjectColor {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ObjectColor(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ObjectColor(self)
+    }
+}
+
+impl<'a> AsNode<'a> for Shape<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Shape(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Shape(self)
+    }
+}
+
+impl<'a> AsNode<'a> for Tuple {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Tuple(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Tuple(self)
+    }
+}
+
+impl<'a> AsNode<'a> for GenericTuple<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::GenericTuple(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::GenericTuple(self)
+    }
+}
+
+impl<'a> AsNode<'a> for GenericTupleOption {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::GenericTupleOption(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::GenericTupleOption(self)
+    }
+}
+
+impl<'a> AsNode<'a> for Range<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Range(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Range(self)
+    }
+}
+
+impl<'a> AsNode<'a> for RangeUnion<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::RangeUnion(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::RangeUnion(self)
+    }
+}
+
+impl<'a> AsNode<'a> for TupleIterator<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::TupleIterator(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::TupleIterator(self)
+    }
+}
+
+impl<'a> AsNode<'a> for TupleComprehension<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::TupleComprehension(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::TupleComprehension(self)
+    }
+}
+
+impl<'a> AsNode<'a> for VarDef<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::VarDef(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::VarDef(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ProblemExample<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ProblemExample(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ProblemExample(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ProblemSolution<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ProblemSolution(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ProblemSolution(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ProblemInput<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ProblemInput(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ProblemInput(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ProblemOutput<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ProblemOutput(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ProblemOutput(self)
+    }
+}
+
+impl<'a> AsNode<'a> for PoisonedStmt {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::Poisoned(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::Poisoned(self)
+    }
+}
+
+impl<'a> AsNode<'a> for FuncCall<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::FuncCall(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::FuncCall(self)
+    }
+}
+
+impl<'a> AsNode<'a> for ObjectCall {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::ObjectCall(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::ObjectCall(self)
+    }
+}
+
+impl<'a> AsNode<'a> for BinaryExpr<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::BinaryExpr(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::BinaryExpr(self)
+    }
+}
+
+impl<'a> AsNode<'a> for UnaryExpr<'a> {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::UnaryExpr(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::UnaryExpr(self)
+    }
+}
+
+impl<'a> AsNode<'a> for RValue {
+    fn as_node(&self) -> NodeRef<'_, 'a> {
+        NodeRef::RValue(self)
+    }
+
+    fn as_node_mut(&mut self) -> NodeMut<'_, 'a> {
+        NodeMut::RValue(self)
+    }
+}
@@ -6,7 +6,9 @@ pub enum CompilerPass {
     Internal,
     Lexer,
     Parser,
+    Loader,
     AST,
     Semantics,
+    Lint,
     ALL,
 }
@@ -1,5 +1,8 @@
 use crate::config::CompilerPass;
-use crate::diagnostics::{create_diagnostic, Code, Diagnostic, Help, Note, Span};
+use crate::diagnostics::suggestion::closest_name;
+use crate::diagnostics::{
+    create_diagnostic, Applicability, Code, Diagnostic, Help, Note, Span, Suggestion,
+};
 use crate::lexer::lex::Lexer;
 
 fn _build_diagnostic(
@@ -28,6 +31,8 @@ pub struct DiagnosticBuilder {
     code: Code,
     note: Option<Note>,
     help: Option<Help>,
+    labels: Vec<(Span, String)>,
+    suggestions: Vec<Suggestion>,
 }
 
 impl DiagnosticBuilder {
@@ -37,6 +42,8 @@ impl DiagnosticBuilder {
             code,
             note: None,
             help: None,
+            labels: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -54,11 +61,57 @@ impl DiagnosticBuilder {
         self
     }
 
-    pub fn emit(self, tokens: &mut Lexer, span: Span) -> Option<Diagnostic> {
-        if tokens.is_poisoned() {
-            return None;
+    /// Points at `span` as well, e.g. where something was first given.
+    pub fn with_label(mut self, span: Span, message: &str) -> Self {
+        self.labels.push((span, message.to_string()));
+        self
+    }
+
+    /// Offers replacing `span` with `replacement` as a fix.
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        replacement: &str,
+        applicability: Applicability,
+    ) -> Self {
+        self.suggestions
+            .push(Suggestion::new(span, replacement, applicability));
+        self
+    }
+
+    /// Offers every one of `suggestions` as a fix, e.g. an `Option` of one that only some
+    /// cases have.
+    pub fn with_suggestions(mut self, suggestions: impl IntoIterator<Item = Suggestion>) -> Self {
+        self.suggestions.extend(suggestions);
+        self
+    }
+
+    /// Turns the help into a "did you mean" when `found`, at `span`, is a near miss of one
+    /// of `candidates`, and offers the closest one as a replacement. The replacement is only
+    /// machine applicable when no other candidate is as close and it differs in more than
+    /// case, so `candidates` should only hold names fit to stand where `found` is.
+    pub fn with_did_you_mean<S: AsRef<str>>(
+        mut self,
+        found: &str,
+        span: Span,
+        candidates: &[S],
+    ) -> Self {
+        if let Some((name, safe)) = closest_name(found, candidates) {
+            let applicability = if safe {
+                Applicability::MachineApplicable
+            } else {
+                Applicability::MaybeIncorrect
+            };
+
+            self.help = Some(Help::DidYouMean(name.to_string()));
+            self.suggestions
+                .push(Suggestion::new(span, name, applicability));
         }
 
+        self
+    }
+
+    pub fn emit(self, tokens: &mut Lexer, span: Span) -> Diagnostic {
         let mut note_str: String = String::default();
         let mut help_str: String = String::default();
 
@@ -70,8 +123,17 @@ impl DiagnosticBuilder {
             help_str = hp.stringify();
         }
 
-        Some(_build_diagnostic(
-            self.pass, self.code, tokens, span, note_str, help_str,
-        ))
+        let mut diagnostic =
+            _build_diagnostic(self.pass, self.code, tokens, span, note_str, help_str);
+
+        for (span, message) in self.labels {
+            diagnostic.add_label(tokens, span, message);
+        }
+
+        for suggestion in self.suggestions {
+            diagnostic.add_suggestion(suggestion);
+        }
+
+        diagnostic
     }
 }
@@ -0,0 +1,233 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+const ENGLISH: &str = include_str!("catalogs/en.txt");
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+/// Message templates by key, such as `note-unknown-object = No object named '{object}'
+/// ...`. English is built in; other catalogs are read from files of the same form and
+/// fall back to English for the keys they leave out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    templates: HashMap<String, String>,
+}
+
+thread_local! {
+    // The catalog messages are looked up in first, see `install`
+    static INSTALLED: RefCell<Option<Rc<Catalog>>> = const { RefCell::new(None) };
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl Catalog {
+    /// The built-in catalog, which has every message there is.
+    pub fn english() -> &'static Catalog {
+        static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+        CATALOG.get_or_init(|| {
+            Catalog::parse_templates(ENGLISH).expect("the English catalog is well formed")
+        })
+    }
+
+    /// Reads a catalog, which may only have keys the English one has, with the same
+    /// arguments.
+    pub fn parse(text: &str) -> Result<Catalog, String> {
+        let catalog = Catalog::parse_templates(text)?;
+        let english = Catalog::english();
+
+        let mut keys: Vec<&String> = catalog.templates.keys().collect();
+        keys.sort();
+
+        for key in keys {
+            let Some(reference) = english.get(key) else {
+                return Err(format!("Unknown message: {}", key));
+            };
+
+            let expected = placeholders(reference)?;
+            let found = placeholders(&catalog.templates[key])?;
+
+            if expected != found {
+                return Err(format!(
+                    "Message {} takes the arguments {{{}}}, but found {{{}}}",
+                    key,
+                    join(&expected),
+                    join(&found)
+                ));
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Catalog, String> {
+        let path = path.as_ref();
+
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+        Catalog::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.templates.get(key).map(String::as_str)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+
+    // `key = template` lines, leaving out blank lines and comments
+    fn parse_templates(text: &str) -> Result<Catalog, String> {
+        let mut templates: HashMap<String, String> = HashMap::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, template)) = line.split_once('=') else {
+                return Err(format!("line {}: expected 'key = template'", number + 1));
+            };
+
+            let key = key.trim();
+
+            if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || c == '-') {
+                return Err(format!(
+                    "line {}: '{}' is not a message key",
+                    number + 1,
+                    key
+                ));
+            }
+
+            placeholders(template.trim()).map_err(|err| format!("line {}: {}", number + 1, err))?;
+
+            if templates
+                .insert(key.to_string(), template.trim().to_string())
+                .is_some()
+            {
+                return Err(format!("line {}: {} is given twice", number + 1, key));
+            }
+        }
+
+        Ok(Catalog { templates })
+    }
+}
+
+// ==================================================================================== //
+// Formatting                                                                           //
+// ==================================================================================== //
+
+/// Looks messages up in `catalog` from now on, on this thread, before falling back to
+/// English.
+pub fn install(catalog: Catalog) {
+    INSTALLED.with(|installed| *installed.borrow_mut() = Some(Rc::new(catalog)));
+}
+
+/// Goes back to English only.
+pub fn uninstall() {
+    INSTALLED.with(|installed| *installed.borrow_mut() = None);
+}
+
+/// The message `key` with its arguments filled in, from the installed catalog or else in
+/// English. Unknown keys come out as they are, so that a missing message shows up.
+pub fn message(key: &str, args: &[(&str, &str)]) -> String {
+    let installed = INSTALLED.with(|installed| installed.borrow().clone());
+
+    let template = installed
+        .as_deref()
+        .and_then(|catalog| catalog.get(key))
+        .or_else(|| Catalog::english().get(key));
+
+    match template {
+        Some(template) => fill(template, args),
+        None => key.to_string(),
+    }
+}
+
+/// The names of the arguments `template` takes.
+pub fn placeholders(template: &str) -> Result<BTreeSet<&str>, String> {
+    let mut names = BTreeSet::new();
+    let mut rest = template;
+
+    while let Some(at) = rest.find(['{', '}']) {
+        let after = &rest[at + 1..];
+
+        if after.starts_with(&rest[at..at + 1]) {
+            rest = &after[1..];
+            continue;
+        }
+
+        if rest[at..].starts_with('}') {
+            return Err(format!("unmatched '}}' in '{}'", template));
+        }
+
+        let Some(close) = after.find('}') else {
+            return Err(format!("unclosed '{{' in '{}'", template));
+        };
+
+        let name = &after[..close];
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("'{{{}}}' is not an argument", name));
+        }
+
+        names.insert(name);
+        rest = &after[close + 1..];
+    }
+
+    Ok(names)
+}
+
+// The template with every argument replaced by its value. Arguments without a value are
+// left as they are.
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+
+        let brace = &rest[at..at + 1];
+        let after = &rest[at + 1..];
+
+        if after.starts_with(brace) {
+            out.push_str(brace);
+            rest = &after[1..];
+            continue;
+        }
+
+        match after.find('}') {
+            Some(close) if brace == "{" => {
+                let name = &after[..close];
+
+                match args.iter().find(|(arg, _)| *arg == name) {
+                    Some((_, value)) => out.push_str(value),
+                    None => out.push_str(&rest[at..at + close + 2]),
+                }
+
+                rest = &after[close + 1..];
+            }
+            _ => {
+                out.push_str(brace);
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn join(names: &BTreeSet<&str>) -> String {
+    names.iter().copied().collect::<Vec<_>>().join(", ")
+}
@@ -1,3 +1,5 @@
+use crate::diagnostics::catalog::{self, Catalog};
+
 use derive_more::Display;
 
 // ==================================================================================== //
@@ -8,14 +10,37 @@ use derive_more::Display;
 pub enum Code {
     #[default]
     I0001, // Internal Compiler Error
-    E0001, // Expected keyword token but found something else
-    E0002, // Expected symbol token but found something else
+    I0002, // Input nested too deeply to parse
+    E0001, // Expected a keyword or punctuation token but found something else
+    E0002, // Expected a data type or range bracket but found something else
     E0003, // Expected integer constant but found something else
     E0004, // Poisoned AST Node detected
-    E000X,
-    W000X,
-    N000X,
-    H000X,
+    E0005, // Unknown named argument in object call
+    E0006, // Named argument given more than once in object call
+    E0007, // Positional and named arguments mixed in object call
+    E0008, // Too many arguments in object call
+    E0009, // Expected a top-level declaration but found something else
+    E0010, // Included module could not be found
+    E0011, // Modules include each other in a cycle
+    E0012, // Included name clashes with another declaration
+    E0013, // Input goes on after the fragment that was parsed
+    E0014, // Text that is no token of the language
+    E0015, // Object call names no object in scope
+    E0016, // Placed object has cells outside its grid
+    E0017, // Grid size outside of ARC's limits
+    E0018, // Range with no values
+    E0019, // Expected a shape or color field but found something else
+    E0020, // Object call names something that is not an object
+    E0021, // Range step that is not positive
+    E0022, // Expected an identifier but found something else
+    E0023, // Expected an identifier or integer constant but found something else
+    E0024, // Expected a statement but found something else
+    E0025, // Expected a shape but found something else
+    W0001, // Object declared but never used
+    W0002, // Object shape covers no cells
+    W0003, // Object never placed in an input or output
+    W0004, // Objects placed over each other
+    W0005, // Lint attribute names no lint
 }
 
 #[derive(Default, Display, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -48,17 +73,7 @@ impl FromCode for DiagnosticLevel {
 
 impl FromCode for String {
     fn from_code(code: &Code) -> Self {
-        match code {
-            Code::I0001 => "Internal Compiler Error occurred".to_string(),
-            Code::E0001 => "Expected keyword but something else was found".to_string(),
-            Code::E0002 => "Expected symbol but something else was found".to_string(),
-            Code::E0003 => "Expected integer constant but something else was found".to_string(),
-            Code::E0004 => "Poisoned AST Node detected".to_string(),
-            Code::E000X => "An error occurred.".to_string(),
-            Code::W000X => "This is a warning.".to_string(),
-            Code::N000X => "This is a note.".to_string(),
-            Code::H000X => "This is a help message.".to_string(),
-        }
+        catalog::message(&code.message_key(), &[])
     }
 }
 
@@ -77,3 +92,107 @@ impl DiagnosticLevel {
         }
     }
 }
+
+// ==================================================================================== //
+// Registry                                                                             //
+// ==================================================================================== //
+
+impl Code {
+    /// Every code there is, in order. Codes are never reused once given out.
+    pub const ALL: [Code; 32] = [
+        Code::I0001,
+        Code::I0002,
+        Code::E0001,
+        Code::E0002,
+        Code::E0003,
+        Code::E0004,
+        Code::E0005,
+        Code::E0006,
+        Code::E0007,
+        Code::E0008,
+        Code::E0009,
+        Code::E0010,
+        Code::E0011,
+        Code::E0012,
+        Code::E0013,
+        Code::E0014,
+        Code::E0015,
+        Code::E0016,
+        Code::E0017,
+        Code::E0018,
+        Code::E0019,
+        Code::E0020,
+        Code::E0021,
+        Code::E0022,
+        Code::E0023,
+        Code::E0024,
+        Code::E0025,
+        Code::W0001,
+        Code::W0002,
+        Code::W0003,
+        Code::W0004,
+        Code::W0005,
+    ];
+
+    /// The one line description diagnostics with this code are headed by, in English.
+    pub fn summary(&self) -> &'static str {
+        Catalog::english()
+            .get(&self.message_key())
+            .expect("every code has a summary in the English catalog")
+    }
+
+    /// The key of the summary in a message catalog, e.g. `code-E0001`.
+    pub fn message_key(&self) -> String {
+        format!("code-{}", self)
+    }
+
+    /// The long form markdown explanation shown by `--explain`, with an erroneous and a
+    /// corrected example.
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            Code::I0001 => include_str!("explanations/I0001.md"),
+            Code::I0002 => include_str!("explanations/I0002.md"),
+            Code::E0001 => include_str!("explanations/E0001.md"),
+            Code::E0002 => include_str!("explanations/E0002.md"),
+            Code::E0003 => include_str!("explanations/E0003.md"),
+            Code::E0004 => include_str!("explanations/E0004.md"),
+            Code::E0005 => include_str!("explanations/E0005.md"),
+            Code::E0006 => include_str!("explanations/E0006.md"),
+            Code::E0007 => include_str!("explanations/E0007.md"),
+            Code::E0008 => include_str!("explanations/E0008.md"),
+            Code::E0009 => include_str!("explanations/E0009.md"),
+            Code::E0010 => include_str!("explanations/E0010.md"),
+            Code::E0011 => include_str!("explanations/E0011.md"),
+            Code::E0012 => include_str!("explanations/E0012.md"),
+            Code::E0013 => include_str!("explanations/E0013.md"),
+            Code::E0014 => include_str!("explanations/E0014.md"),
+            Code::E0015 => include_str!("explanations/E0015.md"),
+            Code::E0016 => include_str!("explanations/E0016.md"),
+            Code::E0017 => include_str!("explanations/E0017.md"),
+            Code::E0018 => include_str!("explanations/E0018.md"),
+            Code::E0019 => include_str!("explanations/E0019.md"),
+            Code::E0020 => include_str!("explanations/E0020.md"),
+            Code::E0021 => include_str!("explanations/E0021.md"),
+            Code::E0022 => include_str!("explanations/E0022.md"),
+            Code::E0023 => include_str!("explanations/E0023.md"),
+            Code::E0024 => include_str!("explanations/E0024.md"),
+            Code::E0025 => include_str!("explanations/E0025.md"),
+            Code::W0001 => include_str!("explanations/W0001.md"),
+            Code::W0002 => include_str!("explanations/W0002.md"),
+            Code::W0003 => include_str!("explanations/W0003.md"),
+            Code::W0004 => include_str!("explanations/W0004.md"),
+            Code::W0005 => include_str!("explanations/W0005.md"),
+        }
+    }
+}
+
+impl std::str::FromStr for Code {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Code::ALL
+            .into_iter()
+            .find(|code| code.to_string() == s)
+            .ok_or_else(|| format!("Unknown error code: {}", s))
+    }
+}
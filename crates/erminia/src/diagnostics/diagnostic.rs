@@ -1,9 +1,9 @@
 use crate::config::CompilerPass;
 use crate::diagnostics::code::{Code, DiagnosticLevel, FromCode};
-use crate::diagnostics::{DiagnosticWindow, Span};
+use crate::diagnostics::render;
+use crate::diagnostics::{DiagnosticWindow, FileId, Label, SourceMap, Span, Suggestion};
 use crate::lexer::lex::Lexer;
 
-use colored::*;
 use std::fmt;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -15,11 +15,14 @@ pub struct Diagnostic {
     pub window: DiagnosticWindow,
     pub note: String,
     pub help: String,
+    pub suggestions: Vec<Suggestion>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DiagnosticAccumulator {
     pub diagnostics: Vec<Diagnostic>,
+    error_limit: Option<usize>,
+    suppressed: usize,
 }
 
 impl Diagnostic {
@@ -38,6 +41,7 @@ impl Diagnostic {
             window,
             note: String::new(),
             help: String::new(),
+            suggestions: vec![],
         }
     }
 
@@ -48,51 +52,61 @@ impl Diagnostic {
     pub fn add_help(&mut self, help: String) {
         self.help = help;
     }
-}
 
-impl fmt::Display for Diagnostic {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(
-            f,
-            " [{}] {} {}",
-            self.code.to_string().red().bold(),
-            self.level,
-            self.message.bold()
-        )?;
-        writeln!(f, "  {} {}", "pass:".dimmed(), self.pass)?;
-
-        if self.pass != CompilerPass::Internal {
-            writeln!(
-                f,
-                "  {}{}:{}::{}",
-                "span:".dimmed(),
-                self.window.span.start.get_line(),
-                self.window.span.start.get_cursor(),
-                self.window.span.end.get_cursor()
-            )?;
-        }
+    /// Points at `span` as well, saying `message` about it. Lines of `tokens` are shown
+    /// when the label is in its file.
+    pub fn add_label(&mut self, tokens: &Lexer, span: Span, message: String) {
+        let lines = if span.file() == self.window.span.file() {
+            tokens.get_lines(span)
+        } else {
+            vec![]
+        };
 
-        writeln!(f, "  │")?;
-        for line in self.window.snippet.lines() {
-            writeln!(
-                f,
-                "{} │   {}",
-                self.window.span.start.get_line().to_string().green().bold(),
-                line.dimmed()
-            )?;
-        }
-        writeln!(f, "  │")?;
+        self.window.add_label(Label { span, message }, lines);
+    }
+
+    pub fn add_suggestion(&mut self, suggestion: Suggestion) {
+        self.suggestions.push(suggestion);
+    }
+
+    /// The diagnostic laid out for a terminal, or without colors for logs when `color` is
+    /// false.
+    pub fn render(&self, color: bool) -> String {
+        render::render(self, None, color)
+    }
+
+    /// Like `render`, with the path of the file from `source_map` in every location.
+    pub fn render_with(&self, source_map: &SourceMap, color: bool) -> String {
+        render::render(self, Some(source_map), color)
+    }
+
+    /// Whether the diagnostic stops compilation, internal errors included.
+    pub fn is_error(&self) -> bool {
+        self.level <= DiagnosticLevel::Error
+    }
 
-        if !self.note.is_empty() {
-            writeln!(f, "  = {} {}", "note:".bold(), self.note)?;
+    /// An error that only exists because of `earlier`: both complain about the same token,
+    /// or a later pass complains about a node an earlier pass already failed to build.
+    pub fn is_cascade_of(&self, earlier: &Diagnostic) -> bool {
+        if self.level != DiagnosticLevel::Error || earlier.level != DiagnosticLevel::Error {
+            return false;
         }
 
-        if !self.help.is_empty() {
-            writeln!(f, "  * {} {}", "help:".bold(), self.help)?;
+        let span = &self.window.span;
+
+        if self.pass == earlier.pass {
+            return span.ends_with(&earlier.window.span);
         }
 
-        writeln!(f)?;
-        Ok(())
+        earlier.pass < self.pass && span.contains(&earlier.window.span)
+    }
+}
+
+// Without colors, as the text may end up in a log or a file. Terminals get theirs from
+// `render` and `render_with`.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.render(false))
     }
 }
 
@@ -100,31 +114,119 @@ impl DiagnosticAccumulator {
     pub fn new() -> Self {
         DiagnosticAccumulator {
             diagnostics: Vec::new(),
+            error_limit: None,
+            suppressed: 0,
         }
     }
 
+    /// Records `diagnostic` unless it is a cascade of one already recorded, the same
+    /// code at the same span as one, or an error past the error limit.
     pub fn add_diag(&mut self, diagnostic: Diagnostic) {
+        if self.diagnostics.iter().any(|d| {
+            diagnostic.is_cascade_of(d)
+                || (d.code == diagnostic.code && d.window.span == diagnostic.window.span)
+        }) {
+            return;
+        }
+
+        if diagnostic.is_error() && self.error_limit.is_some_and(|l| self.error_count() >= l) {
+            self.suppressed += 1;
+            return;
+        }
+
         self.diagnostics.push(diagnostic);
     }
 
+    /// Keeps at most `limit` errors, counting the ones after them as suppressed. Other
+    /// diagnostics are kept either way.
+    pub fn set_error_limit(&mut self, limit: Option<usize>) {
+        self.error_limit = limit;
+    }
+
+    /// How many errors were left out for going past the error limit.
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Orders the diagnostics as `sorted` does.
     pub fn sort(&mut self) {
-        self.diagnostics.sort_by_key(|d| match d.level {
-            DiagnosticLevel::Internal => -1,
-            DiagnosticLevel::Error => 0,
-            DiagnosticLevel::Warning => 1,
-            DiagnosticLevel::Note => 2,
-            DiagnosticLevel::Help => 3,
-        });
-    }
-
-    pub fn is_blocking(&mut self, next: CompilerPass) -> bool {
-        self.diagnostics.sort();
-        for diag in &self.diagnostics {
-            if diag.pass < next && diag.level == DiagnosticLevel::Error {
-                return true;
-            }
+        self.diagnostics.sort_by_key(report_order);
+    }
+
+    /// The diagnostics most severe first, and in source order within each level.
+    pub fn sorted(&self) -> Vec<Diagnostic> {
+        let mut sorted = self.diagnostics.clone();
+        sorted.sort_by_key(report_order);
+        sorted
+    }
+
+    /// Whether an error from a pass before `next` means `next` should not run.
+    pub fn is_blocking(&self, next: CompilerPass) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.pass < next && d.level == DiagnosticLevel::Error)
+    }
+
+    /// Whether a pass before `pass` reported an error within `span`, so that whatever `pass`
+    /// finds wrong there follows from it.
+    pub fn has_error_within(&self, pass: CompilerPass, span: &Span) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.pass < pass && d.is_error() && span.contains(&d.window.span))
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.has_level(DiagnosticLevel::Error)
+    }
+
+    pub fn has_level(&self, level: DiagnosticLevel) -> bool {
+        self.diagnostics.iter().any(|d| d.level == level)
+    }
+
+    /// Errors recorded, internal ones included.
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.is_error()).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.level == DiagnosticLevel::Warning)
+            .count()
+    }
+
+    /// A line like "3 errors, 2 warnings emitted" to close a report with, if there were
+    /// any.
+    pub fn summary(&self) -> Option<String> {
+        let count = |n: usize, what: &str| match n {
+            1 => format!("1 {}", what),
+            n => format!("{} {}s", n, what),
+        };
+
+        let mut parts: Vec<String> = vec![];
+
+        if self.error_count() > 0 {
+            parts.push(count(self.error_count(), "error"));
+        }
+
+        if self.warning_count() > 0 {
+            parts.push(count(self.warning_count(), "warning"));
+        }
+
+        if parts.is_empty() {
+            return None;
         }
-        false
+
+        let mut summary = format!("{} emitted", parts.join(", "));
+
+        if self.suppressed > 0 {
+            summary.push_str(&format!(
+                ", {} past the error limit not shown",
+                count(self.suppressed, "more error")
+            ));
+        }
+
+        Some(summary)
     }
 
     pub fn get(&self, pass: CompilerPass) -> Vec<Diagnostic> {
@@ -138,11 +240,27 @@ impl DiagnosticAccumulator {
             .cloned()
             .collect()
     }
+
+    /// Every diagnostic rendered in the order of `sorted`, see `Diagnostic::render`.
+    pub fn render(&self, color: bool) -> String {
+        self.sorted()
+            .iter()
+            .map(|d| format!("{}\n", d.render(color)))
+            .collect()
+    }
+
+    /// Every diagnostic rendered with paths from `source_map`, see `Diagnostic::render_with`.
+    pub fn render_with(&self, source_map: &SourceMap, color: bool) -> String {
+        self.sorted()
+            .iter()
+            .map(|d| format!("{}\n", d.render_with(source_map, color)))
+            .collect()
+    }
 }
 
 impl fmt::Display for DiagnosticAccumulator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for d in &self.diagnostics {
+        for d in self.sorted() {
             writeln!(f, "{}", d)?;
         }
 
@@ -156,6 +274,18 @@ impl Default for DiagnosticAccumulator {
     }
 }
 
+// Most severe first, then by where in which file
+fn report_order(diagnostic: &Diagnostic) -> (DiagnosticLevel, FileId, usize, usize) {
+    let span = diagnostic.window.span;
+
+    (
+        diagnostic.level,
+        span.file(),
+        span.start.get_cursor(),
+        span.end.get_cursor(),
+    )
+}
+
 #[allow(unused)]
 pub trait ToSnippet {
     fn to_snippet(&self) -> String;
@@ -173,11 +303,8 @@ pub fn create_diagnostic(
 ) -> Diagnostic {
     let level = DiagnosticLevel::from_code(&code);
     let message = String::from_code(&code);
-    let snippet = tokens.get_snippet(span);
-    let window = DiagnosticWindow {
-        span,
-        snippet: snippet.to_string(),
-    };
+    let snippet = tokens.get_snippet(span).to_string();
+    let window = DiagnosticWindow::new(span, snippet, tokens.get_lines(span));
 
     Diagnostic::new(level, code, pass, message, window)
 }
@@ -0,0 +1,293 @@
+use serde_json::{json, Value};
+
+use crate::diagnostics::code::{Code, DiagnosticLevel, FromCode};
+use crate::diagnostics::{Diagnostic, DiagnosticWindow, SourceMap, Span, Suggestion};
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+// ==================================================================================== //
+// Message Format                                                                       //
+// ==================================================================================== //
+
+/// How diagnostics are printed: rendered for people, one JSON object per line, or as a
+/// single SARIF log.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+impl std::str::FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            "sarif" => Ok(MessageFormat::Sarif),
+            _ => Err(format!(
+                "Unknown message format '{}', expected one of human, json, sarif",
+                s
+            )),
+        }
+    }
+}
+
+// ==================================================================================== //
+// JSON                                                                                 //
+// ==================================================================================== //
+
+/// `diagnostic` as a JSON object. File paths come from `source_map`, and `rendered` holds
+/// the human readable form without colors.
+pub fn to_json(diagnostic: &Diagnostic, source_map: &SourceMap) -> Value {
+    let window = &diagnostic.window;
+
+    let mut spans = vec![span_json(window, window.span, None, source_map)];
+    for label in &window.labels {
+        spans.push(span_json(
+            window,
+            label.span,
+            Some(&label.message),
+            source_map,
+        ));
+    }
+
+    let suggestions: Vec<Value> = diagnostic
+        .suggestions
+        .iter()
+        .map(|suggestion| {
+            let mut value = span_json(window, suggestion.span, None, source_map);
+            let fields = value.as_object_mut().expect("spans are objects");
+
+            for key in ["is_primary", "label", "text"] {
+                fields.remove(key);
+            }
+            fields.insert("replacement".into(), json!(suggestion.replacement));
+            fields.insert(
+                "applicability".into(),
+                json!(suggestion.applicability.as_str()),
+            );
+
+            value
+        })
+        .collect();
+
+    json!({
+        "code": diagnostic.code.to_string(),
+        "level": level_name(diagnostic.level),
+        "pass": diagnostic.pass.to_string(),
+        "message": diagnostic.message,
+        "note": non_empty(&diagnostic.note),
+        "help": non_empty(&diagnostic.help),
+        "spans": spans,
+        "suggestions": suggestions,
+        "rendered": diagnostic.render_with(source_map, false),
+    })
+}
+
+fn span_json(
+    window: &DiagnosticWindow,
+    span: Span,
+    label: Option<&str>,
+    source_map: &SourceMap,
+) -> Value {
+    let (start, end) = match line_columns(window, span) {
+        Some((start, end)) => (Some(start), Some(end)),
+        None => (None, None),
+    };
+
+    json!({
+        "file": file_path(span, source_map),
+        "byte_start": span.start.get_cursor(),
+        "byte_end": span.end.get_cursor(),
+        "line_start": start.map(|(line, _)| line),
+        "column_start": start.map(|(_, column)| column),
+        "line_end": end.map(|(line, _)| line),
+        "column_end": end.map(|(_, column)| column),
+        "is_primary": label.is_none(),
+        "label": label,
+        "text": label.is_none().then_some(window.snippet.as_str()),
+    })
+}
+
+// ==================================================================================== //
+// SARIF                                                                                //
+// ==================================================================================== //
+
+/// A SARIF 2.1.0 log with a single run holding `diagnostics`. Every code met becomes a
+/// rule of the run.
+pub fn to_sarif(diagnostics: &[Diagnostic], source_map: &SourceMap) -> Value {
+    let mut codes: Vec<_> = diagnostics.iter().map(|d| d.code).collect();
+    codes.sort();
+    codes.dedup();
+
+    let rules: Vec<Value> = codes
+        .iter()
+        .map(|code| {
+            json!({
+                "id": code.to_string(),
+                "shortDescription": { "text": String::from_code(code) },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| sarif_result(d, &codes, source_map))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "erminia",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(diagnostic: &Diagnostic, codes: &[Code], source_map: &SourceMap) -> Value {
+    let window = &diagnostic.window;
+
+    // The note says what went wrong in this instance, the message is the rule's text
+    let text = non_empty(&diagnostic.note).unwrap_or(&diagnostic.message);
+
+    let related: Vec<Value> = window
+        .labels
+        .iter()
+        .enumerate()
+        .map(|(id, label)| {
+            let mut location = sarif_location(window, label.span, source_map);
+            location["id"] = json!(id);
+            location["message"] = json!({ "text": label.message });
+            location
+        })
+        .collect();
+
+    let mut result = json!({
+        "ruleId": diagnostic.code.to_string(),
+        "ruleIndex": codes.iter().position(|c| *c == diagnostic.code),
+        "level": sarif_level(diagnostic.level),
+        "message": { "text": text },
+        "locations": [sarif_location(window, window.span, source_map)],
+        "properties": {
+            "pass": diagnostic.pass.to_string(),
+        },
+    });
+
+    if !related.is_empty() {
+        result["relatedLocations"] = json!(related);
+    }
+
+    if let Some(help) = non_empty(&diagnostic.help) {
+        result["properties"]["help"] = json!(help);
+    }
+
+    let fixes: Vec<Value> = diagnostic
+        .suggestions
+        .iter()
+        .map(|suggestion| sarif_fix(window, suggestion, source_map))
+        .collect();
+
+    if !fixes.is_empty() {
+        result["fixes"] = json!(fixes);
+    }
+
+    result
+}
+
+fn sarif_location(window: &DiagnosticWindow, span: Span, source_map: &SourceMap) -> Value {
+    let mut region = json!({
+        "byteOffset": span.start.get_cursor(),
+        "byteLength": span.end.get_cursor().saturating_sub(span.start.get_cursor()),
+    });
+
+    if let Some(((start_line, start_column), (end_line, end_column))) = line_columns(window, span) {
+        region["startLine"] = json!(start_line);
+        region["startColumn"] = json!(start_column);
+        region["endLine"] = json!(end_line);
+        region["endColumn"] = json!(end_column);
+    }
+
+    let mut physical = json!({ "region": region });
+
+    if let Some(path) = file_path(span, source_map) {
+        physical["artifactLocation"] = json!({ "uri": path });
+    }
+
+    json!({ "physicalLocation": physical })
+}
+
+fn sarif_fix(window: &DiagnosticWindow, suggestion: &Suggestion, source_map: &SourceMap) -> Value {
+    let location = sarif_location(window, suggestion.span, source_map);
+    let physical = &location["physicalLocation"];
+
+    let mut change = json!({
+        "replacements": [{
+            "deletedRegion": physical["region"],
+            "insertedContent": { "text": suggestion.replacement },
+        }],
+    });
+
+    if let Some(artifact) = physical.get("artifactLocation") {
+        change["artifactLocation"] = artifact.clone();
+    }
+
+    json!({
+        "description": { "text": suggestion.applicability.as_str() },
+        "artifactChanges": [change],
+    })
+}
+
+fn sarif_level(level: DiagnosticLevel) -> &'static str {
+    match level {
+        DiagnosticLevel::Internal | DiagnosticLevel::Error => "error",
+        DiagnosticLevel::Warning => "warning",
+        DiagnosticLevel::Note | DiagnosticLevel::Help => "note",
+    }
+}
+
+// ==================================================================================== //
+// Helpers                                                                              //
+// ==================================================================================== //
+
+fn level_name(level: DiagnosticLevel) -> String {
+    level.to_string().to_lowercase()
+}
+
+fn non_empty(text: &str) -> Option<&str> {
+    (!text.is_empty()).then_some(text)
+}
+
+fn file_path(span: Span, source_map: &SourceMap) -> Option<String> {
+    source_map
+        .get(span.file())
+        .map(|file| file.path.to_string_lossy().replace('\\', "/"))
+}
+
+// Start and end of `span` as line and column, the end column being exclusive as in SARIF.
+// Spans in another file than the window's have none, their lines are not in it.
+fn line_columns(window: &DiagnosticWindow, span: Span) -> Option<((usize, usize), (usize, usize))> {
+    if span.file() != window.span.file() {
+        return None;
+    }
+
+    let start = window.position(span.start.get_cursor())?;
+
+    // A span ending on a line break ends after the last character of its line
+    let end = window.position(span.end.get_cursor()).or_else(|| {
+        let (line, column) = window.position(span.end.get_cursor().checked_sub(1)?)?;
+        Some((line, column + 1))
+    })?;
+
+    Some((start, end))
+}
@@ -4,6 +4,10 @@ use crate::lexer::lex::PositionalOffset;
 // Structs                                                                              //
 // ==================================================================================== //
 
+/// Index of a source file known to the loader. Sources parsed on their own are file 0.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(pub usize);
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Span {
     pub start: PositionalOffset,
@@ -14,10 +18,88 @@ impl Span {
     pub fn new(start: PositionalOffset, end: PositionalOffset) -> Self {
         Span { start, end }
     }
+
+    pub fn file(&self) -> FileId {
+        self.start.get_file()
+    }
+
+    /// Whether `other` lies within this span, in the same file.
+    pub fn contains(&self, other: &Span) -> bool {
+        self.file() == other.file()
+            && self.start.get_cursor() <= other.start.get_cursor()
+            && other.end.get_cursor() <= self.end.get_cursor()
+    }
+
+    /// Whether both spans end on the same token.
+    pub fn ends_with(&self, other: &Span) -> bool {
+        self.file() == other.file() && self.end.get_cursor() == other.end.get_cursor()
+    }
+}
+
+/// A span pointed at besides the main one, with what it has to do with the diagnostic,
+/// e.g. "first given here".
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A whole line of source shown in a diagnostic. `start` is the offset of its first byte.
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLine {
+    pub number: usize,
+    pub start: usize,
+    pub text: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DiagnosticWindow {
     pub span: Span,
     pub snippet: String,
+    pub labels: Vec<Label>,
+    /// The lines `span` and the labels in its file are on, in order.
+    pub lines: Vec<SourceLine>,
+}
+
+impl DiagnosticWindow {
+    pub fn new(span: Span, snippet: String, lines: Vec<SourceLine>) -> Self {
+        DiagnosticWindow {
+            span,
+            snippet,
+            labels: vec![],
+            lines,
+        }
+    }
+
+    /// Line and column of `offset`, both counted from 1 and the column in characters.
+    /// `None` when the offset is on none of the lines the window shows.
+    pub fn position(&self, offset: usize) -> Option<(usize, usize)> {
+        let line = self.lines.iter().rev().find(|l| l.start <= offset)?;
+
+        if offset > line.start + line.text.len() {
+            return None;
+        }
+
+        let column = line
+            .text
+            .char_indices()
+            .take_while(|(i, _)| line.start + i < offset)
+            .count();
+
+        Some((line.number, column + 1))
+    }
+
+    /// Adds a secondary span, along with the lines it is on when it shares the file of the
+    /// main span.
+    pub fn add_label(&mut self, label: Label, lines: Vec<SourceLine>) {
+        if label.span.file() == self.span.file() {
+            for line in lines {
+                if let Err(at) = self.lines.binary_search_by_key(&line.number, |l| l.number) {
+                    self.lines.insert(at, line);
+                }
+            }
+        }
+
+        self.labels.push(label);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::diagnostics::catalog;
+
 use std::fmt;
 
 pub enum MessageKind {
@@ -18,6 +20,28 @@ pub enum Note {
     ExpectedIDorInteger(String),
     ExpectedShapeOrColor(String),
     ExpectedTypeofTuple(String),
+    UnknownObjectArgument(String, String),
+    DuplicateObjectArgument(String, String),
+    MixedObjectArguments(String),
+    TooManyObjectArguments(String, String),
+    ExpectedTopLevelItem(String),
+    ModuleNotFound(String),
+    IncludeCycle(String),
+    ConflictingInclude(String, String),
+    ExpectedEndOfInput(String, String),
+    UnknownObject(String),
+    UnusedObject(String),
+    EmptyShape(String),
+    UnplacedObject(String),
+    OverlappingObjects(String, String),
+    UnknownLint(String),
+    MalformedLintAttribute(String),
+    CellsOutsideGrid(String, String, String, String),
+    GridSizeOutOfLimits(String, String),
+    EmptyRange(String),
+    NestedTooDeeply(String),
+    NotAnObject(String, String),
+    NonPositiveStep(String),
 }
 
 impl fmt::Display for Note {
@@ -27,75 +51,137 @@ impl fmt::Display for Note {
 }
 
 impl Note {
-    pub fn stringify(&self) -> String {
+    /// The key of the note in a message catalog, and its arguments by name.
+    pub fn parts(&self) -> (&'static str, Vec<(&'static str, &str)>) {
         match self {
-            Note::ExpectedLeftInclusive(str1) => format!(
-                "Expected '(' or '[' for range inclusivity, but found '{}'.",
-                str1
+            Note::ExpectedLeftInclusive(found) => (
+                "note-expected-left-inclusive",
+                vec![("found", found.as_str())],
             ),
-            Note::ExpectedRightInclusive(str1) => format!(
-                "Expected ')' or ']' for range inclusivity, but found '{}'.",
-                str1
+            Note::ExpectedRightInclusive(found) => (
+                "note-expected-right-inclusive",
+                vec![("found", found.as_str())],
             ),
-            Note::ExpectedDataType(str1) => format!("Expected a data type, but found '{}'.", str1),
-            Note::ExpectedInteger(str1) => {
-                format!("Expected an integer constant, but found '{}'.", str1)
+            Note::ExpectedDataType(found) => {
+                ("note-expected-data-type", vec![("found", found.as_str())])
             }
-            Note::ExpectedIdentifier(str1) => {
-                format!("Expected an identifier, but found '{}'.", str1)
+            Note::ExpectedInteger(found) => {
+                ("note-expected-integer", vec![("found", found.as_str())])
             }
-            Note::ExpectedSomethingElse(str1, str2) => {
-                format!("Expected '{}', but found '{}'.", str1, str2)
+            Note::ExpectedIdentifier(found) => {
+                ("note-expected-identifier", vec![("found", found.as_str())])
             }
-            Note::ExpectedASTNode(str1, str2) => format!(
-                "Expected '{}' AST Node, but failed to parse Node with id: '{}'.",
-                str1, str2
+            Note::ExpectedSomethingElse(expected, found) => (
+                "note-expected-something-else",
+                vec![("expected", expected.as_str()), ("found", found.as_str())],
             ),
-            Note::ExpectedIDorInteger(str1) => format!(
-                "Expected an identifier or integer constant, but found '{}'.",
-                str1
+            Note::ExpectedASTNode(expected, node) => (
+                "note-expected-ast-node",
+                vec![("expected", expected.as_str()), ("node", node.as_str())],
             ),
-            Note::ExpectedStatement(str1) => {
-                format!("Expected a statement keyword, but found '{}'.", str1)
+            Note::ExpectedStatement(found) => {
+                ("note-expected-statement", vec![("found", found.as_str())])
             }
-            Note::ExpectedShapeOrColor(str1) => {
-                format!("Expected a shape or color keyword, but found '{}'.", str1)
+            Note::ExpectedIDorInteger(found) => (
+                "note-expected-id-or-integer",
+                vec![("found", found.as_str())],
+            ),
+            Note::ExpectedShapeOrColor(found) => (
+                "note-expected-shape-or-color",
+                vec![("found", found.as_str())],
+            ),
+            Note::ExpectedTypeofTuple(found) => (
+                "note-expected-typeof-tuple",
+                vec![("found", found.as_str())],
+            ),
+            Note::UnknownObjectArgument(object, argument) => (
+                "note-unknown-object-argument",
+                vec![("object", object.as_str()), ("argument", argument.as_str())],
+            ),
+            Note::DuplicateObjectArgument(object, argument) => (
+                "note-duplicate-object-argument",
+                vec![("object", object.as_str()), ("argument", argument.as_str())],
+            ),
+            Note::MixedObjectArguments(object) => (
+                "note-mixed-object-arguments",
+                vec![("object", object.as_str())],
+            ),
+            Note::TooManyObjectArguments(object, count) => (
+                "note-too-many-object-arguments",
+                vec![("object", object.as_str()), ("count", count.as_str())],
+            ),
+            Note::ExpectedTopLevelItem(found) => (
+                "note-expected-top-level-item",
+                vec![("found", found.as_str())],
+            ),
+            Note::ModuleNotFound(module) => {
+                ("note-module-not-found", vec![("module", module.as_str())])
+            }
+            Note::IncludeCycle(chain) => ("note-include-cycle", vec![("chain", chain.as_str())]),
+            Note::ConflictingInclude(name, module) => (
+                "note-conflicting-include",
+                vec![("name", name.as_str()), ("module", module.as_str())],
+            ),
+            Note::ExpectedEndOfInput(fragment, found) => (
+                "note-expected-end-of-input",
+                vec![("fragment", fragment.as_str()), ("found", found.as_str())],
+            ),
+            Note::UnknownObject(object) => {
+                ("note-unknown-object", vec![("object", object.as_str())])
+            }
+            Note::UnusedObject(object) => ("note-unused-object", vec![("object", object.as_str())]),
+            Note::EmptyShape(object) => ("note-empty-shape", vec![("object", object.as_str())]),
+            Note::UnplacedObject(object) => {
+                ("note-unplaced-object", vec![("object", object.as_str())])
+            }
+            Note::OverlappingObjects(object, other) => (
+                "note-overlapping-objects",
+                vec![("object", object.as_str()), ("other", other.as_str())],
+            ),
+            Note::UnknownLint(lint) => ("note-unknown-lint", vec![("lint", lint.as_str())]),
+            Note::MalformedLintAttribute(attribute) => (
+                "note-malformed-lint-attribute",
+                vec![("attribute", attribute.as_str())],
+            ),
+            Note::CellsOutsideGrid(object, count, size, cell) => (
+                "note-cells-outside-grid",
+                vec![
+                    ("object", object.as_str()),
+                    ("count", count.as_str()),
+                    ("size", size.as_str()),
+                    ("cell", cell.as_str()),
+                ],
+            ),
+            Note::GridSizeOutOfLimits(width, height) => (
+                "note-grid-size-out-of-limits",
+                vec![("width", width.as_str()), ("height", height.as_str())],
+            ),
+            Note::EmptyRange(range) => ("note-empty-range", vec![("range", range.as_str())]),
+            Note::NestedTooDeeply(limit) => {
+                ("note-nested-too-deeply", vec![("limit", limit.as_str())])
             }
-            Note::ExpectedTypeofTuple(str1) => {
-                format!("Expected a 'tuple' or 'object' type, but found '{}'.", str1)
+            Note::NotAnObject(name, what) => (
+                "note-not-an-object",
+                vec![("name", name.as_str()), ("what", what.as_str())],
+            ),
+            Note::NonPositiveStep(step) => {
+                ("note-non-positive-step", vec![("step", step.as_str())])
             }
         }
     }
 
+    pub fn stringify(&self) -> String {
+        let (key, args) = self.parts();
+
+        catalog::message(key, &args)
+    }
+
     pub fn args_required(&self) -> bool {
-        match self {
-            Note::ExpectedLeftInclusive(_) => true,
-            Note::ExpectedRightInclusive(_)
-            | Note::ExpectedDataType(_)
-            | Note::ExpectedInteger(_)
-            | Note::ExpectedIdentifier(_)
-            | Note::ExpectedSomethingElse(_, _)
-            | Note::ExpectedASTNode(_, _)
-            | Note::ExpectedIDorInteger(_)
-            | Note::ExpectedStatement(_)
-            | Note::ExpectedShapeOrColor(_)
-            | Note::ExpectedTypeofTuple(_) => true,
-        }
+        self.args_count() > 0
     }
 
     pub fn args_count(&self) -> usize {
-        match self {
-            Note::ExpectedLeftInclusive(_)
-            | Note::ExpectedRightInclusive(_)
-            | Note::ExpectedDataType(_)
-            | Note::ExpectedInteger(_)
-            | Note::ExpectedIDorInteger(_)
-            | Note::ExpectedStatement(_)
-            | Note::ExpectedShapeOrColor(_)
-            | Note::ExpectedTypeofTuple(_)
-            | Note::ExpectedIdentifier(_) => 1,
-            Note::ExpectedSomethingElse(_, _) | Note::ExpectedASTNode(_, _) => 2,
-        }
+        self.parts().1.len()
     }
 }
 
@@ -106,6 +192,27 @@ pub enum Help {
     DidYouMeanStmtKeyword,
     DidYouMeanShapeOrColor,
     DidYouMeanTupleorObject,
+    DidYouMeanOffsetArgument,
+    RemoveDuplicateArgument,
+    UseEitherPositionalOrNamed,
+    DidYouMeanTopLevelKeyword,
+    CheckModuleSearchPath,
+    BreakIncludeCycle,
+    RenameConflictingDeclaration,
+    RemoveTrailingInput,
+    DidYouMean(String),
+    DeclareOrIncludeObject,
+    RemoveOrPlaceObject,
+    CheckShapeRanges,
+    PlaceObjectInGrid,
+    MoveOverlappingObject,
+    KnownLints(String),
+    UseLintLevel,
+    MoveObjectIntoGrid,
+    KeepGridSizeInLimits,
+    SplitNestedExpression,
+    CallDeclaredObject,
+    UsePositiveStep,
 }
 
 impl fmt::Display for Help {
@@ -115,23 +222,41 @@ impl fmt::Display for Help {
 }
 
 impl Help {
-    pub fn stringify(&self) -> String {
+    /// The key of the help in a message catalog, and its arguments by name.
+    pub fn parts(&self) -> (&'static str, Vec<(&'static str, &str)>) {
         match self {
-            Help::ConsiderChangingToInclusive => {
-                "Consider changing to an inclusive range by using '[' or ']'.".to_string()
-            }
-            Help::DidYouMeanDataType => {
-                "Did you mean to use 'int', 'string', or 'object' as data type?".to_string()
-            }
-            Help::DidYouMeanStmtKeyword => {
-                "Did you mean to use a statement keyword like 'let', 'input', 'output', 'example', or 'solution'?".to_string()
-            }
-            Help::DidYouMeanShapeOrColor => {
-                "Did you mean to use the 'shape' or 'color' keywords?".to_string()
-            }
-            Help::DidYouMeanTupleorObject => {
-                "Did you mean to use a 'tuple' or an 'object' type? You can create tuples by using this syntax: <tuple> ::= '(' <int> ',' <int> ')' or use a declared object instead.".to_string()
-            }
+            Help::ConsiderChangingToInclusive => ("help-consider-changing-to-inclusive", vec![]),
+            Help::DidYouMeanDataType => ("help-did-you-mean-data-type", vec![]),
+            Help::DidYouMeanStmtKeyword => ("help-did-you-mean-stmt-keyword", vec![]),
+            Help::DidYouMeanShapeOrColor => ("help-did-you-mean-shape-or-color", vec![]),
+            Help::DidYouMeanTupleorObject => ("help-did-you-mean-tuple-or-object", vec![]),
+            Help::DidYouMeanOffsetArgument => ("help-did-you-mean-offset-argument", vec![]),
+            Help::RemoveDuplicateArgument => ("help-remove-duplicate-argument", vec![]),
+            Help::UseEitherPositionalOrNamed => ("help-use-either-positional-or-named", vec![]),
+            Help::DidYouMeanTopLevelKeyword => ("help-did-you-mean-top-level-keyword", vec![]),
+            Help::CheckModuleSearchPath => ("help-check-module-search-path", vec![]),
+            Help::BreakIncludeCycle => ("help-break-include-cycle", vec![]),
+            Help::RenameConflictingDeclaration => ("help-rename-conflicting-declaration", vec![]),
+            Help::RemoveTrailingInput => ("help-remove-trailing-input", vec![]),
+            Help::DidYouMean(name) => ("help-did-you-mean", vec![("name", name.as_str())]),
+            Help::DeclareOrIncludeObject => ("help-declare-or-include-object", vec![]),
+            Help::RemoveOrPlaceObject => ("help-remove-or-place-object", vec![]),
+            Help::CheckShapeRanges => ("help-check-shape-ranges", vec![]),
+            Help::PlaceObjectInGrid => ("help-place-object-in-grid", vec![]),
+            Help::MoveOverlappingObject => ("help-move-overlapping-object", vec![]),
+            Help::KnownLints(lints) => ("help-known-lints", vec![("lints", lints.as_str())]),
+            Help::UseLintLevel => ("help-use-lint-level", vec![]),
+            Help::MoveObjectIntoGrid => ("help-move-object-into-grid", vec![]),
+            Help::KeepGridSizeInLimits => ("help-keep-grid-size-in-limits", vec![]),
+            Help::SplitNestedExpression => ("help-split-nested-expression", vec![]),
+            Help::CallDeclaredObject => ("help-call-declared-object", vec![]),
+            Help::UsePositiveStep => ("help-use-positive-step", vec![]),
         }
     }
+
+    pub fn stringify(&self) -> String {
+        let (key, args) = self.parts();
+
+        catalog::message(key, &args)
+    }
 }
@@ -1,39 +1,56 @@
 pub mod builder;
+pub mod catalog;
 pub mod code;
 pub mod diagnostic;
+pub mod format;
 pub mod location;
 pub mod messages;
+pub mod render;
+pub mod source_map;
+pub mod suggestion;
 
 pub use builder::DiagnosticBuilder;
+pub use catalog::Catalog;
 pub use code::Code;
 pub use diagnostic::{create_diagnostic, Diagnostic, DiagnosticAccumulator};
-pub use location::{DiagnosticWindow, Span};
+pub use format::MessageFormat;
+pub use location::{DiagnosticWindow, FileId, Label, SourceLine, Span};
 pub use messages::{Help, MessageKind, Note};
+pub use source_map::{SourceFile, SourceMap};
+pub use suggestion::{
+    apply_suggestions, did_you_mean, edit_distance, similar_names, Applicability, Suggestion,
+};
 
 pub mod macros {
     #[macro_export]
+    /// Builds a diagnostic and records it, unless the input only ran out early. Builder
+    /// calls such as `with_label(span, "...")` may follow the span, after a `;`.
     macro_rules! diag {
-        ($pass:ident, $code:ident, $note:ident($($args:expr), *), $help:ident, $tokens:expr, $diag:expr, $span:expr) => {{
-            if let Some(dgn) = $crate::diagnostics::DiagnosticBuilder::build(
+        ($pass:ident, $code:ident, $note:ident($($args:expr), *), $help:ident, $tokens:expr, $diag:expr, $span:expr $(; $($method:ident($($margs:expr),*)),+)?) => {{
+            let dgn = $crate::diagnostics::DiagnosticBuilder::build(
                     $crate::config::CompilerPass::$pass,
                     $crate::diagnostics::Code::$code,
                 )
                 .with_note(Some($crate::diagnostics::Note::$note($($args),*)))
                 .with_help(Some($crate::diagnostics::Help::$help))
-                .emit($tokens, $span)
-            {
+                $($(.$method($($margs),*))+)?
+                .emit($tokens, $span);
+
+            if !$tokens.is_truncated() {
                 $diag.add_diag(dgn)
             }
         }};
 
-        ($pass:ident, $code:ident, $note:ident($($args:expr), *), $tokens:expr, $diag:expr, $span:expr) => {{
-            if let Some(dgn) = $crate::diagnostics::DiagnosticBuilder::build(
+        ($pass:ident, $code:ident, $note:ident($($args:expr), *), $tokens:expr, $diag:expr, $span:expr $(; $($method:ident($($margs:expr),*)),+)?) => {{
+            let dgn = $crate::diagnostics::DiagnosticBuilder::build(
                     $crate::config::CompilerPass::$pass,
                     $crate::diagnostics::Code::$code,
                 )
                 .with_note(Some($crate::diagnostics::Note::$note($($args),*)))
-                .emit($tokens, $span)
-            {
+                $($(.$method($($margs),*))+)?
+                .emit($tokens, $span);
+
+            if !$tokens.is_truncated() {
                 $diag.add_diag(dgn)
             }
         }};
@@ -0,0 +1,495 @@
+use colored::*;
+
+use crate::config::CompilerPass;
+use crate::diagnostics::code::DiagnosticLevel;
+use crate::diagnostics::{Diagnostic, DiagnosticWindow, SourceLine, SourceMap, Span, Suggestion};
+
+const TAB_WIDTH: usize = 4;
+
+// Lines of a multi-line span shown after its first line and before its last one, the
+// ones in between are left out
+const SPAN_CONTEXT_LINES: usize = 2;
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+// Applies a style only when colors are on, so that logs get the same layout without the
+// escape codes.
+struct Painter {
+    color: bool,
+}
+
+// A span to underline, located in the window's lines. Columns are display columns and
+// `end_col` is exclusive.
+struct Mark<'d> {
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+    message: &'d str,
+    primary: bool,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl Painter {
+    fn paint(&self, text: &str, style: fn(&str) -> ColoredString) -> String {
+        if self.color {
+            style(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn level(&self, level: DiagnosticLevel, text: &str) -> String {
+        let style: fn(&str) -> ColoredString = match level {
+            DiagnosticLevel::Internal => |s| s.magenta().bold(),
+            DiagnosticLevel::Error => |s| s.red().bold(),
+            DiagnosticLevel::Warning => |s| s.yellow().bold(),
+            DiagnosticLevel::Note => |s| s.green().bold(),
+            DiagnosticLevel::Help => |s| s.cyan().bold(),
+        };
+
+        self.paint(text, style)
+    }
+
+    fn gutter(&self, text: &str) -> String {
+        self.paint(text, |s| s.blue().bold())
+    }
+
+    fn mark(&self, level: DiagnosticLevel, primary: bool, text: &str) -> String {
+        if primary {
+            self.level(level, text)
+        } else {
+            self.gutter(text)
+        }
+    }
+}
+
+impl Mark<'_> {
+    fn is_multiline(&self) -> bool {
+        self.start_line != self.end_line
+    }
+
+    fn symbol(&self) -> char {
+        if self.primary {
+            '^'
+        } else {
+            '-'
+        }
+    }
+}
+
+// ==================================================================================== //
+// Rendering                                                                            //
+// ==================================================================================== //
+
+/// Lays `diagnostic` out the way rustc does: a header, the lines it is about with their
+/// numbers in a gutter, its spans underlined, and its note and help at the bottom. With a
+/// `source_map`, locations start with the path of their file, as in `task.erm:12:5`.
+pub fn render(diagnostic: &Diagnostic, source_map: Option<&SourceMap>, color: bool) -> String {
+    let painter = Painter { color };
+    let window = &diagnostic.window;
+    let level = diagnostic.level;
+
+    let mut out = format!(
+        "{}: {}\n",
+        painter.level(
+            level,
+            &format!("{}[{}]", level.to_string().to_lowercase(), diagnostic.code)
+        ),
+        painter.paint(&diagnostic.message, |s| s.bold())
+    );
+
+    let width = window
+        .lines
+        .last()
+        .map_or(1, |line| line.number.to_string().len());
+    let pad = " ".repeat(width);
+
+    let show_source = diagnostic.pass != CompilerPass::Internal && !window.lines.is_empty();
+
+    if show_source {
+        let (line, column) = window
+            .position(window.span.start.get_cursor())
+            .unwrap_or((window.lines[0].number, 1));
+
+        let location = match source_map.and_then(|map| map.get(window.span.file())) {
+            Some(file) => format!("{}:{}:{}", file.path.display(), line, column),
+            None => format!("{}:{}", line, column),
+        };
+
+        out.push_str(&format!("{}{} {}\n", pad, painter.gutter("-->"), location));
+        out.push_str(&format!("{} {}\n", pad, painter.gutter("|")));
+
+        let mut marks = vec![mark(&window.lines, window.span, "", true)];
+        for label in &window.labels {
+            if label.span.file() == window.span.file() {
+                marks.push(mark(&window.lines, label.span, &label.message, false));
+            }
+        }
+
+        for row in render_lines(&window.lines, &marks, &painter, level) {
+            out.push_str(&row);
+        }
+
+        out.push_str(&format!("{} {}\n", pad, painter.gutter("|")));
+    }
+
+    let mut footer = vec![("pass", diagnostic.pass.to_string())];
+
+    for label in &window.labels {
+        if !show_source || label.span.file() != window.span.file() {
+            // Lines of other files are not in the window, but the source map still knows
+            // where the label is
+            let message = match source_map.and_then(|map| map.location(label.span)) {
+                Some(location) => format!("{} at {}", label.message, location),
+                None => label.message.clone(),
+            };
+
+            footer.push(("note", message));
+        }
+    }
+
+    footer.push(("note", diagnostic.note.clone()));
+    footer.push(("help", diagnostic.help.clone()));
+
+    for suggestion in &diagnostic.suggestions {
+        let kind = if suggestion.is_machine_applicable() {
+            "fix"
+        } else {
+            "suggestion"
+        };
+
+        footer.push((kind, describe(window, suggestion)));
+    }
+
+    for (kind, text) in footer {
+        if !text.is_empty() {
+            out.push_str(&format!(
+                "{} {} {} {}\n",
+                pad,
+                painter.gutter("="),
+                painter.paint(&format!("{}:", kind), |s| s.bold()),
+                text
+            ));
+        }
+    }
+
+    out
+}
+
+fn render_lines(
+    lines: &[SourceLine],
+    marks: &[Mark],
+    painter: &Painter,
+    level: DiagnosticLevel,
+) -> Vec<String> {
+    let width = lines.last().map_or(1, |l| l.number.to_string().len());
+    let multiline: Vec<&Mark> = marks.iter().filter(|m| m.is_multiline()).collect();
+    let has_margin = !multiline.is_empty();
+
+    // Gutter of a row with no line number, `margin` being drawn left of the text
+    let blank = |margin: &str| -> String {
+        let margin = if has_margin { margin } else { "" };
+        format!("{} {} {}", " ".repeat(width), painter.gutter("|"), margin)
+    };
+
+    let mut rows: Vec<String> = vec![];
+    let mut elided = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        if is_elided(marks, i) {
+            if !elided {
+                rows.push(format!("{}\n", painter.gutter("...")));
+            }
+            elided = true;
+            continue;
+        }
+
+        if i > 0 && line.number > lines[i - 1].number + 1 && !elided {
+            rows.push(format!("{}\n", painter.gutter("...")));
+        }
+        elided = false;
+
+        let text = expand_tabs(&line.text);
+        let indent = text.len() - text.trim_start().len();
+
+        // Left margin of the line itself: a span opening at its start, or going through it
+        let mut margin = " ".to_string();
+        for m in &multiline {
+            if m.start_line == i && m.start_col <= indent {
+                margin = painter.mark(level, m.primary, "/");
+            } else if m.start_line < i && i <= m.end_line {
+                margin = painter.mark(level, m.primary, "|");
+            }
+        }
+
+        rows.push(format!(
+            "{} {} {}{}\n",
+            painter.gutter(&format!("{:>width$}", line.number)),
+            painter.gutter("|"),
+            if has_margin {
+                format!("{} ", margin)
+            } else {
+                String::new()
+            },
+            text
+        ));
+
+        // A span opening further into its first line is joined to the margin from below
+        for m in &multiline {
+            if m.start_line == i && m.start_col > indent {
+                let underline = format!("{}{}", "_".repeat(m.start_col + 1), m.symbol());
+                rows.push(format!(
+                    "{}\n",
+                    blank(&painter.mark(level, m.primary, &format!(" {}", underline)))
+                ));
+            }
+        }
+
+        let single: Vec<&Mark> = marks
+            .iter()
+            .filter(|m| !m.is_multiline() && m.start_line == i)
+            .collect();
+
+        if !single.is_empty() {
+            let through = margin_through(&multiline, i, painter, level);
+            for row in underline_rows(&single, painter, level) {
+                rows.push(format!("{}{}\n", blank(&through), row));
+            }
+        }
+
+        for m in &multiline {
+            if m.end_line == i {
+                let column = m.end_col.saturating_sub(1);
+                let underline = format!("|{}{}", "_".repeat(column + 1), m.symbol());
+                let mut row = painter.mark(level, m.primary, &underline);
+
+                if !m.message.is_empty() {
+                    row.push(' ');
+                    row.push_str(&painter.mark(level, m.primary, m.message));
+                }
+
+                // The margin is part of the underline here, so no blank margin before it
+                rows.push(format!(
+                    "{} {} {}\n",
+                    " ".repeat(width),
+                    painter.gutter("|"),
+                    row
+                ));
+            }
+        }
+    }
+
+    rows
+}
+
+// Whether line `i` is deep inside a long multi-line span, with no span starting or ending
+// on it. Only spans longer than the context around both ends lose lines, at least two.
+fn is_elided(marks: &[Mark], i: usize) -> bool {
+    let inside = marks.iter().any(|m| {
+        m.end_line - m.start_line > 2 * SPAN_CONTEXT_LINES + 2
+            && i > m.start_line + SPAN_CONTEXT_LINES
+            && i + SPAN_CONTEXT_LINES < m.end_line
+    });
+
+    inside && !marks.iter().any(|m| m.start_line == i || m.end_line == i)
+}
+
+// Margin of the underline rows below line `i`: a bar while a span is still open
+fn margin_through(
+    multiline: &[&Mark],
+    i: usize,
+    painter: &Painter,
+    level: DiagnosticLevel,
+) -> String {
+    for m in multiline {
+        if m.start_line <= i && i < m.end_line {
+            return format!("{} ", painter.mark(level, m.primary, "|"));
+        }
+    }
+
+    "  ".to_string()
+}
+
+// Underlines the spans on one line. The rightmost message goes right after its underline,
+// the others hang below their span's start.
+fn underline_rows(marks: &[&Mark], painter: &Painter, level: DiagnosticLevel) -> Vec<String> {
+    let mut marks: Vec<&Mark> = marks.to_vec();
+    marks.sort_by_key(|m| m.start_col);
+
+    let end = marks
+        .iter()
+        .map(|m| m.end_col.max(m.start_col + 1))
+        .max()
+        .unwrap_or(0);
+
+    // Which mark, if any, owns each column. Primary spans win over secondary ones.
+    let mut owners: Vec<Option<&Mark>> = vec![None; end];
+    for m in marks
+        .iter()
+        .filter(|m| !m.primary)
+        .chain(marks.iter().filter(|m| m.primary))
+    {
+        for owner in owners
+            .iter_mut()
+            .take(m.end_col.max(m.start_col + 1))
+            .skip(m.start_col)
+        {
+            *owner = Some(m);
+        }
+    }
+
+    let mut first = String::new();
+    let mut column = 0;
+    while column < end {
+        let owner = owners[column];
+        let run = owners[column..]
+            .iter()
+            .take_while(|o| o.map(|m| m.primary) == owner.map(|m| m.primary))
+            .count();
+
+        match owner {
+            Some(m) => {
+                let symbols = m.symbol().to_string().repeat(run);
+                first.push_str(&painter.mark(level, m.primary, &symbols));
+            }
+            None => first.push_str(&" ".repeat(run)),
+        }
+
+        column += run;
+    }
+
+    let (rightmost, rest) = match marks.split_last() {
+        Some(split) => split,
+        None => return vec![first],
+    };
+
+    if !rightmost.message.is_empty() {
+        first.push(' ');
+        first.push_str(&painter.mark(level, rightmost.primary, rightmost.message));
+    }
+
+    let mut hanging: Vec<&Mark> = rest
+        .iter()
+        .filter(|m| !m.message.is_empty())
+        .copied()
+        .collect();
+
+    let mut rows = vec![first];
+
+    while let Some(m) = hanging.pop() {
+        // A bar under the start of every span whose message is still to come
+        let bars = |upto: usize| -> String {
+            let mut row = String::new();
+
+            for other in hanging.iter().chain(std::iter::once(&m)) {
+                if other.start_col < upto {
+                    let gap = other.start_col.saturating_sub(visible_len(&row));
+                    row.push_str(&" ".repeat(gap));
+                    row.push_str(&painter.mark(level, other.primary, "|"));
+                }
+            }
+
+            row
+        };
+
+        rows.push(bars(m.start_col + 1));
+
+        let mut message = bars(m.start_col);
+        let gap = m.start_col.saturating_sub(visible_len(&message));
+        message.push_str(&" ".repeat(gap));
+        message.push_str(&painter.mark(level, m.primary, m.message));
+        rows.push(message);
+    }
+
+    rows
+}
+
+// What `suggestion` does to the source, e.g. "insert `;` at 2:17"
+fn describe(window: &DiagnosticWindow, suggestion: &Suggestion) -> String {
+    let start = suggestion.span.start.get_cursor();
+    let end = suggestion.span.end.get_cursor().max(start);
+
+    let at = match window.position(start) {
+        Some((line, column)) if suggestion.span.file() == window.span.file() => {
+            format!(" at {}:{}", line, column)
+        }
+        _ => String::new(),
+    };
+
+    // The text replaced, when it is on one of the window's lines
+    let old = window
+        .lines
+        .iter()
+        .find(|l| l.start <= start && end <= l.start + l.text.len())
+        .and_then(|l| l.text.get(start - l.start..end - l.start));
+
+    match (start == end, suggestion.replacement.is_empty(), old) {
+        (true, _, _) => format!("insert `{}`{}", suggestion.replacement, at),
+        (false, true, Some(old)) => format!("remove `{}`{}", old, at),
+        (false, false, Some(old)) => {
+            format!("replace `{}`{} with `{}`", old, at, suggestion.replacement)
+        }
+        (false, true, None) => format!("remove the text{}", at),
+        (false, false, None) => format!("replace the text{} with `{}`", at, suggestion.replacement),
+    }
+}
+
+fn mark<'d>(lines: &[SourceLine], span: Span, message: &'d str, primary: bool) -> Mark<'d> {
+    let (start_line, start_col) = locate(lines, span.start.get_cursor());
+    let (end_line, end_col) = locate(lines, span.end.get_cursor().max(span.start.get_cursor()));
+
+    Mark {
+        start_line,
+        start_col,
+        end_line,
+        end_col,
+        message,
+        primary,
+    }
+}
+
+// The index of the line `offset` is on and its display column there
+fn locate(lines: &[SourceLine], offset: usize) -> (usize, usize) {
+    let index = lines.iter().rposition(|l| l.start <= offset).unwrap_or(0);
+    let line = &lines[index];
+
+    let mut bytes = offset.saturating_sub(line.start).min(line.text.len());
+    while !line.text.is_char_boundary(bytes) {
+        bytes -= 1;
+    }
+
+    (index, display_width(&line.text[..bytes]))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+// Length of `text` as shown, leaving out the escape codes colors add
+fn visible_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        match c {
+            '\u{1b}' => escaped = true,
+            'm' if escaped => escaped = false,
+            _ if escaped => {}
+            _ => len += 1,
+        }
+    }
+
+    len
+}
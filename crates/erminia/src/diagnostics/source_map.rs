@@ -0,0 +1,92 @@
+use crate::diagnostics::{FileId, Span};
+
+use std::ops::Index;
+use std::path::{Path, PathBuf};
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub id: FileId,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Every source file diagnostics may point into, indexed by `FileId`.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl SourceFile {
+    /// Line and column of `offset`, both counted from 1 and the column in characters.
+    /// `None` when the offset is past the end of the file or inside a character.
+    pub fn position(&self, offset: usize) -> Option<(usize, usize)> {
+        let before = self.content.get(..offset)?;
+
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        Some((line, column))
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    /// Adds a file and hands back the id its spans are to carry.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, content: String) -> FileId {
+        let id = FileId(self.files.len());
+
+        self.files.push(SourceFile {
+            id,
+            path: path.as_ref().to_path_buf(),
+            content,
+        });
+
+        id
+    }
+
+    pub fn get(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file.0)
+    }
+
+    /// Where `span` starts, as `path:line:col`, or just the path when the offset is not in
+    /// the file.
+    pub fn location(&self, span: Span) -> Option<String> {
+        let file = self.get(span.file())?;
+
+        Some(match file.position(span.start.get_cursor()) {
+            Some((line, column)) => format!("{}:{}:{}", file.path.display(), line, column),
+            None => file.path.display().to_string(),
+        })
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl Index<FileId> for SourceMap {
+    type Output = SourceFile;
+
+    fn index(&self, file: FileId) -> &SourceFile {
+        &self.files[file.0]
+    }
+}
@@ -0,0 +1,179 @@
+use crate::diagnostics::Span;
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+/// How sure a suggestion is to fix the error it comes with.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Applicability {
+    /// The replacement is what was meant, and can be applied without a look.
+    MachineApplicable,
+    /// The replacement fixes the error, but may not be what was meant.
+    #[default]
+    MaybeIncorrect,
+}
+
+/// A change to the source that fixes a diagnostic: `span` replaced by `replacement`. An
+/// empty span inserts, an empty replacement deletes.
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl Applicability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "machine-applicable",
+            Applicability::MaybeIncorrect => "maybe-incorrect",
+        }
+    }
+}
+
+impl Suggestion {
+    pub fn new(span: Span, replacement: &str, applicability: Applicability) -> Self {
+        Suggestion {
+            span,
+            replacement: replacement.to_string(),
+            applicability,
+        }
+    }
+
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
+}
+
+/// Applies `suggestions` to `source`, which all of them point into. A suggestion that
+/// overlaps one applied before it is left out, as the two cannot both hold. Returns the
+/// fixed source and how many suggestions went into it.
+pub fn apply_suggestions(source: &str, suggestions: &[Suggestion]) -> (String, usize) {
+    let mut sorted: Vec<&Suggestion> = suggestions.iter().collect();
+    sorted.sort_by_key(|s| (s.span.start.get_cursor(), s.span.end.get_cursor()));
+    sorted.dedup_by(|a, b| a.span == b.span && a.replacement == b.replacement);
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut copied = 0;
+    let mut applied = 0;
+
+    for suggestion in sorted {
+        let start = suggestion.span.start.get_cursor();
+        let end = suggestion.span.end.get_cursor().max(start);
+
+        if start < copied || end > source.len() {
+            continue;
+        }
+
+        fixed.push_str(&source[copied..start]);
+        fixed.push_str(&suggestion.replacement);
+
+        copied = end;
+        applied += 1;
+    }
+
+    fixed.push_str(&source[copied..]);
+
+    (fixed, applied)
+}
+
+/// Edit distance between `a` and `b`: the fewest insertions, deletions, substitutions and
+/// swaps of two neighbouring characters that turn one into the other. Swaps count as one
+/// edit since `shpae` is as likely a typo as `shapr`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Rows i - 2, i - 1 and i of the distance table
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The `candidates` close enough to `name` to be what was meant, closest first. A name
+/// differing only in case is closest, otherwise up to one edit per three characters is
+/// allowed. Names that are not words, e.g. `;`, have no similar names.
+pub fn similar_names<'c, S: AsRef<str>>(name: &str, candidates: &'c [S]) -> Vec<&'c str> {
+    ranked_names(name, candidates)
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// The closest of `similar_names`, if any.
+pub fn did_you_mean<'c, S: AsRef<str>>(name: &str, candidates: &'c [S]) -> Option<&'c str> {
+    closest_name(name, candidates).map(|(candidate, _)| candidate)
+}
+
+/// The closest of `similar_names`, if any, and whether it is safe to replace `name` with:
+/// no other one is as close, and it differs from `name` in more than case. Names are case
+/// sensitive, so `b` for `B` may just as well be another name that was meant.
+pub(crate) fn closest_name<'c, S: AsRef<str>>(
+    name: &str,
+    candidates: &'c [S],
+) -> Option<(&'c str, bool)> {
+    let ranked = ranked_names(name, candidates);
+    let (distance, candidate) = *ranked.first()?;
+
+    let unique = ranked.get(1).is_none_or(|(next, _)| *next > distance);
+
+    Some((candidate, unique && !candidate.eq_ignore_ascii_case(name)))
+}
+
+// The similar names paired with how far each is from `name`, closest first
+fn ranked_names<'c, S: AsRef<str>>(name: &str, candidates: &'c [S]) -> Vec<(usize, &'c str)> {
+    let is_word =
+        |text: &str| !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    if !is_word(name) {
+        return vec![];
+    }
+
+    let length = name.chars().count();
+    let max_distance = length.max(3) / 3;
+
+    let mut ranked: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|candidate| *candidate != name && is_word(candidate))
+        .filter_map(|candidate| {
+            if candidate.eq_ignore_ascii_case(name) {
+                return Some((0, candidate));
+            }
+
+            let distance = edit_distance(name, candidate);
+            (distance <= max_distance && distance < length).then_some((distance, candidate))
+        })
+        .collect();
+
+    ranked.sort_by_key(|(distance, _)| *distance);
+    ranked.dedup_by(|a, b| a.1 == b.1);
+
+    ranked
+}
@@ -7,18 +7,29 @@ pub enum ASTError {
     ExpectedKeyWordError,
     ExpectedIdentifierError,
     ExpectedIntegerConstError,
+    #[from]
+    Message(String),
 }
 
 pub type ASTResult<T> = core::result::Result<T, ASTError>;
 
 impl std::fmt::Display for ASTError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        match self {
+            ASTError::Message(message) => write!(f, "{message}"),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
 impl std::error::Error for ASTError {}
 
+impl From<&str> for ASTError {
+    fn from(value: &str) -> Self {
+        ASTError::Message(value.to_string())
+    }
+}
+
 impl From<LexerError> for ASTError {
     fn from(value: LexerError) -> Self {
         #[allow(clippy::match_single_binding)]
@@ -0,0 +1,22 @@
+use derive_more::From;
+use std::path::PathBuf;
+
+#[derive(Debug, From)]
+pub enum LoaderError {
+    FileNotFound(PathBuf),
+    #[from]
+    IoError(std::io::Error),
+}
+
+pub type LoaderResult<T> = core::result::Result<T, LoaderError>;
+
+impl std::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::FileNotFound(path) => write!(f, "file not found: {}", path.display()),
+            LoaderError::IoError(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
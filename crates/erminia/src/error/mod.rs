@@ -1,3 +1,4 @@
 pub mod ast_error;
 pub mod lexer_error;
+pub mod loader_error;
 pub mod parser_error;
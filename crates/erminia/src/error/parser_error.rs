@@ -1,72 +1,32 @@
-use crate::error::lexer_error::LexerError;
-use crate::lexer::token::TokenKind;
-// use derive_more::From;
+use crate::diagnostics::Diagnostic;
 
+/// Why parsing failed altogether. Syntax errors the parser recovers from are not errors in
+/// this sense, they are reported as diagnostics next to the tree that was still built.
 #[derive(Debug)]
-pub struct ParserErrorInfo {
-    _expected: TokenKind,
-    _actual: TokenKind,
-}
-
-impl Default for ParserErrorInfo {
-    fn default() -> Self {
-        ParserErrorInfo {
-            _expected: TokenKind::START,
-            _actual: TokenKind::EOF,
-        }
-    }
-}
-
-impl ParserErrorInfo {
-    pub fn new(expected: TokenKind, actual: TokenKind) -> Self {
-        ParserErrorInfo {
-            _expected: expected,
-            _actual: actual,
-        }
-    }
-}
-
-#[derive(Debug /*, From*/)]
 pub enum ParserError {
-    ExpectedLeftInclusivity(ParserErrorInfo),
-    ExpectedRightInclusivity(ParserErrorInfo),
-    ParserError(ParserErrorInfo),
-    ExpectedKeyWordError(ParserErrorInfo),
-    ExpectedIdentifierError(ParserErrorInfo),
-    ExpectedIntegerConstError(ParserErrorInfo),
-    IoError(std::io::Error, ParserErrorInfo),
+    /// Parsing could not go on, the internal diagnostics among these say why, e.g. input
+    /// nested deeper than the parser goes.
+    Fatal(Vec<Diagnostic>),
 }
 
 pub type ParserResult<T> = core::result::Result<T, ParserError>;
 
-impl Default for ParserError {
-    fn default() -> Self {
-        ParserError::ParserError(ParserErrorInfo::default())
+impl ParserError {
+    /// The diagnostics saying why parsing failed, rendered as by `Diagnostic::render`.
+    pub fn render(&self, color: bool) -> String {
+        match self {
+            ParserError::Fatal(diagnostics) => diagnostics
+                .iter()
+                .map(|d| format!("{}\n", d.render(color)))
+                .collect(),
+        }
     }
 }
 
 impl std::fmt::Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        write!(f, "{}", self.render(false))
     }
 }
 
 impl std::error::Error for ParserError {}
-
-impl From<LexerError> for ParserError {
-    fn from(value: LexerError) -> Self {
-        #[allow(clippy::match_single_binding)]
-        match value {
-            _ => ParserError::default(),
-        }
-    }
-}
-
-impl From<std::io::Error> for ParserError {
-    fn from(value: std::io::Error) -> Self {
-        #[allow(clippy::match_single_binding)]
-        match value {
-            _ => ParserError::default(),
-        }
-    }
-}
@@ -1,11 +1,15 @@
 use std::fmt;
 use std::str::FromStr;
 
+use crate::ast::node::{NodeId, NodeIdAllocator};
 use crate::diagnostics::location::*;
 use crate::lexer::token::*;
 
-static KEYWORDS: [&str; 10] = [
+pub static KEYWORDS: [&str; 18] = [
     "def",
+    "func",
+    "include",
+    "use",
     "let",
     "object",
     "superobject",
@@ -15,8 +19,18 @@ static KEYWORDS: [&str; 10] = [
     "solution",
     "input",
     "output",
+    "int",
+    "string",
+    "void",
+    "by",
+    "U",
 ];
 
+/// How deep parentheses and unary operators may nest, and how many binary operators may
+/// chain, before the parser gives up, well before it or the passes walking the tree would
+/// run out of stack.
+pub const MAX_NESTING: usize = 128;
+
 // static OPERATORS: [&str; 26] = [
 //     "+", "-", "*", "/", "//", "%", "<<", ">>", "<", ">", ".", "!", "!=", "=", "(", ")", "[", "]",
 //     "{", "}", ",", ";", ":", "..", "(*", "*)",
@@ -28,6 +42,7 @@ static KEYWORDS: [&str; 10] = [
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PositionalOffset {
+    file: FileId,
     pos: usize,
     cursor: usize,
     line: usize,
@@ -36,6 +51,7 @@ pub struct PositionalOffset {
 impl Default for PositionalOffset {
     fn default() -> Self {
         PositionalOffset {
+            file: FileId::default(),
             pos: 0,
             cursor: 1,
             line: 1,
@@ -99,6 +115,20 @@ impl PositionalOffset {
     pub fn get_line(&self) -> usize {
         self.line
     }
+
+    pub fn get_file(&self) -> FileId {
+        self.file
+    }
+
+    /// The position `bytes` further along the same line.
+    pub fn shifted(&self, bytes: usize) -> PositionalOffset {
+        let mut pos = *self;
+
+        pos.increment_pos(bytes);
+        pos.increment_cursor(bytes);
+
+        pos
+    }
 }
 
 impl fmt::Display for PositionalOffset {
@@ -111,6 +141,19 @@ impl fmt::Display for PositionalOffset {
     }
 }
 
+// ==================================================================================== //
+// Attribute Struct                                                                     //
+// ==================================================================================== //
+
+/// An attribute, `#[...]` for what follows it or `#![...]` for the whole file. The lexer
+/// skips attributes like whitespace, leaving their meaning to whoever asks for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attribute {
+    pub span: Span,
+    /// Where the token after the attribute starts.
+    pub next: PositionalOffset,
+}
+
 // ==================================================================================== //
 // Lexer Struct                                                                         //
 // ==================================================================================== //
@@ -120,8 +163,16 @@ pub struct Lexer<'input> {
     content: &'input str,
     start: PositionalOffset,
     previous: PositionalOffset,
+    last: PositionalOffset,
     pub token: Token<'input>,
     poisoned: bool,
+    sync: Vec<&'static [TokenKind]>,
+    node_ids: NodeIdAllocator,
+    partial: bool,
+    expected: Vec<TokenKind>,
+    ended_early: bool,
+    nesting: usize,
+    given_up: bool,
 }
 
 impl<'input> Lexer<'input> {
@@ -130,8 +181,40 @@ impl<'input> Lexer<'input> {
             content,
             start: PositionalOffset::default(),
             previous: PositionalOffset::default(),
+            last: PositionalOffset::default(),
+            token: Token::default(),
+            poisoned: false,
+            sync: vec![],
+            node_ids: NodeIdAllocator::new(),
+            partial: false,
+            expected: vec![],
+            ended_early: false,
+            nesting: 0,
+            given_up: false,
+        }
+    }
+
+    /// Like `new`, but every position handed out belongs to `file`.
+    pub fn with_file(content: &'input str, file: FileId) -> Lexer<'input> {
+        let start = PositionalOffset {
+            file,
+            ..PositionalOffset::default()
+        };
+
+        Lexer {
+            content,
+            start,
+            previous: start,
+            last: start,
             token: Token::default(),
             poisoned: false,
+            sync: vec![],
+            node_ids: NodeIdAllocator::new(),
+            partial: false,
+            expected: vec![],
+            ended_early: false,
+            nesting: 0,
+            given_up: false,
         }
     }
 
@@ -148,6 +231,7 @@ impl<'input> Lexer<'input> {
 
         let token = Token::new(kind, lexeme, start_pos.get_line(), start_pos.get_cursor());
 
+        self.last = self.start;
         self.start = end_pos;
         self.previous = start_pos;
 
@@ -188,7 +272,27 @@ impl<'input> Lexer<'input> {
         (first, second, first_end_pos, second_end_pos)
     }
 
-    pub fn lex_with_separate_pass(&mut self) -> Vec<Token<'_>> {
+    pub fn lookahead_kinds(&self) -> impl Iterator<Item = TokenKind> + '_ {
+        let mut next_start = self.start;
+        let mut finished = false;
+
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+
+            let start_pos = trim_starting_whitespace(self.content, next_start);
+
+            let (kind, end_pos) = get_next_token_kind(self.content, start_pos);
+
+            next_start = end_pos;
+            finished = kind == TokenKind::EOF;
+
+            Some(kind)
+        })
+    }
+
+    pub fn lex_with_separate_pass(&mut self) -> Vec<Token<'input>> {
         let mut tokens: Vec<Token> = Vec::new();
 
         loop {
@@ -218,21 +322,120 @@ impl<'input> Lexer<'input> {
         self.previous
     }
 
+    /// End of the last token consumed, which is where a node built from it ends.
+    pub fn get_last_position(&self) -> PositionalOffset {
+        self.last
+    }
+
+    /// Every attribute skipped between tokens, in source order, whatever position the
+    /// lexer is at.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        let mut attributes: Vec<Attribute> = vec![];
+        let mut pending: Vec<Span> = vec![];
+
+        let mut pos = PositionalOffset {
+            file: self.start.file,
+            ..PositionalOffset::default()
+        };
+
+        loop {
+            pos = trim_whitespace(self.content, pos);
+
+            if let Some(length) = attribute_length(slice_from_position(self.content, pos)) {
+                let end = pos.shifted(length);
+                pending.push(Span::new(pos, end));
+                pos = end;
+                continue;
+            }
+
+            attributes.extend(pending.drain(..).map(|span| Attribute { span, next: pos }));
+
+            let (kind, end) = get_next_token_kind(self.content, pos);
+
+            if kind == TokenKind::EOF {
+                break;
+            }
+
+            pos = end;
+        }
+
+        attributes
+    }
+
     pub fn get_snippet(&self, span: Span) -> &str {
         self._return_content(span.start, span.end)
     }
 
+    /// The whole lines `span` is on, numbered from 1.
+    pub fn get_lines(&self, span: Span) -> Vec<SourceLine> {
+        let end = span.end.pos.max(span.start.pos).min(self.content.len());
+
+        let mut start = self.content[..span.start.pos.min(end)]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let mut number = self.content[..start].matches('\n').count() + 1;
+
+        let mut lines = vec![];
+
+        loop {
+            let text = self.content[start..].lines().next().unwrap_or("");
+
+            lines.push(SourceLine {
+                number,
+                start,
+                text: text.to_string(),
+            });
+
+            match self.content[start..].find('\n') {
+                Some(i) if start + i + 1 < end => {
+                    start += i + 1;
+                    number += 1;
+                }
+                _ => break,
+            }
+        }
+
+        lines
+    }
+
     pub fn get_extended_snippet(&self, span: Span, before: u8, after: u8) -> &str {
         &self.content[span.start.pos - before as usize..span.end.pos + after as usize]
     }
 
-    pub fn loop_to_kind(&mut self, kind: TokenKind) {
-        while self.is_poisoned()
-            && self.token.get_kind() != kind
-            && self.token.get_kind() != TokenKind::EOF
-        {
-            self.advance();
+    /// Makes the tokens of `set` places where error recovery may stop, for as long as the
+    /// production that pushed it is being parsed.
+    pub fn push_sync(&mut self, set: &'static [TokenKind]) {
+        self.sync.push(set);
+    }
+
+    pub fn pop_sync(&mut self) {
+        self.sync.pop();
+    }
+
+    /// Goes one level deeper into nested expressions. False, leaving the level as it was,
+    /// once that would go past `MAX_NESTING`.
+    pub fn nest(&mut self) -> bool {
+        if self.nesting == MAX_NESTING {
+            return false;
         }
+
+        self.nesting += 1;
+
+        true
+    }
+
+    pub fn unnest(&mut self) {
+        self.nesting -= 1;
+    }
+
+    /// Id for the node about to be built, unique within everything parsed from this lexer.
+    pub fn next_node_id(&mut self) -> NodeId {
+        self.node_ids.fresh()
+    }
+
+    /// True when any production being parsed can resume at `kind`.
+    pub fn is_sync_kind(&self, kind: TokenKind) -> bool {
+        self.sync.iter().any(|set| set.contains(&kind))
     }
 
     fn _return_content(&self, start: PositionalOffset, end: PositionalOffset) -> &str {
@@ -240,12 +443,61 @@ impl<'input> Lexer<'input> {
     }
 
     pub fn is_poisoned(&self) -> bool {
-        self.poisoned
+        self.poisoned || self.given_up
     }
 
     pub fn set_poisoned(&mut self, val: bool) {
+        if val && self.token.get_kind() == TokenKind::EOF {
+            self.ended_early = true;
+        }
+
         self.poisoned = val;
     }
+
+    /// In partial mode the input may stop anywhere, running out of it is no error.
+    pub fn set_partial(&mut self, val: bool) {
+        self.partial = val;
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// True when an error now would only be because the partial input stopped here, or
+    /// because the parser gave up on the rest of it.
+    pub fn is_truncated(&self) -> bool {
+        self.given_up || (self.partial && self.token.get_kind() == TokenKind::EOF)
+    }
+
+    /// Stops parsing: the rest of the input is skipped, and whatever the productions still
+    /// being parsed find wrong with its end is not reported.
+    pub fn give_up(&mut self) {
+        while self.peek().get_kind() != TokenKind::EOF {
+            self.advance();
+        }
+
+        self.set_poisoned(true);
+        self.given_up = true;
+    }
+
+    /// Notes that the production being parsed accepts `kinds` as the current token. Kept
+    /// only at the end of the input and until something there fails to parse, so that it
+    /// adds up to the kinds that could follow the input.
+    pub fn expect(&mut self, kinds: &[TokenKind]) {
+        if self.token.get_kind() != TokenKind::EOF || self.poisoned || self.ended_early {
+            return;
+        }
+
+        for kind in kinds {
+            if !self.expected.contains(kind) {
+                self.expected.push(*kind);
+            }
+        }
+    }
+
+    pub fn get_expected(&self) -> &[TokenKind] {
+        &self.expected
+    }
 }
 
 // ==================================================================================== //
@@ -264,7 +516,31 @@ fn _advance(text: &str, po: PositionalOffset) -> (Token<'_>, PositionalOffset) {
     (token, end_pos)
 }
 
+// Whitespace and attributes, neither of which is a token
 fn trim_starting_whitespace(text: &str, mut pos: PositionalOffset) -> PositionalOffset {
+    loop {
+        pos = trim_whitespace(text, pos);
+
+        match attribute_length(slice_from_position(text, pos)) {
+            Some(length) => pos = pos.shifted(length),
+            None => return pos,
+        }
+    }
+}
+
+// An attribute, `#[...]` or `#![...]`, ends at the first `]` on its line. A `#` without
+// one is left to the lexer, which finds no token in it.
+fn attribute_length(text: &str) -> Option<usize> {
+    if !text.starts_with("#[") && !text.starts_with("#![") {
+        return None;
+    }
+
+    let line = text.lines().next().unwrap_or("");
+
+    line.find(']').map(|end| end + 1)
+}
+
+fn trim_whitespace(text: &str, mut pos: PositionalOffset) -> PositionalOffset {
     let starting_text = &text[pos.pos..];
 
     let mut chars = starting_text.chars();
@@ -352,10 +628,16 @@ fn get_next_symbol(text: &str, mut pos: PositionalOffset) -> Option<(TokenKind,
             }
         }
         Some('-') => {
-            if matches!(chars.next(), Some('-')) {
+            let next = chars.next();
+
+            if matches!(next, Some('-')) {
                 pos.increment_pos(2);
                 pos.increment_cursor(2);
                 TokenKind::Decrement
+            } else if matches!(next, Some('>')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::RightArrow
             } else {
                 pos.increment_pos(1);
                 pos.increment_cursor(1);
@@ -400,6 +682,10 @@ fn get_next_symbol(text: &str, mut pos: PositionalOffset) -> Option<(TokenKind,
                 pos.increment_pos(2);
                 pos.increment_cursor(2);
                 TokenKind::LeftArrow
+            } else if matches!(next, Some('=')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::LesserEquals
             } else {
                 pos.increment_pos(1);
                 pos.increment_cursor(1);
@@ -407,10 +693,16 @@ fn get_next_symbol(text: &str, mut pos: PositionalOffset) -> Option<(TokenKind,
             }
         }
         Some('>') => {
-            if matches!(chars.next(), Some('>')) {
+            let next = chars.next();
+
+            if matches!(next, Some('>')) {
                 pos.increment_pos(2);
                 pos.increment_cursor(2);
                 TokenKind::ShiftRight
+            } else if matches!(next, Some('=')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::GreaterEquals
             } else {
                 pos.increment_pos(1);
                 pos.increment_cursor(1);
@@ -418,9 +710,15 @@ fn get_next_symbol(text: &str, mut pos: PositionalOffset) -> Option<(TokenKind,
             }
         }
         Some('=') => {
-            pos.increment_pos(1);
-            pos.increment_cursor(1);
-            TokenKind::Equals
+            if matches!(chars.next(), Some('=')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::DoubleEquals
+            } else {
+                pos.increment_pos(1);
+                pos.increment_cursor(1);
+                TokenKind::Equals
+            }
         }
         Some('(') => {
             if matches!(chars.next(), Some('*')) {
@@ -491,14 +789,37 @@ fn get_next_symbol(text: &str, mut pos: PositionalOffset) -> Option<(TokenKind,
             TokenKind::SemiColon
         }
         Some(':') => {
-            pos.increment_pos(1);
-            pos.increment_cursor(1);
-            TokenKind::Colon
+            if matches!(chars.next(), Some(':')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::PathSep
+            } else {
+                pos.increment_pos(1);
+                pos.increment_cursor(1);
+                TokenKind::Colon
+            }
         }
         Some('|') => {
-            pos.increment_pos(1);
-            pos.increment_cursor(1);
-            TokenKind::Pipe
+            if matches!(chars.next(), Some('|')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::Or
+            } else {
+                pos.increment_pos(1);
+                pos.increment_cursor(1);
+                TokenKind::Pipe
+            }
+        }
+        Some('&') => {
+            if matches!(chars.next(), Some('&')) {
+                pos.increment_pos(2);
+                pos.increment_cursor(2);
+                TokenKind::And
+            } else {
+                pos.increment_pos(1);
+                pos.increment_cursor(1);
+                TokenKind::Poisoned
+            }
         }
         Some('"') => {
             pos.increment_pos(1);
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::diagnostics::Span;
+
 // ==================================================================================== //
 // Identifier Extension Trait                                                           //
 // ==================================================================================== //
@@ -41,13 +43,21 @@ pub enum TokenKind {
     Mod,
     Greater,
     Lesser,
+    GreaterEquals,
+    LesserEquals,
     ShiftLeft,
     ShiftRight,
     Member,
     Not,
     NotEquals,
+    DoubleEquals,
+    And,
+    Or,
     Pipe,
     ProblemDef,
+    FuncKwd,
+    IncludeKwd,
+    UseKwd,
     LetKwd,
     Object,
     SuperObject,
@@ -57,6 +67,11 @@ pub enum TokenKind {
     ProblemSolution,
     ProblemInput,
     ProblemOutput,
+    IntType,
+    StringType,
+    VoidType,
+    RangeStep,
+    RangeUnion,
     Equals,
     LeftPar,
     RightPar,
@@ -66,9 +81,11 @@ pub enum TokenKind {
     RightBrace,
     Comma,
     Colon,
+    PathSep,
     SemiColon,
     Range,
     LeftArrow,
+    RightArrow,
     CommentStart,
     CommentEnd,
     NewLine,
@@ -82,11 +99,31 @@ pub enum TokenKind {
     Poisoned,
 }
 
+impl TokenKind {
+    /// The only text a token of this kind can have, `None` for kinds such as identifiers
+    /// and numbers that stand for many.
+    pub fn spelling(&self) -> Option<String> {
+        match self {
+            TokenKind::Int
+            | TokenKind::Float
+            | TokenKind::Ident
+            | TokenKind::String
+            | TokenKind::EOF
+            | TokenKind::START
+            | TokenKind::Poisoned => None,
+            _ => Some(self.to_string()),
+        }
+    }
+}
+
 impl fmt::Display for TokenKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match *self {
             TokenKind::START => "[START]",
             TokenKind::ProblemDef => "def",
+            TokenKind::FuncKwd => "func",
+            TokenKind::IncludeKwd => "include",
+            TokenKind::UseKwd => "use",
             TokenKind::LetKwd => "let",
             TokenKind::Object => "object",
             TokenKind::SuperObject => "superobject",
@@ -96,6 +133,11 @@ impl fmt::Display for TokenKind {
             TokenKind::ProblemSolution => "solution",
             TokenKind::ProblemInput => "input",
             TokenKind::ProblemOutput => "output",
+            TokenKind::IntType => "int",
+            TokenKind::StringType => "string",
+            TokenKind::VoidType => "void",
+            TokenKind::RangeStep => "by",
+            TokenKind::RangeUnion => "U",
             TokenKind::Plus => "+",
             TokenKind::Minus => "-",
             TokenKind::Increment => "++",
@@ -106,11 +148,16 @@ impl fmt::Display for TokenKind {
             TokenKind::Mod => "%",
             TokenKind::Greater => ">",
             TokenKind::Lesser => "<",
+            TokenKind::GreaterEquals => ">=",
+            TokenKind::LesserEquals => "<=",
             TokenKind::ShiftLeft => "<<",
             TokenKind::ShiftRight => ">>",
             TokenKind::Member => ".",
             TokenKind::Not => "!",
             TokenKind::NotEquals => "!=",
+            TokenKind::DoubleEquals => "==",
+            TokenKind::And => "&&",
+            TokenKind::Or => "||",
             TokenKind::Equals => "=",
             TokenKind::LeftPar => "(",
             TokenKind::RightPar => ")",
@@ -120,10 +167,12 @@ impl fmt::Display for TokenKind {
             TokenKind::RightBrace => "}",
             TokenKind::Comma => ",",
             TokenKind::Colon => ":",
+            TokenKind::PathSep => "::",
             TokenKind::SemiColon => ";",
             TokenKind::Range => "..",
             TokenKind::Pipe => "|",
             TokenKind::LeftArrow => "<-",
+            TokenKind::RightArrow => "->",
             TokenKind::CommentStart => "(*",
             TokenKind::CommentEnd => "*)",
             TokenKind::NewLine => "\n",
@@ -147,6 +196,9 @@ impl std::str::FromStr for TokenKind {
         let token = match s {
             "[START]" => TokenKind::START,
             "def" => TokenKind::ProblemDef,
+            "func" => TokenKind::FuncKwd,
+            "include" => TokenKind::IncludeKwd,
+            "use" => TokenKind::UseKwd,
             "let" => TokenKind::LetKwd,
             "object" => TokenKind::Object,
             "superobject" => TokenKind::SuperObject,
@@ -156,6 +208,11 @@ impl std::str::FromStr for TokenKind {
             "solution" => TokenKind::ProblemSolution,
             "input" => TokenKind::ProblemInput,
             "output" => TokenKind::ProblemOutput,
+            "int" => TokenKind::IntType,
+            "string" => TokenKind::StringType,
+            "void" => TokenKind::VoidType,
+            "by" => TokenKind::RangeStep,
+            "U" => TokenKind::RangeUnion,
             "+" => TokenKind::Plus,
             "-" => TokenKind::Minus,
             "++" => TokenKind::Increment,
@@ -168,9 +225,14 @@ impl std::str::FromStr for TokenKind {
             ">>" => TokenKind::ShiftLeft,
             ">" => TokenKind::Greater,
             "<" => TokenKind::Lesser,
+            ">=" => TokenKind::GreaterEquals,
+            "<=" => TokenKind::LesserEquals,
             "." => TokenKind::Member,
             "!" => TokenKind::Not,
             "!=" => TokenKind::NotEquals,
+            "==" => TokenKind::DoubleEquals,
+            "&&" => TokenKind::And,
+            "||" => TokenKind::Or,
             "=" => TokenKind::Equals,
             "(" => TokenKind::LeftPar,
             ")" => TokenKind::RightPar,
@@ -181,9 +243,11 @@ impl std::str::FromStr for TokenKind {
             "," => TokenKind::Comma,
             ";" => TokenKind::SemiColon,
             ":" => TokenKind::Colon,
+            "::" => TokenKind::PathSep,
             ".." => TokenKind::Range,
             "|" => TokenKind::Pipe,
             "<-" => TokenKind::LeftArrow,
+            "->" => TokenKind::RightArrow,
             "(*" => TokenKind::CommentStart,
             "*)" => TokenKind::CommentEnd,
             "\n" => TokenKind::NewLine,
@@ -300,3 +364,38 @@ impl std::fmt::Display for Token<'_> {
         fmt::Display::fmt(&s, f)
     }
 }
+
+// ==================================================================================== //
+// SyntaxToken Struct                                                                   //
+// ==================================================================================== //
+
+/// A punctuation or keyword token a node was parsed from, kept for its position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: TokenKind,
+    pub span: Span,
+    poisoned: bool,
+}
+
+impl SyntaxToken {
+    pub fn new(kind: TokenKind, span: Span) -> Self {
+        SyntaxToken {
+            kind,
+            span,
+            poisoned: false,
+        }
+    }
+
+    /// The token `kind` was expected at `span` but was not there.
+    pub fn poisoned(kind: TokenKind, span: Span) -> Self {
+        SyntaxToken {
+            kind,
+            span,
+            poisoned: true,
+        }
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
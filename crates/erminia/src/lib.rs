@@ -3,5 +3,8 @@ pub mod config;
 pub mod diagnostics;
 pub mod error;
 pub mod lexer;
+pub mod lint;
+pub mod loader;
+pub mod semantics;
 pub mod syntax;
 pub mod types;
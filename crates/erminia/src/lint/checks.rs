@@ -0,0 +1,201 @@
+use crate::diagnostics::{Help, Note};
+use crate::lint::{Finding, EMPTY_SHAPES, OVERLAPPING_OBJECTS, UNPLACED_OBJECTS, UNUSED_OBJECTS};
+use crate::semantics::facts::{Context, Facts, Owner, Piece, Placement, Positions};
+
+use std::collections::HashSet;
+
+// ==================================================================================== //
+// Checks                                                                               //
+// ==================================================================================== //
+
+/// Everything the object lints find in a file, from the `facts` gathered over it.
+pub(crate) fn run(facts: &Facts) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    findings.extend(unused_objects(facts));
+    findings.extend(empty_shapes(facts));
+    findings.extend(unplaced_objects(facts));
+    findings.extend(overlapping_objects(facts));
+
+    findings
+}
+
+// Objects local to a problem or function that nothing but themselves refers to
+fn unused_objects(facts: &Facts) -> Vec<Finding> {
+    facts
+        .objects
+        .iter()
+        .enumerate()
+        .filter(|(_, object)| object.context == Context::Local)
+        .filter(|(index, object)| {
+            !facts
+                .references
+                .iter()
+                .any(|(name, owner)| *name == object.name && *owner != Owner::Object(*index))
+        })
+        .map(|(_, object)| Finding {
+            lint: &UNUSED_OBJECTS,
+            span: object.name_span,
+            note: Note::UnusedObject(object.name.clone()),
+            help: Help::RemoveOrPlaceObject,
+            label: None,
+        })
+        .collect()
+}
+
+fn empty_shapes(facts: &Facts) -> Vec<Finding> {
+    facts
+        .objects
+        .iter()
+        .filter(|object| {
+            facts
+                .cells(&object.name)
+                .is_some_and(|cells| cells.is_empty())
+        })
+        .map(|object| Finding {
+            lint: &EMPTY_SHAPES,
+            span: object.shape_span,
+            note: Note::EmptyShape(object.name.clone()),
+            help: Help::CheckShapeRanges,
+            label: None,
+        })
+        .collect()
+}
+
+// Objects of a problem with inputs or outputs that are used, but only by what never ends
+// up in any of them. Unused objects are left to `unused_objects`.
+fn unplaced_objects(facts: &Facts) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    for problem in 0..facts.problems {
+        let in_problem = |p: &Option<usize>| *p == Some(problem);
+
+        if !facts.grids.iter().any(|grid| in_problem(&grid.problem)) {
+            continue;
+        }
+
+        // Names in an input or output, and then everything those refer to
+        let mut reached: HashSet<&str> = HashSet::new();
+        let mut pending: Vec<&str> = vec![];
+
+        for (index, object) in facts.objects.iter().enumerate() {
+            if object.context == Context::Grid && in_problem(&object.problem) {
+                pending.extend(facts.referred_by(Owner::Object(index)));
+            }
+        }
+
+        for (index, variable) in facts.variables.iter().enumerate() {
+            if variable.context == Context::Grid && in_problem(&variable.problem) {
+                pending.extend(facts.referred_by(Owner::Variable(index)));
+            }
+        }
+
+        for (index, grid) in facts.grids.iter().enumerate() {
+            if in_problem(&grid.problem) {
+                pending.extend(facts.referred_by(Owner::Grid(index)));
+            }
+        }
+
+        while let Some(name) = pending.pop() {
+            if !reached.insert(name) {
+                continue;
+            }
+
+            for (index, object) in facts.objects.iter().enumerate() {
+                if object.name == name {
+                    pending.extend(facts.referred_by(Owner::Object(index)));
+                }
+            }
+
+            for (index, variable) in facts.variables.iter().enumerate() {
+                if variable.name == name {
+                    pending.extend(facts.referred_by(Owner::Variable(index)));
+                }
+            }
+        }
+
+        for (index, object) in facts.objects.iter().enumerate() {
+            let used = facts
+                .references
+                .iter()
+                .any(|(name, owner)| *name == object.name && *owner != Owner::Object(index));
+
+            if object.context == Context::Local
+                && in_problem(&object.problem)
+                && used
+                && !reached.contains(object.name.as_str())
+            {
+                findings.push(Finding {
+                    lint: &UNPLACED_OBJECTS,
+                    span: object.name_span,
+                    note: Note::UnplacedObject(object.name.clone()),
+                    help: Help::PlaceObjectInGrid,
+                    label: None,
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+// Objects placed over each other within a shape, or within an input or output
+fn overlapping_objects(facts: &Facts) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    for object in &facts.objects {
+        let calls: Vec<Placement> = object
+            .pieces
+            .iter()
+            .filter_map(|piece| match piece {
+                Piece::Call(placement) => Some(placement.clone()),
+                Piece::Cells(_) => None,
+            })
+            .collect();
+
+        findings.extend(overlaps(facts, &calls));
+    }
+
+    for grid in &facts.grids {
+        findings.extend(overlaps(facts, &grid.placements));
+    }
+
+    findings
+}
+
+// Every placement that covers cells an earlier one already covers, once
+fn overlaps(facts: &Facts, placements: &[Placement]) -> Vec<Finding> {
+    let cells: Vec<Option<Positions>> = placements
+        .iter()
+        .map(|placement| facts.placed_cells(placement))
+        .collect();
+
+    let mut findings = vec![];
+
+    for (later, placement) in placements.iter().enumerate() {
+        let Some(later_cells) = &cells[later] else {
+            continue;
+        };
+
+        let earlier = (0..later).find(|&earlier| {
+            cells[earlier]
+                .as_ref()
+                .is_some_and(|earlier_cells| !earlier_cells.is_disjoint(later_cells))
+        });
+
+        if let Some(earlier) = earlier {
+            findings.push(Finding {
+                lint: &OVERLAPPING_OBJECTS,
+                span: placement.span,
+                note: Note::OverlappingObjects(
+                    placement.name.clone(),
+                    placements[earlier].name.clone(),
+                ),
+                help: Help::MoveOverlappingObject,
+                label: Some((placements[earlier].span, "placed here first".to_string())),
+            });
+        }
+    }
+
+    findings
+}
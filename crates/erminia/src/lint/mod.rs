@@ -0,0 +1,374 @@
+pub mod checks;
+
+use crate::ast::node::NodeTable;
+use crate::config::CompilerPass;
+use crate::diagnostics::code::DiagnosticLevel;
+use crate::diagnostics::{Code, DiagnosticAccumulator, DiagnosticBuilder, Help, Note, Span};
+use crate::lexer::lex::{Attribute, Lexer};
+use crate::semantics::facts::Facts;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The name that stands for every lint at once, in flags and attributes alike.
+pub const ALL_LINTS: &str = "warnings";
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+/// What becomes of what a lint finds: nothing, a warning, or an error that stops the
+/// passes after linting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// A named check for code that is valid but likely not what was meant.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub name: &'static str,
+    pub code: Code,
+    pub default_level: LintLevel,
+    pub description: &'static str,
+}
+
+pub static UNUSED_OBJECTS: Lint = Lint {
+    name: "unused_objects",
+    code: Code::W0001,
+    default_level: LintLevel::Warn,
+    description: "objects declared in a problem or function that nothing refers to",
+};
+
+pub static EMPTY_SHAPES: Lint = Lint {
+    name: "empty_shapes",
+    code: Code::W0002,
+    default_level: LintLevel::Warn,
+    description: "objects whose shape covers no cells",
+};
+
+pub static UNPLACED_OBJECTS: Lint = Lint {
+    name: "unplaced_objects",
+    code: Code::W0003,
+    default_level: LintLevel::Warn,
+    description: "objects of a problem that never end up in any of its inputs or outputs",
+};
+
+pub static OVERLAPPING_OBJECTS: Lint = Lint {
+    name: "overlapping_objects",
+    code: Code::W0004,
+    default_level: LintLevel::Warn,
+    description: "objects placed so that they cover the same cells",
+};
+
+pub static UNKNOWN_LINTS: Lint = Lint {
+    name: "unknown_lints",
+    code: Code::W0005,
+    default_level: LintLevel::Warn,
+    description: "attributes naming lints that do not exist",
+};
+
+/// Every lint there is.
+pub static LINTS: [&Lint; 5] = [
+    &UNUSED_OBJECTS,
+    &EMPTY_SHAPES,
+    &UNPLACED_OBJECTS,
+    &OVERLAPPING_OBJECTS,
+    &UNKNOWN_LINTS,
+];
+
+/// The levels lints run at when no attribute says otherwise, e.g. as given on the command
+/// line.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+/// Something a lint found, before its level is known.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub lint: &'static Lint,
+    pub span: Span,
+    pub note: Note,
+    pub help: Help,
+    pub label: Option<(Span, String)>,
+}
+
+// `#[level(name, ...)]` or `#![level(name, ...)]` as written, with where each name starts
+// within it
+struct LintAttribute<'t> {
+    inner: bool,
+    level: LintLevel,
+    names: Vec<(usize, &'t str)>,
+}
+
+// The level an attribute sets for some lints, over the declaration it comes before or
+// over the whole file
+struct Scope {
+    span: Option<Span>,
+    level: LintLevel,
+    lints: Vec<&'static Lint>,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl LintLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(format!("Unknown lint level: {}", s)),
+        }
+    }
+}
+
+impl Lint {
+    pub fn find(name: &str) -> Option<&'static Lint> {
+        LINTS.into_iter().find(|lint| lint.name == name)
+    }
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        LintConfig::default()
+    }
+
+    /// Runs the lint called `name`, or every lint for `warnings`, at `level`. Later calls
+    /// win over earlier ones, as repeated flags do.
+    pub fn set(&mut self, name: &str, level: LintLevel) -> Result<(), String> {
+        if name == ALL_LINTS {
+            for lint in LINTS {
+                self.levels.insert(lint.name, level);
+            }
+
+            return Ok(());
+        }
+
+        let lint = Lint::find(name).ok_or_else(|| format!("Unknown lint: {}", name))?;
+
+        self.levels.insert(lint.name, level);
+
+        Ok(())
+    }
+
+    pub fn level(&self, lint: &Lint) -> LintLevel {
+        self.levels
+            .get(lint.name)
+            .copied()
+            .unwrap_or(lint.default_level)
+    }
+}
+
+impl Scope {
+    // How much of the file the scope covers, to let the innermost one win
+    fn extent(&self) -> usize {
+        self.span.map_or(usize::MAX, |span| {
+            span.end.get_cursor() - span.start.get_cursor()
+        })
+    }
+
+    fn applies(&self, lint: &Lint, span: &Span) -> bool {
+        self.lints.contains(&lint) && self.span.is_none_or(|scope| scope.contains(span))
+    }
+}
+
+// ==================================================================================== //
+// Linting                                                                              //
+// ==================================================================================== //
+
+/// Runs every lint over a file, from its `tokens`, its `nodes` and the `facts` gathered
+/// over it, and records what they find at the level attributes or else `config` give them.
+pub(crate) fn check(
+    tokens: &mut Lexer,
+    nodes: &NodeTable,
+    facts: &Facts,
+    config: &LintConfig,
+    diag: &mut DiagnosticAccumulator,
+) {
+    let (scopes, mut findings) = read_attributes(tokens, nodes);
+
+    findings.extend(checks::run(facts));
+
+    for finding in findings {
+        let level = scopes
+            .iter()
+            .filter(|scope| scope.applies(finding.lint, &finding.span))
+            .fold(None, |innermost: Option<&Scope>, scope| match innermost {
+                Some(inner) if inner.extent() < scope.extent() => Some(inner),
+                _ => Some(scope),
+            })
+            .map_or(config.level(finding.lint), |scope| scope.level);
+
+        if level == LintLevel::Allow {
+            continue;
+        }
+
+        let mut builder = DiagnosticBuilder::build(CompilerPass::Lint, finding.lint.code)
+            .with_note(Some(finding.note.clone()))
+            .with_help(Some(finding.help));
+
+        if let Some((span, message)) = &finding.label {
+            builder = builder.with_label(*span, message);
+        }
+
+        if let Note::UnknownLint(name) = &finding.note {
+            let names: Vec<&str> = LINTS.iter().map(|lint| lint.name).collect();
+            builder = builder.with_did_you_mean(name, finding.span, &names);
+        }
+
+        let mut diagnostic = builder.emit(tokens, finding.span);
+
+        if level == LintLevel::Deny {
+            diagnostic.level = DiagnosticLevel::Error;
+        }
+
+        diag.add_diag(diagnostic);
+    }
+}
+
+// The scopes of the lint attributes in the file, and what is wrong with the attributes
+// themselves
+fn read_attributes(tokens: &Lexer, nodes: &NodeTable) -> (Vec<Scope>, Vec<Finding>) {
+    let mut scopes: Vec<Scope> = vec![];
+    let mut findings: Vec<Finding> = vec![];
+
+    for attribute in tokens.attributes() {
+        let text = tokens.get_snippet(attribute.span);
+
+        let Some(parsed) = parse_attribute(text) else {
+            findings.push(Finding {
+                lint: &UNKNOWN_LINTS,
+                span: attribute.span,
+                note: Note::MalformedLintAttribute(text.to_string()),
+                help: Help::UseLintLevel,
+                label: None,
+            });
+            continue;
+        };
+
+        let mut lints: Vec<&'static Lint> = vec![];
+
+        for (offset, name) in parsed.names {
+            if name == ALL_LINTS {
+                lints.extend(LINTS);
+                continue;
+            }
+
+            match Lint::find(name) {
+                Some(lint) => lints.push(lint),
+                None => {
+                    let start = attribute.span.start.shifted(offset);
+
+                    findings.push(Finding {
+                        lint: &UNKNOWN_LINTS,
+                        span: Span::new(start, start.shifted(name.len())),
+                        note: Note::UnknownLint(name.to_string()),
+                        help: Help::KnownLints(known_lints()),
+                        label: None,
+                    });
+                }
+            }
+        }
+
+        let span = if parsed.inner {
+            None
+        } else {
+            // An attribute before nothing it could apply to applies to nothing
+            match target(nodes, &attribute) {
+                Some(span) => Some(span),
+                None => continue,
+            }
+        };
+
+        scopes.push(Scope {
+            span,
+            level: parsed.level,
+            lints,
+        });
+    }
+
+    (scopes, findings)
+}
+
+fn parse_attribute(text: &str) -> Option<LintAttribute<'_>> {
+    let inner = text.starts_with("#![");
+    let level_start = if inner { 3 } else { 2 };
+
+    let open = text.find('(')?;
+    let close = text.rfind(')')?;
+
+    if close < open || text[close + 1..].trim() != "]" {
+        return None;
+    }
+
+    let level: LintLevel = text[level_start..open].trim().parse().ok()?;
+
+    let mut names = vec![];
+    let mut offset = open + 1;
+
+    for part in text[open + 1..close].split(',') {
+        let name = part.trim();
+
+        if !name.is_empty() {
+            if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return None;
+            }
+
+            names.push((offset + part.len() - part.trim_start().len(), name));
+        }
+
+        offset += part.len() + 1;
+    }
+
+    Some(LintAttribute {
+        inner,
+        level,
+        names,
+    })
+}
+
+// The declaration an outer attribute comes right before, which is the largest node
+// below the program starting at the next token
+fn target(nodes: &NodeTable, attribute: &Attribute) -> Option<Span> {
+    let next = attribute.next.get_cursor();
+
+    nodes
+        .ids()
+        .into_iter()
+        .filter_map(|id| nodes.get(id))
+        .filter(|entry| entry.parent.is_some() && entry.span.start.get_cursor() == next)
+        .map(|entry| entry.span)
+        .max_by_key(|span| span.end.get_cursor())
+}
+
+fn known_lints() -> String {
+    LINTS
+        .iter()
+        .map(|lint| format!("'{}'", lint.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,476 @@
+use crate::ast::ast::BoxAST;
+use crate::ast::decl::{DeclKind, Declaration, ModulePath};
+use crate::ast::expr::ObjectCall;
+use crate::ast::node::NodeTable;
+use crate::ast::stmt::{FuncDecl, ObjectDecl, VarDef};
+use crate::ast::visit::*;
+use crate::diag;
+use crate::diagnostics::code::DiagnosticLevel;
+use crate::diagnostics::{DiagnosticAccumulator, FileId, SourceMap, Span};
+use crate::error::loader_error::{LoaderError, LoaderResult};
+use crate::lexer::lex::Lexer;
+use crate::lint::{self, LintConfig};
+use crate::semantics::{self, facts::Facts};
+use crate::syntax::parse::parse_program;
+use crate::types::ErminiaType;
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+pub use crate::diagnostics::SourceFile;
+
+pub const FILE_EXTENSION: &str = "erm";
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+/// A parsed file together with the names it can see.
+#[derive(Debug)]
+pub struct Module {
+    pub file: FileId,
+    pub program: BoxAST<'static>,
+    /// The nodes of `program` by id, with their spans and parents.
+    pub nodes: NodeTable,
+    pub includes: Vec<FileId>,
+    /// Objects and functions declared in this file, visible to the files including it.
+    pub exports: Vec<Declaration>,
+    /// Everything declared in this file plus the exports of the modules it includes.
+    pub scope: HashMap<String, Declaration>,
+}
+
+/// Reads, parses and links a file and, transitively, everything it includes. Every file is
+/// parsed once, no matter how many modules include it.
+#[derive(Debug, Default)]
+pub struct Loader {
+    search_paths: Vec<PathBuf>,
+    virtual_files: HashMap<PathBuf, String>,
+    files: SourceMap,
+    modules: Vec<Option<Module>>,
+    ids: HashMap<PathBuf, FileId>,
+    stack: Vec<FileId>,
+    lints: LintConfig,
+    diagnostics: DiagnosticAccumulator,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl Loader {
+    pub fn new() -> Self {
+        Loader::default()
+    }
+
+    /// Directories searched, in order, when a module is not found next to the file
+    /// including it.
+    pub fn add_search_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.search_paths.push(normalize(path.as_ref()));
+    }
+
+    /// Makes `content` available under `path` without touching the file system.
+    pub fn add_virtual_file<P: AsRef<Path>>(&mut self, path: P, content: &str) {
+        self.virtual_files
+            .insert(normalize(path.as_ref()), content.to_string());
+    }
+
+    /// The levels lints run at in every file loaded, unless its attributes say otherwise.
+    pub fn set_lint_config(&mut self, config: LintConfig) {
+        self.lints = config;
+    }
+
+    /// Stops recording errors after the first `limit`, see
+    /// `DiagnosticAccumulator::set_error_limit`.
+    pub fn set_error_limit(&mut self, limit: usize) {
+        self.diagnostics.set_error_limit(Some(limit));
+    }
+
+    /// Loads the entry file and everything it includes.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> LoaderResult<FileId> {
+        let path = normalize(path.as_ref());
+
+        if !self.exists(&path) {
+            return Err(LoaderError::FileNotFound(path));
+        }
+
+        self.load_file(path)
+    }
+
+    pub fn get_module(&self, file: FileId) -> Option<&Module> {
+        self.modules.get(file.0).and_then(|m| m.as_ref())
+    }
+
+    pub fn get_file(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file)
+    }
+
+    pub fn get_files(&self) -> &[SourceFile] {
+        self.files.files()
+    }
+
+    pub fn get_source_map(&self) -> &SourceMap {
+        &self.files
+    }
+
+    pub fn get_diagnostics(&self) -> &DiagnosticAccumulator {
+        &self.diagnostics
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.virtual_files.contains_key(path) || path.is_file()
+    }
+
+    fn read(&self, path: &Path) -> LoaderResult<String> {
+        match self.virtual_files.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => Ok(std::fs::read_to_string(path)?),
+        }
+    }
+
+    /// `a::b` names `a/b.erm`, looked up next to `from` first and then in the search paths.
+    fn resolve(&self, from: &Path, module: &ModulePath) -> Option<PathBuf> {
+        let mut relative: PathBuf = module.segments.iter().collect();
+        relative.set_extension(FILE_EXTENSION);
+
+        let base = from.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        std::iter::once(base)
+            .chain(self.search_paths.iter().cloned())
+            .map(|dir| normalize(&dir.join(&relative)))
+            .find(|candidate| self.exists(candidate))
+    }
+
+    fn load_file(&mut self, path: PathBuf) -> LoaderResult<FileId> {
+        if let Some(&id) = self.ids.get(&path) {
+            return Ok(id);
+        }
+
+        let content = self.read(&path)?;
+
+        let id = self.files.add_file(&path, content);
+
+        self.modules.push(None);
+        self.ids.insert(path, id);
+
+        self.stack.push(id);
+
+        let program: BoxAST<'static> = {
+            let mut tokens = Lexer::with_file(&self.files[id].content, id);
+            parse_program(&mut tokens, &mut self.diagnostics)
+        };
+
+        let declarations = program.declarations();
+
+        let mut scope: HashMap<String, Declaration> = HashMap::new();
+
+        for decl in &declarations {
+            scope.entry(decl.name.clone()).or_insert(decl.clone());
+        }
+
+        let mut includes: Vec<FileId> = vec![];
+
+        for module in program.includes() {
+            if let Some(included) = self.include(id, &module) {
+                includes.push(included);
+                self.merge_exports(id, included, &module, &mut scope);
+            }
+        }
+
+        self.stack.pop();
+
+        self.check_object_calls(id, &program, &scope);
+
+        let nodes = NodeTable::build(&program);
+
+        // Both the semantic checks and the lints work from these, and only on a file that parses
+        if !self.has_errors(id) {
+            let facts = Facts::gather(&program);
+
+            self.check_semantics(id, &program, &facts);
+            self.lint(id, &nodes, &facts);
+        }
+
+        let exports = declarations
+            .into_iter()
+            .filter(|d| d.kind.is_exported())
+            .collect();
+
+        self.modules[id.0] = Some(Module {
+            file: id,
+            program,
+            nodes,
+            includes,
+            exports,
+            scope,
+        });
+
+        Ok(id)
+    }
+
+    fn include(&mut self, from: FileId, module: &ModulePath) -> Option<FileId> {
+        let Some(path) = self.resolve(&self.files[from].path, module) else {
+            self.report_missing(from, module);
+            return None;
+        };
+
+        if let Some(&id) = self.ids.get(&path) {
+            if self.stack.contains(&id) {
+                self.report_cycle(from, id, module.span);
+                return None;
+            }
+        }
+
+        match self.load_file(path) {
+            Ok(id) => Some(id),
+            Err(_) => {
+                self.report_missing(from, module);
+                None
+            }
+        }
+    }
+
+    fn merge_exports(
+        &mut self,
+        from: FileId,
+        included: FileId,
+        module: &ModulePath,
+        scope: &mut HashMap<String, Declaration>,
+    ) {
+        let Some(exports) = self.get_module(included).map(|m| m.exports.clone()) else {
+            return;
+        };
+
+        for export in exports {
+            match scope.get(&export.name) {
+                // The same module reached through two different includes
+                Some(existing) if *existing == export => {}
+                Some(existing) => {
+                    let mut tokens = Lexer::with_file(&self.files[from].content, from);
+
+                    diag!(
+                        Loader,
+                        E0012,
+                        ConflictingInclude(export.name.clone(), module.to_string()),
+                        RenameConflictingDeclaration,
+                        &mut tokens,
+                        self.diagnostics,
+                        module.span;
+                        with_label(existing.span, "first declared here")
+                    );
+                }
+                None => {
+                    scope.insert(export.name.clone(), export);
+                }
+            }
+        }
+    }
+
+    /// Reports the object calls in `program` that name no object, either in `scope` or
+    /// declared further in, and suggests the closest object name. A call to something else,
+    /// e.g. a variable, is told apart from a call to nothing at all.
+    fn check_object_calls(
+        &mut self,
+        file: FileId,
+        program: &BoxAST<'static>,
+        scope: &HashMap<String, Declaration>,
+    ) {
+        let mut names = Names::default();
+        names.visit_ast(program);
+
+        let mut objects: Vec<String> = scope
+            .values()
+            .filter(|d| d.kind == DeclKind::Object)
+            .map(|d| d.name.clone())
+            .chain(names.objects)
+            .collect();
+        objects.sort();
+        objects.dedup();
+
+        for decl in scope.values().filter(|d| d.kind != DeclKind::Object) {
+            names
+                .others
+                .push((decl.name.clone(), decl.kind.describe(), decl.span));
+        }
+
+        for (name, span) in names.calls {
+            if objects.contains(&name) {
+                continue;
+            }
+
+            let mut tokens = Lexer::with_file(&self.files[file].content, file);
+
+            match names.others.iter().find(|(other, _, _)| *other == name) {
+                Some((_, what, declared)) => {
+                    diag!(
+                        Loader,
+                        E0020,
+                        NotAnObject(name.clone(), what.to_string()),
+                        CallDeclaredObject,
+                        &mut tokens,
+                        self.diagnostics,
+                        span;
+                        with_label(*declared, "declared here"),
+                        with_did_you_mean(&name, span, &objects)
+                    );
+                }
+                None => {
+                    diag!(
+                        Loader,
+                        E0015,
+                        UnknownObject(name.clone()),
+                        DeclareOrIncludeObject,
+                        &mut tokens,
+                        self.diagnostics,
+                        span;
+                        with_did_you_mean(&name, span, &objects)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks what `program` means, once it has parsed and its names resolve.
+    fn check_semantics(&mut self, file: FileId, program: &BoxAST<'static>, facts: &Facts) {
+        let mut tokens = Lexer::with_file(&self.files[file].content, file);
+
+        semantics::check(&mut tokens, program, facts, &mut self.diagnostics);
+    }
+
+    /// Runs the lints over the file with `nodes`, unless errors in it already make their
+    /// findings moot.
+    fn lint(&mut self, file: FileId, nodes: &NodeTable, facts: &Facts) {
+        if self.has_errors(file) {
+            return;
+        }
+
+        let mut tokens = Lexer::with_file(&self.files[file].content, file);
+
+        lint::check(
+            &mut tokens,
+            nodes,
+            facts,
+            &self.lints,
+            &mut self.diagnostics,
+        );
+    }
+
+    fn has_errors(&self, file: FileId) -> bool {
+        self.diagnostics
+            .diagnostics
+            .iter()
+            .any(|d| d.level <= DiagnosticLevel::Error && d.window.span.file() == file)
+    }
+
+    fn report_missing(&mut self, from: FileId, module: &ModulePath) {
+        let mut tokens = Lexer::with_file(&self.files[from].content, from);
+
+        diag!(
+            Loader,
+            E0010,
+            ModuleNotFound(module.to_string()),
+            CheckModuleSearchPath,
+            &mut tokens,
+            self.diagnostics,
+            module.span
+        );
+    }
+
+    fn report_cycle(&mut self, from: FileId, to: FileId, span: Span) {
+        let start = self.stack.iter().position(|&id| id == to).unwrap_or(0);
+
+        let chain: Vec<String> = self.stack[start..]
+            .iter()
+            .chain(std::iter::once(&to))
+            .map(|&id| self.files[id].path.display().to_string())
+            .collect();
+
+        let mut tokens = Lexer::with_file(&self.files[from].content, from);
+
+        diag!(
+            Loader,
+            E0011,
+            IncludeCycle(chain.join(" -> ")),
+            BreakIncludeCycle,
+            &mut tokens,
+            self.diagnostics,
+            span
+        );
+    }
+}
+
+// ==================================================================================== //
+// Name Collection                                                                      //
+// ==================================================================================== //
+
+// The objects called in a file, the objects declared below its top level that a call may
+// refer to, and the other names declared there that a call must not. Which of those a call
+// can actually see is left to a later pass.
+#[derive(Default)]
+struct Names {
+    objects: Vec<String>,
+    others: Vec<(String, &'static str, Span)>,
+    calls: Vec<(String, Span)>,
+}
+
+impl Names {
+    fn declare(&mut self, id: &ErminiaType, what: &'static str, span: Span) {
+        if let ErminiaType::Ident(name) = id {
+            self.others.push((name.to_string(), what, span));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Names {
+    fn visit_object_decl(&mut self, node: &ObjectDecl<'a>) {
+        if let ErminiaType::Ident(name) = &node.id {
+            self.objects.push(name.to_string());
+        }
+
+        walk_object_decl(self, node)
+    }
+
+    fn visit_func_decl(&mut self, node: &FuncDecl<'a>) {
+        for param in &node.params {
+            // Parameters have no span of their own
+            self.declare(&param.id, "parameter", node.span);
+        }
+
+        walk_func_decl(self, node)
+    }
+
+    fn visit_var_def(&mut self, node: &VarDef<'a>) {
+        self.declare(&node.id, "variable", node.span);
+
+        walk_var_def(self, node)
+    }
+
+    fn visit_object_call(&mut self, node: &ObjectCall) {
+        if node.is_poisoned {
+            return;
+        }
+
+        self.calls.push((node.id.to_id(), node.name_span()));
+    }
+}
+
+/// Removes `.` and resolves `..` without asking the file system, so that a file reached
+/// through different relative paths is still loaded once.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if normalized.file_name().is_some() {
+                    normalized.pop();
+                } else {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+
+    normalized
+}
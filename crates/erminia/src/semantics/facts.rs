@@ -0,0 +1,360 @@
+use crate::ast::ast::BoxAST;
+use crate::ast::eval::{Cells, Env};
+use crate::ast::expr::{ObjectCall, RValue, RValueKind};
+use crate::ast::stmt::*;
+use crate::ast::visit::*;
+use crate::diagnostics::Span;
+use crate::types::ErminiaType;
+
+use std::collections::BTreeSet;
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+// Where an object or variable is declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Context {
+    // The top level of the file, where objects are exported
+    TopLevel,
+    // The body of a problem or a function
+    Local,
+    // The body of an input or output, which puts what it declares on the grid
+    Grid,
+}
+
+// An object called by name and moved by an offset, as in `Dot(1, 2)`
+#[derive(Debug, Clone)]
+pub(crate) struct Placement {
+    pub(crate) name: String,
+    pub(crate) offset: (i32, i32),
+    pub(crate) span: Span,
+}
+
+// Cells once placed, as `i64` so that no offset can move them past what they can hold
+pub(crate) type Positions = BTreeSet<(i64, i64)>;
+
+// A piece of a shape: cells written out, or another object placed within it
+#[derive(Debug, Clone)]
+pub(crate) enum Piece {
+    Cells(Option<Cells>),
+    Call(Placement),
+}
+
+#[derive(Debug)]
+pub(crate) struct Object {
+    pub(crate) name: String,
+    pub(crate) name_span: Span,
+    pub(crate) shape_span: Span,
+    pub(crate) pieces: Vec<Piece>,
+    pub(crate) context: Context,
+    pub(crate) problem: Option<usize>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Variable {
+    pub(crate) name: String,
+    pub(crate) placement: Option<Placement>,
+    pub(crate) context: Context,
+    pub(crate) problem: Option<usize>,
+}
+
+// What a name is referred to from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Owner {
+    Object(usize),
+    Variable(usize),
+    Grid(usize),
+    Other,
+}
+
+// An input or output: its problem, its size and what is placed in it
+#[derive(Debug)]
+pub(crate) struct Grid {
+    pub(crate) problem: Option<usize>,
+    pub(crate) size: Option<(i32, i32)>,
+    pub(crate) size_span: Span,
+    pub(crate) placements: Vec<Placement>,
+}
+
+// What the objects of a file are made of and where they end up, gathered in one walk over
+// it for the semantic checks and the lints
+#[derive(Debug, Default)]
+pub(crate) struct Facts {
+    pub(crate) objects: Vec<Object>,
+    pub(crate) variables: Vec<Variable>,
+    pub(crate) references: Vec<(String, Owner)>,
+    pub(crate) grids: Vec<Grid>,
+    pub(crate) problems: usize,
+    problem: Option<usize>,
+    grid: Option<usize>,
+    owner: Vec<Owner>,
+    in_function: bool,
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl Facts {
+    pub(crate) fn gather(program: &BoxAST) -> Self {
+        let mut facts = Facts::default();
+        facts.visit_ast(program);
+
+        facts
+    }
+
+    pub(crate) fn referred_by(&self, owner: Owner) -> impl Iterator<Item = &str> + '_ {
+        self.references
+            .iter()
+            .filter(move |(_, o)| *o == owner)
+            .map(|(name, _)| name.as_str())
+    }
+
+    // The cells of what `name` stands for: the first object declared with it, or else a
+    // variable set to a placed object. `None` when that cannot be told.
+    pub(crate) fn cells(&self, name: &str) -> Option<Positions> {
+        self.cells_of(name, &mut vec![])
+    }
+
+    pub(crate) fn placed_cells(&self, placement: &Placement) -> Option<Positions> {
+        self.placed_cells_of(placement, &mut vec![])
+    }
+
+    fn cells_of<'f>(&'f self, name: &'f str, visiting: &mut Vec<&'f str>) -> Option<Positions> {
+        if visiting.contains(&name) {
+            return None;
+        }
+
+        visiting.push(name);
+
+        let cells = match self.objects.iter().find(|object| object.name == name) {
+            Some(object) => {
+                let mut cells = Positions::new();
+
+                for piece in &object.pieces {
+                    match piece {
+                        Piece::Cells(piece_cells) => cells.extend(
+                            piece_cells
+                                .as_ref()?
+                                .iter()
+                                .map(|&(x, y)| (i64::from(x), i64::from(y))),
+                        ),
+                        Piece::Call(placement) => {
+                            cells.extend(self.placed_cells_of(placement, visiting)?)
+                        }
+                    }
+                }
+
+                Some(cells)
+            }
+            None => self
+                .variables
+                .iter()
+                .find(|variable| variable.name == name)
+                .and_then(|variable| variable.placement.as_ref())
+                .and_then(|placement| self.placed_cells_of(placement, visiting)),
+        };
+
+        visiting.pop();
+
+        cells
+    }
+
+    fn placed_cells_of<'f>(
+        &'f self,
+        placement: &'f Placement,
+        visiting: &mut Vec<&'f str>,
+    ) -> Option<Positions> {
+        let (dx, dy) = placement.offset;
+
+        let cells = self.cells_of(&placement.name, visiting)?;
+
+        cells
+            .into_iter()
+            .map(|(x, y)| Some((x.checked_add(dx.into())?, y.checked_add(dy.into())?)))
+            .collect()
+    }
+
+    fn context(&self) -> Context {
+        if self.grid.is_some() {
+            Context::Grid
+        } else if self.problem.is_some() || self.in_function {
+            Context::Local
+        } else {
+            Context::TopLevel
+        }
+    }
+
+    fn refer(&mut self, name: String) {
+        let owner = match (self.owner.last(), self.grid) {
+            (Some(owner), _) => *owner,
+            (None, Some(grid)) => Owner::Grid(grid),
+            (None, None) => Owner::Other,
+        };
+
+        self.references.push((name, owner));
+    }
+
+    fn place(&mut self, placement: Placement) {
+        if let Some(grid) = self.grid {
+            self.grids[grid].placements.push(placement);
+        }
+    }
+
+    // Inputs and outputs are walked alike
+    fn visit_grid<'a>(&mut self, tuple: &BoxAST<'a>, stmts: &[BoxAST<'a>]) {
+        let size = match tuple.as_node() {
+            NodeRef::Tuple(Tuple {
+                left: ErminiaType::Integer(width),
+                right: ErminiaType::Integer(height),
+                ..
+            }) => Some((*width, *height)),
+            _ => None,
+        };
+
+        let outer = self.grid.replace(self.grids.len());
+        self.grids.push(Grid {
+            problem: self.problem,
+            size,
+            size_span: tuple.get_span(),
+            placements: vec![],
+        });
+
+        for stmt in stmts {
+            self.visit_ast(stmt);
+        }
+
+        self.grid = outer;
+    }
+}
+
+impl Placement {
+    fn of_call(node: &ObjectCall) -> Self {
+        Placement {
+            name: node.id.to_id(),
+            offset: (node.offset_x.to_int(), node.offset_y.to_int()),
+            span: node.span,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Facts {
+    fn visit_problem_decl(&mut self, node: &ProblemDecl<'a>) {
+        let outer = self.problem.replace(self.problems);
+        self.problems += 1;
+
+        walk_problem_decl(self, node);
+
+        self.problem = outer;
+    }
+
+    fn visit_func_decl(&mut self, node: &FuncDecl<'a>) {
+        let outer = std::mem::replace(&mut self.in_function, true);
+
+        walk_func_decl(self, node);
+
+        self.in_function = outer;
+    }
+
+    fn visit_problem_input(&mut self, node: &ProblemInput<'a>) {
+        self.visit_grid(&node.tuple, &node.stmts);
+    }
+
+    fn visit_problem_output(&mut self, node: &ProblemOutput<'a>) {
+        self.visit_grid(&node.tuple, &node.stmts);
+    }
+
+    fn visit_object_decl(&mut self, node: &ObjectDecl<'a>) {
+        let ErminiaType::Ident(name) = &node.id else {
+            return;
+        };
+
+        let NodeRef::ObjectDesc(desc) = node.desc.as_node() else {
+            return;
+        };
+
+        let NodeRef::ObjectShape(shape) = desc.shape.as_node() else {
+            return;
+        };
+
+        let pieces = shape
+            .shape
+            .iter()
+            .map(|piece| match piece.as_node() {
+                NodeRef::ObjectCall(call) => Piece::Call(Placement::of_call(call)),
+                _ => Piece::Cells(piece.eval_cells(&Env::new())),
+            })
+            .collect();
+
+        self.place(Placement {
+            name: name.clone(),
+            offset: (0, 0),
+            span: node.name_span(),
+        });
+
+        self.objects.push(Object {
+            name: name.clone(),
+            name_span: node.name_span(),
+            shape_span: shape.span,
+            pieces,
+            context: self.context(),
+            problem: self.problem,
+        });
+
+        self.owner.push(Owner::Object(self.objects.len() - 1));
+
+        walk_object_decl(self, node);
+
+        self.owner.pop();
+    }
+
+    fn visit_var_def(&mut self, node: &VarDef<'a>) {
+        let ErminiaType::Ident(name) = &node.id else {
+            return;
+        };
+
+        let placement = match node.expr.as_node() {
+            NodeRef::ObjectCall(call) => Some(Placement::of_call(call)),
+            NodeRef::RValue(RValue {
+                value: RValueKind::Id(id),
+                span,
+                ..
+            }) => Some(Placement {
+                name: id.clone(),
+                offset: (0, 0),
+                span: *span,
+            }),
+            _ => None,
+        };
+
+        if let Some(placement) = &placement {
+            self.place(placement.clone());
+        }
+
+        self.variables.push(Variable {
+            name: name.clone(),
+            placement,
+            context: self.context(),
+            problem: self.problem,
+        });
+
+        self.owner.push(Owner::Variable(self.variables.len() - 1));
+
+        walk_var_def(self, node);
+
+        self.owner.pop();
+    }
+
+    fn visit_object_call(&mut self, node: &ObjectCall) {
+        if !node.is_poisoned {
+            self.refer(node.id.to_id());
+        }
+    }
+
+    fn visit_rvalue(&mut self, node: &RValue) {
+        if let RValueKind::Id(id) = &node.value {
+            self.refer(id.clone());
+        }
+    }
+}
@@ -0,0 +1,156 @@
+pub mod facts;
+
+use crate::ast::ast::BoxAST;
+use crate::ast::eval::{Env, Evaluate};
+use crate::ast::stmt::Range;
+use crate::ast::visit::*;
+use crate::diag;
+use crate::diagnostics::{DiagnosticAccumulator, Span};
+use crate::lexer::lex::Lexer;
+use facts::{Facts, Grid};
+
+/// The widths and heights ARC grids may have.
+pub const GRID_SIZES: std::ops::RangeInclusive<i32> = 1..=30;
+
+// ==================================================================================== //
+// Structs                                                                              //
+// ==================================================================================== //
+
+// The ranges in a file that evaluate to no values on their own, without the values of any
+// variable bound around them, and the steps that are not positive on their own
+#[derive(Default)]
+struct Ranges {
+    empty: Vec<Span>,
+    bad_steps: Vec<(Span, i32)>,
+}
+
+// ==================================================================================== //
+// Checks                                                                               //
+// ==================================================================================== //
+
+/// Checks what `program`, parsed from `tokens`, means: that every range steps forward and
+/// has values, that inputs and outputs are sized as ARC allows, and that what they place stays within them.
+pub(crate) fn check(
+    tokens: &mut Lexer,
+    program: &BoxAST,
+    facts: &Facts,
+    diag: &mut DiagnosticAccumulator,
+) {
+    let mut ranges = Ranges::default();
+    ranges.visit_ast(program);
+
+    for (span, step) in ranges.bad_steps {
+        diag!(
+            Semantics,
+            E0021,
+            NonPositiveStep(step.to_string()),
+            UsePositiveStep,
+            tokens,
+            diag,
+            span
+        );
+    }
+
+    for span in ranges.empty {
+        let text = tokens.get_snippet(span).to_string();
+
+        diag!(
+            Semantics,
+            E0018,
+            EmptyRange(text),
+            ConsiderChangingToInclusive,
+            tokens,
+            diag,
+            span
+        );
+    }
+
+    for grid in &facts.grids {
+        let Some((width, height)) = grid.size else {
+            continue;
+        };
+
+        if !GRID_SIZES.contains(&width) || !GRID_SIZES.contains(&height) {
+            diag!(
+                Semantics,
+                E0017,
+                GridSizeOutOfLimits(width.to_string(), height.to_string()),
+                KeepGridSizeInLimits,
+                tokens,
+                diag,
+                grid.size_span
+            );
+            continue;
+        }
+
+        check_bounds(tokens, facts, grid, (width, height), diag);
+    }
+}
+
+// Reports every placement in `grid` with cells outside of it
+fn check_bounds(
+    tokens: &mut Lexer,
+    facts: &Facts,
+    grid: &Grid,
+    (width, height): (i32, i32),
+    diag: &mut DiagnosticAccumulator,
+) {
+    for placement in &grid.placements {
+        let Some(cells) = facts.placed_cells(placement) else {
+            continue;
+        };
+
+        let (width, height) = (i64::from(width), i64::from(height));
+
+        let outside: Vec<(i64, i64)> = cells
+            .into_iter()
+            .filter(|(x, y)| !(0..width).contains(x) || !(0..height).contains(y))
+            .collect();
+
+        let Some((x, y)) = outside.first() else {
+            continue;
+        };
+
+        diag!(
+            Semantics,
+            E0016,
+            CellsOutsideGrid(
+                placement.name.clone(),
+                outside.len().to_string(),
+                format!("{}x{}", width, height),
+                format!("({}, {})", x, y)
+            ),
+            MoveObjectIntoGrid,
+            tokens,
+            diag,
+            placement.span;
+            with_label(grid.size_span, "grid size given here")
+        );
+    }
+}
+
+// ==================================================================================== //
+// Implementations                                                                      //
+// ==================================================================================== //
+
+impl<'a> Visitor<'a> for Ranges {
+    fn visit_range(&mut self, node: &Range<'a>) {
+        let env = Env::new();
+
+        let step = node
+            .step
+            .as_ref()
+            .and_then(|step| Some((step.get_span(), step.eval(&env)?)));
+
+        // A range with a bad step has no values to speak of, so it is not also empty
+        match step {
+            Some((span, step)) if step <= 0 => self.bad_steps.push((span, step)),
+            _ if node.eval_range(&env).is_some_and(|range| range.is_empty()) => {
+                self.empty.push(node.span)
+            }
+            _ => {}
+        }
+
+        walk_range(self, node);
+    }
+}
@@ -1,10 +1,54 @@
+use crate::ast::expr::BinaryOp;
 use crate::diag;
-use crate::diagnostics::{DiagnosticAccumulator, Span};
-use crate::lexer::lex::Lexer;
+use crate::diagnostics::{Applicability, DiagnosticAccumulator, Span, Suggestion};
 use crate::lexer::lex::PositionalOffset;
-use crate::lexer::token::TokenKind;
+use crate::lexer::lex::{Lexer, KEYWORDS};
+use crate::lexer::token::{SyntaxToken, TokenKind};
+use crate::syntax::recovery::*;
 use crate::types::ErminiaType;
 
+// ==================================================================================== //
+//  First Sets                                                                          //
+// ==================================================================================== //
+
+/// Tokens an expression can start with.
+pub const EXPR_START: &[TokenKind] = &[
+    TokenKind::Ident,
+    TokenKind::Int,
+    TokenKind::LeftPar,
+    TokenKind::Minus,
+    TokenKind::Not,
+];
+
+/// Tokens a statement can start with.
+pub const STMT_START: &[TokenKind] = &[
+    TokenKind::Ident,
+    TokenKind::Object,
+    TokenKind::FuncKwd,
+    TokenKind::LetKwd,
+    TokenKind::ProblemExample,
+    TokenKind::ProblemSolution,
+    TokenKind::ProblemInput,
+    TokenKind::ProblemOutput,
+];
+
+/// Tokens a top-level declaration can start with.
+pub const TOP_LEVEL_START: &[TokenKind] = &[
+    TokenKind::ProblemDef,
+    TokenKind::FuncKwd,
+    TokenKind::Object,
+    TokenKind::IncludeKwd,
+    TokenKind::UseKwd,
+];
+
+/// Tokens a data type can be.
+pub const DATA_TYPES: &[TokenKind] = &[
+    TokenKind::Object,
+    TokenKind::IntType,
+    TokenKind::StringType,
+    TokenKind::VoidType,
+];
+
 // ==================================================================================== //
 //  Utilities                                                                           //
 // ==================================================================================== //
@@ -12,16 +56,22 @@ use crate::types::ErminiaType;
 pub fn is_next_right_inclusive(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
 ) -> ErminiaType {
+    tokens.expect(&[TokenKind::RightPar, TokenKind::RightBracket]);
+
     if tokens.is_poisoned() {
         return ErminiaType::Poisoned;
     }
 
     let token = tokens.token;
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = current_span(tokens);
+
+    let fix = range_bracket_fix(
+        tokens,
+        "]",
+        matches!(token.get_kind(), TokenKind::RightBrace | TokenKind::Greater),
+    );
 
     let res = match token.get_kind() {
         TokenKind::RightPar => ErminiaType::Bool(false),
@@ -29,12 +79,13 @@ pub fn is_next_right_inclusive(
         TokenKind::Poisoned => {
             diag!(
                 Lexer,
-                E0002,
+                E0014,
                 ExpectedRightInclusive(token.text.to_string()),
                 ConsiderChangingToInclusive,
                 tokens,
                 diag,
-                span
+                span;
+                with_suggestions(Some(fix))
             );
 
             ErminiaType::Poisoned
@@ -47,12 +98,11 @@ pub fn is_next_right_inclusive(
                 ConsiderChangingToInclusive,
                 tokens,
                 diag,
-                span
+                span;
+                with_suggestions(Some(fix))
             );
 
-            tokens.set_poisoned(true);
-
-            tokens.loop_to_kind(TokenKind::SemiColon);
+            recover(tokens);
 
             return ErminiaType::Poisoned;
         }
@@ -63,19 +113,22 @@ pub fn is_next_right_inclusive(
     res
 }
 
-pub fn is_next_left_inclusive(
-    tokens: &mut Lexer,
-    diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
-) -> ErminiaType {
+pub fn is_next_left_inclusive(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> ErminiaType {
+    tokens.expect(&[TokenKind::LeftPar, TokenKind::LeftBracket]);
+
     if tokens.is_poisoned() {
         return ErminiaType::Poisoned;
     }
 
     let token = tokens.token;
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = current_span(tokens);
+
+    let fix = range_bracket_fix(
+        tokens,
+        "[",
+        matches!(token.get_kind(), TokenKind::LeftBrace | TokenKind::Lesser),
+    );
 
     let res = match token.get_kind() {
         TokenKind::LeftPar => ErminiaType::Bool(false),
@@ -83,12 +136,13 @@ pub fn is_next_left_inclusive(
         TokenKind::Poisoned => {
             diag!(
                 Lexer,
-                E0002,
+                E0014,
                 ExpectedLeftInclusive(token.text.to_string()),
                 ConsiderChangingToInclusive,
                 tokens,
                 diag,
-                span
+                span;
+                with_suggestions(Some(fix))
             );
 
             ErminiaType::Poisoned
@@ -101,12 +155,11 @@ pub fn is_next_left_inclusive(
                 ConsiderChangingToInclusive,
                 tokens,
                 diag,
-                span
+                span;
+                with_suggestions(Some(fix))
             );
 
-            tokens.set_poisoned(true);
-
-            tokens.loop_to_kind(TokenKind::SemiColon);
+            recover(tokens);
 
             return ErminiaType::Poisoned;
         }
@@ -117,29 +170,146 @@ pub fn is_next_left_inclusive(
     res
 }
 
+// A range bracket that is not one. A brace or angle bracket `looks_alike` was meant to be
+// `bracket` for sure, anything else may be a bracket left out before it.
+fn range_bracket_fix(tokens: &Lexer, bracket: &str, looks_alike: bool) -> Suggestion {
+    let span = current_span(tokens);
+
+    if looks_alike {
+        Suggestion::new(span, bracket, Applicability::MachineApplicable)
+    } else {
+        Suggestion::new(
+            Span::new(span.start, span.start),
+            bracket,
+            Applicability::MaybeIncorrect,
+        )
+    }
+}
+
 pub fn next_is_comma(tokens: &mut Lexer) -> bool {
-    matches!(tokens.peek().get_kind(), TokenKind::Comma)
+    match_next(tokens, TokenKind::Comma)
 }
 
 pub fn next_is_expr(tokens: &mut Lexer) -> bool {
-    matches!(tokens.peek().get_kind(), TokenKind::Ident | TokenKind::Int)
+    match_next_any(tokens, EXPR_START)
+}
+
+/// The binary operator the current token stands for, if any.
+pub fn next_binary_op(tokens: &mut Lexer) -> Option<BinaryOp> {
+    tokens.expect(&BinaryOp::ALL.map(|op| op.to_kind()));
+
+    BinaryOp::from_kind(tokens.peek().get_kind())
+}
+
+pub fn next_is_generator(tokens: &mut Lexer) -> bool {
+    match_next(tokens, TokenKind::Ident) && matches!(tokens.lookahead().0, TokenKind::LeftArrow)
+}
+
+// Comprehension clauses and shapes are both separated by commas inside a shape list, so
+// with the current token being a ',' this decides whether what follows it still belongs to
+// the comprehension (a generator or a guard) or starts the next shape.
+pub fn next_is_comprehension_clause(tokens: &mut Lexer) -> bool {
+    if !next_is_comma(tokens) {
+        return false;
+    }
+
+    let mut kinds = tokens.lookahead_kinds();
+
+    match kinds.next() {
+        Some(TokenKind::Int) | Some(TokenKind::Minus) | Some(TokenKind::Not) => true,
+        Some(TokenKind::Ident) => {
+            let next = kinds.next().unwrap_or(TokenKind::EOF);
+
+            next == TokenKind::LeftArrow || BinaryOp::from_kind(next).is_some()
+        }
+        Some(TokenKind::LeftPar) => {
+            // A parenthesised guard never holds a top level ',' while a shape tuple does.
+            let mut depth = 1;
+
+            for kind in kinds {
+                match kind {
+                    TokenKind::LeftPar => depth += 1,
+                    TokenKind::RightPar if depth == 1 => return true,
+                    TokenKind::RightPar => depth -= 1,
+                    TokenKind::Comma if depth == 1 => return false,
+                    TokenKind::EOF => return false,
+                    _ => {}
+                }
+            }
+
+            false
+        }
+        // Cut off right after the ',': either could follow, and an expression starts with
+        // anything a shape does.
+        Some(TokenKind::EOF) => tokens.is_partial(),
+        _ => false,
+    }
 }
 
 pub fn next_is_stmt(tokens: &mut Lexer) -> bool {
-    matches!(
-        tokens.peek().get_kind(),
-        TokenKind::Ident
-            | TokenKind::Object
-            | TokenKind::LetKwd
-            | TokenKind::ProblemExample
-            | TokenKind::ProblemSolution
-            | TokenKind::ProblemInput
-            | TokenKind::ProblemOutput
-    )
+    match_next_any(tokens, STMT_START)
+}
+
+/// True while a block goes on, which is up to its `}`, the end of the input or, with the
+/// `}` missing, the next declaration that can only be a top-level one.
+pub fn next_is_in_block(tokens: &mut Lexer) -> bool {
+    if next_is_stmt(tokens) {
+        return true;
+    }
+
+    if match_next(tokens, TokenKind::RightBrace) {
+        return false;
+    }
+
+    tokens.peek().get_kind() != TokenKind::EOF && !next_is_top_level_item(tokens)
+}
+
+/// True when the current token starts a declaration, i.e. a top-level keyword followed by
+/// the name being declared or included. A bare `object` may just as well be a data type.
+pub fn next_is_top_level_item(tokens: &mut Lexer) -> bool {
+    match_next_any(tokens, TOP_LEVEL_START) && tokens.lookahead().0 == TokenKind::Ident
 }
 
 pub fn match_next(tokens: &mut Lexer, matched: TokenKind) -> bool {
-    tokens.peek().get_kind() == matched
+    match_next_any(tokens, &[matched])
+}
+
+/// Whether the current token is one of `kinds`, all of which the production accepts here.
+pub fn match_next_any(tokens: &mut Lexer, kinds: &[TokenKind]) -> bool {
+    tokens.expect(kinds);
+
+    kinds.contains(&tokens.peek().get_kind())
+}
+
+/// The keywords among `kinds`, what an identifier found in their place is compared with
+/// when looking for a misspelling.
+pub fn keyword_spellings(kinds: &[TokenKind]) -> Vec<String> {
+    kinds
+        .iter()
+        .filter_map(|kind| kind.spelling())
+        .filter(|spelling| KEYWORDS.contains(&spelling.as_str()))
+        .collect()
+}
+
+/// The span of the current token alone.
+pub fn current_span(tokens: &Lexer) -> Span {
+    Span::new(tokens.get_previous_position(), tokens.get_position())
+}
+
+/// The syntax token of an identifier consumed at `span`, poisoned along with `id`. Nodes
+/// keep it to point at their name rather than at all of them.
+pub fn identifier_syntax(id: &ErminiaType, span: Span) -> SyntaxToken {
+    if id.is_poisoned() {
+        SyntaxToken::poisoned(TokenKind::Ident, span)
+    } else {
+        SyntaxToken::new(TokenKind::Ident, span)
+    }
+}
+
+/// The span of a node that started at `start` and ends with the last token consumed. It is
+/// empty when the node consumed nothing.
+pub fn node_span(tokens: &Lexer, start: PositionalOffset) -> Span {
+    Span::new(start, start.max(tokens.get_last_position()))
 }
 
 // ==================================================================================== //
@@ -147,28 +317,26 @@ pub fn match_next(tokens: &mut Lexer, matched: TokenKind) -> bool {
 // ==================================================================================== //
 
 // TODO: handle tuple & list types
-pub fn consume_data_type(
-    tokens: &mut Lexer,
-    diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
-) -> ErminiaType {
+pub fn consume_data_type(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> ErminiaType {
+    tokens.expect(DATA_TYPES);
+
     if tokens.is_poisoned() {
         return ErminiaType::Poisoned;
     }
 
     let token = tokens.token;
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = current_span(tokens);
 
-    // TODO: Map TokenKind to ErminiaType
-    println!("Consuming data type: {:?}", token.get_kind());
     let res = match token.get_kind() {
         TokenKind::Object => ErminiaType::Object,
+        TokenKind::IntType => ErminiaType::Int,
+        TokenKind::StringType => ErminiaType::String,
+        TokenKind::VoidType => ErminiaType::Void,
         TokenKind::Poisoned => {
             diag!(
                 Lexer,
-                E0002,
+                E0014,
                 ExpectedDataType(token.text.to_string()),
                 tokens,
                 diag,
@@ -184,12 +352,11 @@ pub fn consume_data_type(
                 ExpectedDataType(token.text.to_string()),
                 tokens,
                 diag,
-                span
+                span;
+                with_did_you_mean(token.text, current_span(tokens), &keyword_spellings(DATA_TYPES))
             );
 
-            tokens.set_poisoned(true);
-
-            tokens.loop_to_kind(TokenKind::SemiColon);
+            recover(tokens);
 
             return ErminiaType::Poisoned;
         }
@@ -200,19 +367,16 @@ pub fn consume_data_type(
     res
 }
 
-pub fn consume_int_const(
-    tokens: &mut Lexer,
-    diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
-) -> ErminiaType {
+pub fn consume_int_const(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> ErminiaType {
+    tokens.expect(&[TokenKind::Int]);
+
     if tokens.is_poisoned() {
         return ErminiaType::Poisoned;
     }
 
     let int_const = tokens.token;
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = current_span(tokens);
 
     let res = if int_const.get_kind() == TokenKind::Int {
         ErminiaType::Integer(int_const.text.parse::<i32>().unwrap())
@@ -226,9 +390,7 @@ pub fn consume_int_const(
             span
         );
 
-        tokens.set_poisoned(true);
-
-        tokens.loop_to_kind(TokenKind::SemiColon);
+        recover(tokens);
 
         return ErminiaType::Poisoned;
     };
@@ -238,35 +400,47 @@ pub fn consume_int_const(
     res
 }
 
-pub fn consume_identifier(
+// <signed_int_const> ::= ["-"] <int_const>
+pub fn consume_signed_int_const(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
 ) -> ErminiaType {
+    if !match_next(tokens, TokenKind::Minus) {
+        return consume_int_const(tokens, diag);
+    }
+
+    tokens.advance();
+
+    match consume_int_const(tokens, diag) {
+        ErminiaType::Integer(i) => ErminiaType::Integer(-i),
+        other => other,
+    }
+}
+
+pub fn consume_identifier(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> ErminiaType {
+    tokens.expect(&[TokenKind::Ident]);
+
     if tokens.is_poisoned() {
         return ErminiaType::Poisoned;
     }
 
     let id = tokens.token;
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = current_span(tokens);
 
     let res = match id.get_kind() {
         TokenKind::Ident => ErminiaType::Ident(id.text.to_string()),
         _ => {
             diag!(
                 Parser,
-                E0001,
+                E0022,
                 ExpectedIdentifier(id.text.to_string()),
                 tokens,
                 diag,
                 span
             );
 
-            tokens.set_poisoned(true);
-
-            tokens.loop_to_kind(TokenKind::SemiColon);
+            recover(tokens);
 
             return ErminiaType::Poisoned;
         }
@@ -281,31 +455,43 @@ pub fn consume_keyword(
     tokens: &mut Lexer,
     expected: TokenKind,
     diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
-) -> ErminiaType {
+) -> SyntaxToken {
+    tokens.expect(&[expected]);
+
     if tokens.is_poisoned() {
-        return ErminiaType::Poisoned;
+        // Recovery stopped right where this production goes on, the node that failed has
+        // already been marked and this one is intact from here on
+        if tokens.peek().get_kind() == expected {
+            let syntax = SyntaxToken::new(expected, current_span(tokens));
+
+            tokens.set_poisoned(false);
+            tokens.advance();
+
+            return syntax;
+        }
+
+        return SyntaxToken::poisoned(expected, current_span(tokens));
     }
 
     let token = tokens.token;
-
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = missing_token_span(tokens, expected);
 
     let res = if token.get_kind() == expected {
-        ErminiaType::Void
+        SyntaxToken::new(expected, current_span(tokens))
     } else {
         if let TokenKind::Poisoned = token.get_kind() {
             diag!(
                 Lexer,
-                E0001,
+                E0014,
                 ExpectedSomethingElse(expected.to_string(), token.text.to_string()),
                 tokens,
                 diag,
                 span
             );
 
-            return ErminiaType::Poisoned;
+            recover(tokens);
+
+            return SyntaxToken::poisoned(expected, current_span(tokens));
         }
 
         diag!(
@@ -314,17 +500,114 @@ pub fn consume_keyword(
             ExpectedSomethingElse(expected.to_string(), token.text.to_string()),
             tokens,
             diag,
-            span
+            span;
+            with_suggestions(missing_terminator_fix(tokens, expected)),
+            with_did_you_mean(token.text, current_span(tokens), &keyword_spellings(&[expected]))
         );
 
-        tokens.set_poisoned(true);
+        recover(tokens);
 
-        tokens.loop_to_kind(TokenKind::SemiColon);
+        let syntax = SyntaxToken::poisoned(expected, current_span(tokens));
 
-        return ErminiaType::Poisoned;
+        // The token was missing rather than wrong, pick up where it was expected
+        if tokens.peek().get_kind() == expected {
+            tokens.set_poisoned(false);
+            tokens.advance();
+        }
+
+        return syntax;
     };
 
     tokens.advance();
 
     res
 }
+
+// Where a token that was not found should have been: right after the token before it when
+// it was left out for sure, or else on what was found instead
+fn missing_token_span(tokens: &Lexer, expected: TokenKind) -> Span {
+    match missing_terminator_fix(tokens, expected) {
+        Some(fix) if fix.applicability == Applicability::MachineApplicable => fix.span,
+        _ => current_span(tokens),
+    }
+}
+
+// A `;` left out goes right after the token before it. It was left out for sure when what
+// comes instead ends the block or starts something else.
+fn missing_terminator_fix(tokens: &Lexer, expected: TokenKind) -> Option<Suggestion> {
+    if expected != TokenKind::SemiColon {
+        return None;
+    }
+
+    let found = tokens.token.get_kind();
+    let at = tokens.get_last_position();
+
+    let applicability = if matches!(found, TokenKind::RightBrace | TokenKind::EOF)
+        || STMT_START.contains(&found)
+        || TOP_LEVEL_START.contains(&found)
+    {
+        Applicability::MachineApplicable
+    } else {
+        Applicability::MaybeIncorrect
+    };
+
+    Some(Suggestion::new(Span::new(at, at), ";", applicability))
+}
+
+/// The `;` that ends a statement. A statement that went wrong ends here as well, so the
+/// next one is parsed with a clean slate.
+pub fn consume_terminator(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> SyntaxToken {
+    if !tokens.is_poisoned() {
+        return consume_keyword(tokens, TokenKind::SemiColon, diag);
+    }
+
+    synchronize(tokens);
+
+    // Closers left behind by the broken statement belong to nobody
+    while match_next(tokens, TokenKind::RightPar) || match_next(tokens, TokenKind::RightBracket) {
+        tokens.advance();
+        synchronize(tokens);
+    }
+
+    let syntax = SyntaxToken::poisoned(TokenKind::SemiColon, current_span(tokens));
+
+    if match_next(tokens, TokenKind::SemiColon) {
+        tokens.advance();
+    }
+
+    tokens.set_poisoned(false);
+
+    syntax
+}
+
+/// The end of the input, after a fragment that has to stand on its own. Whatever follows
+/// the fragment is skipped and reported at once.
+pub fn consume_eof(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator, fragment: &str) {
+    if match_next(tokens, TokenKind::EOF) {
+        return;
+    }
+
+    let start = tokens.get_previous_position();
+    let found = tokens.peek().text.to_string();
+
+    while !match_next(tokens, TokenKind::EOF) {
+        tokens.advance();
+    }
+
+    // Left over by a broken fragment, which has been reported already
+    if tokens.is_poisoned() {
+        return;
+    }
+
+    let span = Span::new(start, tokens.get_last_position());
+
+    diag!(
+        Parser,
+        E0013,
+        ExpectedEndOfInput(fragment.to_string(), found),
+        RemoveTrailingInput,
+        tokens,
+        diag,
+        span
+    );
+}
@@ -1,31 +1,187 @@
-use crate::ast::ast::BoxAST;
-use crate::diagnostics::DiagnosticAccumulator;
+use crate::ast::ast::{check_poisoning, BoxAST};
+use crate::diagnostics::code::DiagnosticLevel;
+use crate::diagnostics::{DiagnosticAccumulator, SourceMap};
+use crate::error::parser_error::{ParserError, ParserResult};
 use crate::lexer::lex::Lexer;
-use crate::syntax::parse::parse_program;
+use crate::lexer::token::{Token, TokenKind};
+use crate::syntax::consumers::consume_eof;
+use crate::syntax::parse::{
+    parse_expr, parse_list_of_shapes, parse_object_decl, parse_program, parse_shape,
+};
+use crate::syntax::recovery::{with_sync, STMT_SYNC};
 pub mod consumers;
+pub mod oracle;
 pub mod parse;
+pub mod recovery;
+
+/// Path the source map files input under when it did not come from a file.
+pub const INPUT_PATH: &str = "<input>";
 
 // ==================================================================================== //
 // Parser Object                                                                        //
 // ==================================================================================== //
 
 pub struct Parser<'a> {
+    input: &'a str,
     lexer: Lexer<'a>,
     diagnostics: DiagnosticAccumulator,
 }
 
+/// Everything a successful parse produced. The tree may still hold poisoned nodes, in
+/// which case `diagnostics` has the errors that poisoned them.
+#[derive(Debug)]
+pub struct ParseOutput<'a> {
+    pub ast: BoxAST<'a>,
+    pub diagnostics: DiagnosticAccumulator,
+    pub tokens: Vec<Token<'a>>,
+    pub source_map: SourceMap,
+}
+
+/// What was made of input that may stop anywhere, e.g. in the middle of a declaration.
+#[derive(Debug)]
+pub struct PartialParse<'a> {
+    /// Everything parsed before the input ran out. The nodes it ran out in are poisoned.
+    pub ast: BoxAST<'a>,
+    /// Errors in the input itself. Its end coming too early is not one of them.
+    pub diagnostics: DiagnosticAccumulator,
+    /// The kinds of token that could come next, in the order the parser asked for them.
+    pub expected: Vec<TokenKind>,
+}
+
 impl<'a> Parser<'a> {
     pub fn new<'input>(input: &'input str) -> Parser<'input> {
         let lexer = Lexer::new(input);
         let diagnostics = DiagnosticAccumulator::new();
-        Parser { lexer, diagnostics }
+        Parser {
+            input,
+            lexer,
+            diagnostics,
+        }
     }
 
     pub fn parse(&mut self) -> BoxAST<'a> {
         parse_program(&mut self.lexer, &mut self.diagnostics)
     }
 
+    /// Parses the input as a single object declaration, `object A { ... };`.
+    pub fn parse_object(&mut self) -> BoxAST<'a> {
+        let object = self.parse_fragment("object declaration", |tokens, diag| {
+            with_sync(tokens, STMT_SYNC, |tokens| parse_object_decl(tokens, diag))
+        });
+
+        check_poisoning(&object, &mut self.lexer, &mut self.diagnostics);
+
+        object
+    }
+
+    /// Parses the input as a single shape, `(0, 1)`, `Dot(1, 2)` or a comprehension.
+    pub fn parse_shape(&mut self) -> BoxAST<'a> {
+        let shape = self.parse_fragment("shape", parse_shape);
+
+        check_poisoning(&shape, &mut self.lexer, &mut self.diagnostics);
+
+        shape
+    }
+
+    /// Parses the input as a list of shapes, `[(0, 0), Dot(1, 2)]`.
+    pub fn parse_shape_list(&mut self) -> Vec<BoxAST<'a>> {
+        let (shapes, _) = self.parse_fragment("shape list", parse_list_of_shapes);
+
+        for shape in &shapes {
+            check_poisoning(shape, &mut self.lexer, &mut self.diagnostics);
+        }
+
+        shapes
+    }
+
+    /// Parses the input as a single expression.
+    pub fn parse_expr(&mut self) -> BoxAST<'a> {
+        let expr = self.parse_fragment("expression", parse_expr);
+
+        check_poisoning(&expr, &mut self.lexer, &mut self.diagnostics);
+
+        expr
+    }
+
+    // Fragments are checked to make up the whole input, `fragment` names them in the
+    // error otherwise.
+    fn parse_fragment<T, F>(&mut self, fragment: &str, parse: F) -> T
+    where
+        F: FnOnce(&mut Lexer<'a>, &mut DiagnosticAccumulator) -> T,
+    {
+        self.lexer.advance();
+
+        let res = parse(&mut self.lexer, &mut self.diagnostics);
+
+        consume_eof(&mut self.lexer, &mut self.diagnostics, fragment);
+
+        res
+    }
+
+    /// Parses the whole input. Syntax errors are recovered from and end up in the output's
+    /// diagnostics, only an internal error makes this fail.
+    pub fn parse_output(mut self) -> ParserResult<ParseOutput<'a>> {
+        let ast = self.parse();
+
+        if self.diagnostics.has_level(DiagnosticLevel::Internal) {
+            return Err(ParserError::Fatal(self.diagnostics.diagnostics));
+        }
+
+        let tokens = Lexer::new(self.input).lex_with_separate_pass();
+
+        let mut source_map = SourceMap::new();
+        source_map.add_file(INPUT_PATH, self.input.to_string());
+
+        Ok(ParseOutput {
+            ast,
+            diagnostics: self.diagnostics,
+            tokens,
+            source_map,
+        })
+    }
+
+    /// Parses input that may have been cut off at any point, such as an unfinished model
+    /// completion. Running out of input is not reported; instead the output tells what
+    /// could have come next.
+    pub fn parse_partial(mut self) -> PartialParse<'a> {
+        self.lexer.set_partial(true);
+
+        let ast = self.parse();
+
+        PartialParse {
+            ast,
+            diagnostics: self.diagnostics,
+            expected: self.lexer.get_expected().to_vec(),
+        }
+    }
+
     pub fn get_diagnostics(&self) -> &DiagnosticAccumulator {
         &self.diagnostics
     }
 }
+
+impl PartialParse<'_> {
+    /// True when the input is a whole, valid program as it is. Input with errors is never
+    /// complete, even where the parser recovered far enough to accept the end of it.
+    pub fn is_complete(&self) -> bool {
+        self.is_valid() && self.expected.contains(&TokenKind::EOF)
+    }
+
+    /// True when nothing is wrong with the input as far as it goes, so that it can still
+    /// be finished into a program.
+    pub fn is_valid(&self) -> bool {
+        self.diagnostics.error_count() == 0
+    }
+}
+
+impl ParseOutput<'_> {
+    /// True when the input had syntax errors, the tree then holds poisoned nodes.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.has_errors()
+    }
+}
+
+/// Parses `input` as a whole program, see `Parser::parse_output`.
+pub fn parse(input: &str) -> ParserResult<ParseOutput<'_>> {
+    Parser::new(input).parse_output()
+}
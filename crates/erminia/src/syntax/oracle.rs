@@ -0,0 +1,151 @@
+use crate::ast::stmt::{FuncDecl, ObjectDecl, Program, TupleComprehension, TupleIterator, VarDef};
+use crate::ast::visit::*;
+use crate::lexer::token::TokenKind;
+use crate::syntax::Parser;
+use crate::types::ErminiaType;
+
+// ==================================================================================== //
+// Next Token Oracle                                                                    //
+// ==================================================================================== //
+
+/// What may follow a prefix of a program. A sampler can mask every token that is not
+/// one of `kinds`, and every identifier that is not one of `identifiers` where the name
+/// has to refer to something.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextTokens {
+    /// The kinds of token the grammar allows next, `EOF` when the prefix may end here.
+    pub kinds: Vec<TokenKind>,
+    /// The names declared before the end of the prefix and visible from it.
+    pub identifiers: Vec<String>,
+    /// False when the prefix has an error in it. `kinds` then only tells where the parser
+    /// recovered to, which may be anything or nothing, and is no suggestion to offer.
+    pub valid: bool,
+}
+
+impl NextTokens {
+    pub fn allows(&self, kind: TokenKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    /// The text of every allowed token that can only be spelled one way, i.e. keywords
+    /// and punctuation. Identifiers and numbers are left to `kinds`.
+    pub fn spellings(&self) -> Vec<String> {
+        self.kinds
+            .iter()
+            .filter_map(|kind| kind.spelling())
+            .collect()
+    }
+}
+
+/// Tells what can come after `prefix`. The answer comes from a partial parse, so it
+/// follows `syntax::parse` exactly. The prefix is taken to end on a token boundary: a
+/// trailing `de` is the identifier `de`, not the start of `def`.
+pub fn next_tokens(prefix: &str) -> NextTokens {
+    let partial = Parser::new(prefix).parse_partial();
+
+    let mut kinds: Vec<TokenKind> = vec![];
+    for &kind in &partial.expected {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+
+    let mut scope = Scope {
+        complete: partial.is_complete(),
+        identifiers: vec![],
+    };
+    scope.visit_ast(&partial.ast);
+
+    NextTokens {
+        kinds,
+        identifiers: scope.identifiers,
+        valid: partial.is_valid(),
+    }
+}
+
+// ==================================================================================== //
+// Scope Collection                                                                     //
+// ==================================================================================== //
+
+// Finished items only contribute what they export. The item the prefix ends in also
+// contributes its locals, as the next token is inside it.
+struct Scope {
+    complete: bool,
+    identifiers: Vec<String>,
+}
+
+impl Scope {
+    fn declare(&mut self, id: &ErminiaType) {
+        if id.is_poisoned() {
+            return;
+        }
+
+        let name = id.to_id();
+        if !self.identifiers.contains(&name) {
+            self.identifiers.push(name);
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Scope {
+    fn visit_program(&mut self, node: &Program<'a>) {
+        let unfinished = if self.complete {
+            None
+        } else {
+            node.items.last()
+        };
+
+        for item in &node.items {
+            let exported = item
+                .declarations()
+                .into_iter()
+                .filter(|decl| decl.kind.is_exported());
+
+            for decl in exported {
+                if !self.identifiers.contains(&decl.name) {
+                    self.identifiers.push(decl.name);
+                }
+            }
+        }
+
+        if let Some(item) = unfinished {
+            self.visit_ast(item);
+        }
+    }
+
+    fn visit_func_decl(&mut self, node: &FuncDecl<'a>) {
+        self.declare(&node.id);
+
+        for param in &node.params {
+            self.declare(&param.id);
+        }
+
+        walk_func_decl(self, node)
+    }
+
+    fn visit_object_decl(&mut self, node: &ObjectDecl<'a>) {
+        self.declare(&node.id);
+
+        walk_object_decl(self, node)
+    }
+
+    // The generators of a finished comprehension are out of scope past its end
+    fn visit_tuple_comprehension(&mut self, node: &TupleComprehension<'a>) {
+        if node.is_poisoned {
+            walk_tuple_comprehension(self, node)
+        }
+    }
+
+    // A variable is not in scope in its own initializer
+    fn visit_var_def(&mut self, node: &VarDef<'a>) {
+        if !node.is_poisoned {
+            self.declare(&node.id);
+        }
+    }
+
+    fn visit_tuple_iterator(&mut self, node: &TupleIterator<'a>) {
+        self.declare(&node.id);
+
+        walk_tuple_iterator(self, node)
+    }
+}
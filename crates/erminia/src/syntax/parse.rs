@@ -1,132 +1,267 @@
-use crate::ast::ast::BoxAST;
+use crate::ast::ast::{check_poisoning, BoxAST};
 
 use crate::ast::expr::*;
 use crate::ast::stmt::*;
 use crate::diag;
-use crate::diagnostics::{DiagnosticAccumulator, Span};
-use crate::lexer::lex::Lexer;
-use crate::lexer::lex::PositionalOffset;
-use crate::lexer::token::TokenKind;
+use crate::diagnostics::{did_you_mean, DiagnosticAccumulator, Span};
+use crate::lexer::lex::{Lexer, MAX_NESTING};
+use crate::lexer::token::{SyntaxToken, TokenKind};
 use crate::syntax::consumers::*;
+use crate::syntax::recovery::*;
 use crate::types::ErminiaType;
 
 // ==================================================================================== //
 // Parsers                                                                              //
 // ==================================================================================== //
 
-// <expr> ::= <object_call> | <id> | <int_const>
-pub fn parse_expr<'a>(
+// <expr> ::= <unary_expr> (<binary_op> <unary_expr>)*
+pub fn parse_expr<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    parse_binary_expr(tokens, diag, 1)
+}
+
+// <binary_expr> ::= <unary_expr> (<binary_op> <binary_expr>)*
+// Operators bind according to `BinaryOp::precedence`, all of them left associative.
+pub fn parse_binary_expr<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
+    min_precedence: u8,
 ) -> BoxAST<'a> {
+    let start = tokens.get_previous_position();
+
+    let mut left = parse_unary_expr(tokens, diag);
+
+    // Every operator puts the operands before it one level deeper in the tree, so a long
+    // chain of them nests as deep as any parentheses would
+    let mut depth = 0;
+
+    while let Some(op) = next_binary_op(tokens) {
+        if op.precedence() < min_precedence {
+            break;
+        }
+
+        if !tokens.nest() {
+            left = give_up_nesting(tokens, diag);
+            break;
+        }
+
+        depth += 1;
+
+        let syntax = vec![consume_keyword(tokens, op.to_kind(), diag)];
+
+        let right = parse_binary_expr(tokens, diag, op.precedence() + 1);
+
+        let span = node_span(tokens, start);
+
+        left = BinaryExpr::boxed(tokens.next_node_id(), op, left, right, span, syntax);
+    }
+
+    for _ in 0..depth {
+        tokens.unnest();
+    }
+
+    left
+}
+
+// <unary_expr> ::= ("-" | "!") <unary_expr> | <primary_expr>
+// Every level of parentheses and unary operators comes back through here, which is where
+// the parser stops when they go too deep.
+pub fn parse_unary_expr<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    if !tokens.nest() {
+        return give_up_nesting(tokens, diag);
+    }
+
+    let expr = parse_nested_unary_expr(tokens, diag);
+
+    tokens.unnest();
+
+    expr
+}
+
+fn parse_nested_unary_expr<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    tokens.expect(EXPR_START);
+
+    let kind = tokens.peek().get_kind();
+
+    let start = tokens.get_previous_position();
+
+    match UnaryOp::from_kind(kind) {
+        Some(op) => {
+            let syntax = vec![consume_keyword(tokens, kind, diag)];
+
+            let operand = parse_unary_expr(tokens, diag);
+
+            let span = node_span(tokens, start);
+
+            UnaryExpr::boxed(tokens.next_node_id(), op, operand, span, syntax)
+        }
+        None => parse_primary_expr(tokens, diag),
+    }
+}
+
+// Nesting any deeper would risk running out of stack, so nothing after this point is parsed
+fn give_up_nesting<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let span = current_span(tokens);
+
+    diag!(
+        Parser,
+        I0002,
+        NestedTooDeeply(MAX_NESTING.to_string()),
+        SplitNestedExpression,
+        tokens,
+        diag,
+        span
+    );
+
+    tokens.give_up();
+
+    PoisonedStmt::boxed(tokens.next_node_id(), span)
+}
+
+// <primary_expr> ::= <object_call> | <id> | <int_const> | "(" <expr> ")"
+pub fn parse_primary_expr<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
     let kind = tokens.peek().get_kind();
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let start = tokens.get_previous_position();
 
     match kind {
-        TokenKind::Ident => {
-            let lookahead = tokens.lookahead();
+        TokenKind::Ident if matches!(tokens.lookahead().0, TokenKind::LeftPar) => {
+            parse_object_call(tokens, diag)
+        }
+        TokenKind::Ident | TokenKind::Int => parse_rvalue(tokens, diag),
+        TokenKind::LeftPar => {
+            let mut syntax: Vec<SyntaxToken> = vec![];
 
-            if matches!(lookahead.0, TokenKind::LeftPar) {
-                parse_object_call(tokens, diag)
-            } else {
-                let id = consume_identifier(tokens, diag, start);
-                RValue::boxed_id(id.to_string())
+            syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
+
+            let expr = parse_expr(tokens, diag);
+
+            syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
+
+            if syntax.iter().any(|s| s.is_poisoned()) {
+                return PoisonedStmt::boxed(tokens.next_node_id(), node_span(tokens, start));
             }
+
+            expr
+        }
+        // Still recovering from an error reported further up
+        _ if tokens.is_poisoned() => {
+            PoisonedStmt::boxed(tokens.next_node_id(), current_span(tokens))
         }
-        TokenKind::Int => RValue::boxed_int(consume_int_const(tokens, diag, start).to_int()),
         _ => {
+            let span = current_span(tokens);
+
             diag!(
                 Parser,
-                E0001,
+                E0023,
                 ExpectedIDorInteger(kind.to_string()),
                 tokens,
                 diag,
                 span
             );
 
-            parse_object_call(tokens, diag)
+            recover(tokens);
+
+            PoisonedStmt::boxed(tokens.next_node_id(), span)
         }
     }
 }
 
+// <rvalue> ::= <int_const> | <id>
+pub fn parse_rvalue<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let start = tokens.get_previous_position();
+
+    let value = if match_next(tokens, TokenKind::Int) {
+        consume_int_const(tokens, diag)
+    } else {
+        consume_identifier(tokens, diag)
+    };
+
+    match value {
+        ErminiaType::Integer(i) => {
+            RValue::boxed_int(tokens.next_node_id(), i, node_span(tokens, start))
+        }
+        ErminiaType::Ident(id) => {
+            RValue::boxed_id(tokens.next_node_id(), id, node_span(tokens, start))
+        }
+        _ => PoisonedStmt::boxed(tokens.next_node_id(), current_span(tokens)),
+    }
+}
+
 // <list_of_exprs> ::= <expr> ("," <expr>)*
 pub fn parse_list_of_exprs<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (Vec<BoxAST<'a>>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
+    tokens.push_sync(LIST_SYNC);
 
     let mut exprs: Vec<BoxAST> = vec![];
 
     while next_is_expr(tokens) {
-        let expr = parse_expr(tokens, diag, start);
+        let expr = parse_expr(tokens, diag);
 
         exprs.push(expr);
 
-        let next = tokens.peek().get_kind();
-
-        if matches!(next, TokenKind::Comma) {
-            syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
+        if next_is_comma(tokens) {
+            syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
         }
     }
 
+    tokens.pop_sync();
+
     (exprs, syntax)
 }
 
 // <func_call> ::= <id> "(" [<list_of_exprs>] ")" ";"
 pub fn parse_func_call<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
     let (exprs, inner_syntax) = parse_list_of_exprs(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
-
-    tokens.set_poisoned(false);
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    syntax.push(consume_terminator(tokens, diag));
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    FuncCall::boxed(id, exprs, span, syntax)
+    FuncCall::boxed(tokens.next_node_id(), id, exprs, span, syntax)
 }
 
 // <inner_stmt> ::= <object_decl> | <var_def> | <func_call>
 pub fn parse_inner_stmt<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
     let kind = tokens.peek().get_kind();
 
-    match kind {
+    with_sync(tokens, STMT_SYNC, |tokens| match kind {
         TokenKind::Object => parse_object_decl(tokens, diag),
         TokenKind::LetKwd => parse_var_def(tokens, diag),
         TokenKind::Ident => parse_func_call(tokens, diag),
         TokenKind::ProblemInput => parse_problem_input(tokens, diag),
         TokenKind::ProblemOutput => parse_problem_output(tokens, diag),
-        _ => parse_object_decl(tokens, diag),
-    }
+        _ => parse_unexpected_stmt(tokens, diag, &keyword_spellings(STMT_START)),
+    })
 }
 
 // <inner_stmt_list> ::= (<inner_stmt>)*
+// Goes on up to the `}` of the block, like `parse_stmt_list`.
 pub fn parse_inner_stmt_list<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> Vec<BoxAST<'a>> {
     let mut stmts: Vec<BoxAST> = vec![];
-    while next_is_stmt(tokens) {
+    while next_is_in_block(tokens) {
+        let before = tokens.get_previous_position().get_cursor();
         let stmt = parse_inner_stmt(tokens, diag);
         stmts.push(stmt);
+        ensure_progress(tokens, before);
     }
     stmts
 }
@@ -135,265 +270,461 @@ pub fn parse_inner_stmt_list<'a>(
 pub fn parse_inner_compound_stmt<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (Vec<BoxAST<'a>>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
-
-    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag));
     let stmts = parse_inner_stmt_list(tokens, diag);
-    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag));
     (stmts, syntax)
 }
 
 // TODO: handle type inference
 // <var_def> ::= "let" <id> ":" <data_type> "=" <expr> ";"
 pub fn parse_var_def<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
     let mut data_type: ErminiaType = ErminiaType::default();
 
-    syntax.push(consume_keyword(tokens, TokenKind::LetKwd, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LetKwd, diag));
+
+    let id = with_sync(tokens, VAR_DEF_SYNC, |tokens| {
+        let id = consume_identifier(tokens, diag);
+
+        if match_next(tokens, TokenKind::Colon) {
+            syntax.push(consume_keyword(tokens, TokenKind::Colon, diag));
+
+            // change here if it's explicit about data type
+            data_type = consume_data_type(tokens, diag);
+        }
+
+        id
+    });
+
+    syntax.push(consume_keyword(tokens, TokenKind::Equals, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let expr = parse_expr(tokens, diag);
 
-    if match_next(tokens, TokenKind::Colon) {
-        syntax.push(consume_keyword(tokens, TokenKind::Colon, diag, start));
+    syntax.push(consume_terminator(tokens, diag));
 
-        // change here if it's explicit about data type
-        data_type = consume_data_type(tokens, diag, start);
+    let span = node_span(tokens, start);
+
+    VarDef::boxed(tokens.next_node_id(), id, data_type, expr, span, syntax)
+}
+
+// <range_expr> ::= <range> ("U" <range>)*
+pub fn parse_range_expr<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+
+    let start = tokens.get_previous_position();
+
+    let range = parse_range(tokens, diag);
+
+    if !match_next(tokens, TokenKind::RangeUnion) {
+        return range;
     }
 
-    syntax.push(consume_keyword(tokens, TokenKind::Equals, diag, start));
+    let mut ranges: Vec<BoxAST> = vec![range];
 
-    let expr = parse_expr(tokens, diag, start);
+    while match_next(tokens, TokenKind::RangeUnion) {
+        syntax.push(consume_keyword(tokens, TokenKind::RangeUnion, diag));
 
-    tokens.set_poisoned(false);
+        let range = parse_range(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+        ranges.push(range);
+    }
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    VarDef::boxed(id, data_type, expr, span, syntax)
+    RangeUnion::boxed(tokens.next_node_id(), ranges, span, syntax)
 }
 
-// <range> ::= ("[" | "(") <int_const> ".." <int_const> ("]" | ")")
+// <range> ::= ("[" | "(") <expr> ".." <expr> ["by" <expr>] ("]" | ")")
 pub fn parse_range<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    let is_left_inclusive = is_next_left_inclusive(tokens, diag, start);
+    let is_left_inclusive = is_next_left_inclusive(tokens, diag);
 
-    let left = consume_int_const(tokens, diag, start);
+    let left = parse_expr(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::Range, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Range, diag));
 
-    let right = consume_int_const(tokens, diag, start);
+    let right = parse_expr(tokens, diag);
 
-    let is_right_inclusive = is_next_right_inclusive(tokens, diag, start);
+    let mut step: Option<BoxAST> = None;
 
-    let end = tokens.get_position();
+    if match_next(tokens, TokenKind::RangeStep) {
+        syntax.push(consume_keyword(tokens, TokenKind::RangeStep, diag));
+
+        step = Some(parse_expr(tokens, diag));
+    }
 
-    let span = Span::new(start, end);
+    let is_right_inclusive = is_next_right_inclusive(tokens, diag);
+
+    let span = node_span(tokens, start);
 
     Range::boxed(
+        tokens.next_node_id(),
         is_left_inclusive,
         is_right_inclusive,
         left,
         right,
+        step,
         span,
         syntax,
     )
 }
 
-// <shape_tuple_iter> ::= <id> "<-" <range>
+// <shape_tuple_iter> ::= <id> "<-" <range_expr>
 pub fn parse_shape_tuple_iter<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
-    let coord = consume_identifier(tokens, diag, start);
+    let coord = consume_identifier(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftArrow, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftArrow, diag));
 
-    let range = parse_range(tokens, diag);
+    let range = parse_range_expr(tokens, diag);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    TupleIterator::boxed(coord, range, span, syntax)
+    TupleIterator::boxed(tokens.next_node_id(), coord, range, span, syntax)
 }
 
-// <shape_tuple_iter_pair> ::= <shape_tuple_iter> ("," <shape_tuple_iter>)
-pub fn parse_shape_tuple_iter_pair<'a>(
+// <shape_tuple_compr_clauses> ::= <shape_tuple_compr_clause> ("," <shape_tuple_compr_clause>)*
+// <shape_tuple_compr_clause> ::= <shape_tuple_iter> | <expr>
+pub fn parse_shape_tuple_compr_clauses<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (Vec<BoxAST<'a>>, Vec<BoxAST<'a>>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
+    tokens.push_sync(LIST_SYNC);
 
-    let mut pairs: Vec<BoxAST> = vec![];
+    let mut generators: Vec<BoxAST> = vec![];
+    let mut predicates: Vec<BoxAST> = vec![];
 
-    let first_tuple_iter = parse_shape_tuple_iter(tokens, diag);
+    loop {
+        if next_is_generator(tokens) {
+            let generator = parse_shape_tuple_iter(tokens, diag);
+            generators.push(generator);
+        } else {
+            let predicate = parse_expr(tokens, diag);
+            predicates.push(predicate);
+        }
 
-    pairs.push(first_tuple_iter);
+        if !next_is_comprehension_clause(tokens) {
+            break;
+        }
 
-    if next_is_comma(tokens) {
-        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
-        let second_tuple_iter = parse_shape_tuple_iter(tokens, diag);
-        pairs.push(second_tuple_iter);
+        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
     }
 
-    (pairs, syntax)
+    tokens.pop_sync();
+
+    (generators, predicates, syntax)
 }
 
-// <shape_tuple_compr> ::= <shape_tuple> "|" <shape_tuple_iter_pair>
+// <shape_tuple_compr> ::= <shape_tuple_generic> "|" <shape_tuple_compr_clauses>
 pub fn parse_shape_tuple_compr<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
     let tuple = parse_shape_tuple_generic(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::Pipe, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Pipe, diag));
 
-    let (iter_pair, inner_syntax) = parse_shape_tuple_iter_pair(tokens, diag);
+    let (generators, predicates, inner_syntax) = parse_shape_tuple_compr_clauses(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    TupleComprehension::boxed(tuple, iter_pair, span, syntax)
+    TupleComprehension::boxed(
+        tokens.next_node_id(),
+        tuple,
+        generators,
+        predicates,
+        span,
+        syntax,
+    )
 }
 
-// <object_call> ::= <id> <shape_tuple>
+// <object_call> ::= <id> ["(" [<object_call_args>] ")"]
 pub fn parse_object_call<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    let id = consume_identifier(tokens, diag, start);
+    let id_span = current_span(tokens);
+    let id = consume_identifier(tokens, diag);
 
-    match tokens.peek().get_kind() {
-        TokenKind::LeftPar => {
-            let tuple = parse_shape_tuple(tokens, diag);
+    // The name alone, for what refers to the object called rather than the whole call
+    syntax.push(identifier_syntax(&id, id_span));
 
-            let end = tokens.get_position();
-            let span = Span::new(start, end);
+    let mut offset_x = ErminiaType::Integer(0);
+    let mut offset_y = ErminiaType::Integer(0);
 
-            ObjectCall::boxed(id, Some(tuple), span, syntax)
-        }
-        _ => {
-            let end = tokens.get_position();
-            let span = Span::new(start, end);
+    if match_next(tokens, TokenKind::LeftPar) {
+        syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
-            ObjectCall::boxed(id, None, span, syntax)
-        }
+        let (offsets, inner_syntax) = parse_object_call_args(tokens, diag, &id);
+
+        [offset_x, offset_y] = offsets;
+
+        syntax.extend(inner_syntax);
+
+        syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
     }
+
+    let span = node_span(tokens, start);
+
+    ObjectCall::boxed(tokens.next_node_id(), id, offset_x, offset_y, span, syntax)
 }
 
-// <shape_tuple_generic> ::= "(" (<int_const> | <id>) "," (<int_const> | <id>) ")"
-pub fn parse_shape_tuple_generic<'a>(
+/// The names an object call argument can have.
+const OBJECT_CALL_ARGUMENTS: &[&str] = &["offset_x", "offset_y", "x", "y"];
+
+// <object_call_args> ::= <signed_int_const> ["," <signed_int_const>] | <offset_arg> ("," <offset_arg>)*
+// <offset_arg> ::= ("offset_x" | "x" | "offset_y" | "y") ":" <signed_int_const>
+// Offsets that are left out default to 0.
+pub fn parse_object_call_args(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    id: &ErminiaType,
+) -> ([ErminiaType; 2], Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
+    tokens.push_sync(LIST_SYNC);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    let mut offsets: [Option<ErminiaType>; 2] = [None, None];
+    let mut given: [Option<Span>; 2] = [None, None];
+    let mut positional = 0;
+    let mut named = 0;
+    let mut extra_span: Option<Span> = None;
 
-    let left: BoxAST;
-    let right: BoxAST;
+    while match_next_any(
+        tokens,
+        &[TokenKind::Int, TokenKind::Minus, TokenKind::Ident],
+    ) {
+        let arg_start = tokens.get_previous_position();
+
+        if match_next(tokens, TokenKind::Ident) {
+            let name_span = current_span(tokens);
+            let name = consume_identifier(tokens, diag);
+
+            syntax.push(consume_keyword(tokens, TokenKind::Colon, diag));
+
+            let value = consume_signed_int_const(tokens, diag);
+            let span = node_span(tokens, arg_start);
+
+            named += 1;
+
+            let slot = match name.to_id().as_str() {
+                "offset_x" | "x" => Some(0),
+                "offset_y" | "y" => Some(1),
+                _ => None,
+            };
+
+            if positional > 0 {
+                diag!(
+                    Parser,
+                    E0007,
+                    MixedObjectArguments(id.to_id()),
+                    UseEitherPositionalOrNamed,
+                    tokens,
+                    diag,
+                    span
+                );
+
+                syntax.push(SyntaxToken::poisoned(TokenKind::Ident, span));
+            } else {
+                match slot {
+                    None => {
+                        diag!(
+                            Parser,
+                            E0005,
+                            UnknownObjectArgument(id.to_id(), name.to_id()),
+                            DidYouMeanOffsetArgument,
+                            tokens,
+                            diag,
+                            span;
+                            with_did_you_mean(&name.to_id(), name_span, OBJECT_CALL_ARGUMENTS)
+                        );
+
+                        syntax.push(SyntaxToken::poisoned(TokenKind::Ident, span));
+                    }
+                    Some(i) if offsets[i].is_some() => {
+                        diag!(
+                            Parser,
+                            E0006,
+                            DuplicateObjectArgument(id.to_id(), name.to_id()),
+                            RemoveDuplicateArgument,
+                            tokens,
+                            diag,
+                            span;
+                            with_label(given[i].unwrap_or(span), "first given here")
+                        );
+
+                        syntax.push(SyntaxToken::poisoned(TokenKind::Ident, span));
+                    }
+                    Some(i) => {
+                        offsets[i] = Some(value);
+                        given[i] = Some(span);
+                    }
+                }
+            }
+        } else {
+            let value = consume_signed_int_const(tokens, diag);
+            let span = node_span(tokens, arg_start);
+
+            if named > 0 {
+                diag!(
+                    Parser,
+                    E0007,
+                    MixedObjectArguments(id.to_id()),
+                    UseEitherPositionalOrNamed,
+                    tokens,
+                    diag,
+                    span
+                );
+
+                syntax.push(SyntaxToken::poisoned(TokenKind::Int, span));
+            } else if positional >= 2 {
+                extra_span.get_or_insert(span);
+
+                syntax.push(SyntaxToken::poisoned(TokenKind::Int, span));
+            } else {
+                offsets[positional] = Some(value);
+            }
 
-    if match_next(tokens, TokenKind::Int) {
-        let int_const = consume_int_const(tokens, diag, start);
+            positional += 1;
+        }
 
-        left = GenericTupleOption::boxed_int(int_const);
-    } else if match_next(tokens, TokenKind::Ident) {
-        let id = consume_identifier(tokens, diag, start);
+        if !next_is_comma(tokens) {
+            break;
+        }
 
-        left = GenericTupleOption::boxed_id(id);
-    } else {
+        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
+    }
+
+    if let Some(span) = extra_span {
         diag!(
             Parser,
-            E0003,
-            ExpectedIDorInteger(tokens.peek().get_kind().to_string()),
+            E0008,
+            TooManyObjectArguments(id.to_id(), positional.to_string()),
             tokens,
             diag,
-            Span::default()
+            span
         );
-
-        left = GenericTupleOption::boxed_int(ErminiaType::Poisoned);
     }
 
-    syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
+    let [offset_x, offset_y] = offsets;
 
-    if match_next(tokens, TokenKind::Int) {
-        let int_const = consume_int_const(tokens, diag, start);
+    tokens.pop_sync();
 
-        right = GenericTupleOption::boxed_int(int_const);
-    } else if match_next(tokens, TokenKind::Ident) {
-        let id = consume_identifier(tokens, diag, start);
+    (
+        [
+            offset_x.unwrap_or(ErminiaType::Integer(0)),
+            offset_y.unwrap_or(ErminiaType::Integer(0)),
+        ],
+        syntax,
+    )
+}
 
-        right = GenericTupleOption::boxed_id(id);
-    } else {
-        diag!(
-            Parser,
-            E0003,
-            ExpectedIDorInteger(tokens.peek().get_kind().to_string()),
-            tokens,
-            diag,
-            Span::default()
-        );
+// <shape_tuple_generic> ::= "(" (<int_const> | <id>) "," (<int_const> | <id>) ")"
+pub fn parse_shape_tuple_generic<'a>(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+) -> BoxAST<'a> {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+
+    let start = tokens.get_previous_position();
 
-        right = GenericTupleOption::boxed_int(ErminiaType::Poisoned);
+    tokens.push_sync(LIST_SYNC);
+
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
+
+    let left = parse_generic_tuple_value(tokens, diag);
+
+    syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
+
+    let right = parse_generic_tuple_value(tokens, diag);
+
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
+
+    let span = node_span(tokens, start);
+
+    tokens.pop_sync();
+
+    GenericTuple::boxed(tokens.next_node_id(), left, right, span, syntax)
+}
+
+// <generic_tuple_value> ::= <int_const> | <id>
+pub fn parse_generic_tuple_value<'a>(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+) -> BoxAST<'a> {
+    if match_next(tokens, TokenKind::Int) || match_next(tokens, TokenKind::Ident) {
+        return parse_rvalue(tokens, diag);
     }
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
+    let span = current_span(tokens);
+
+    diag!(
+        Parser,
+        E0023,
+        ExpectedIDorInteger(tokens.peek().get_kind().to_string()),
+        tokens,
+        diag,
+        span
+    );
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    recover(tokens);
 
-    GenericTuple::boxed(left, right, span, syntax)
+    PoisonedStmt::boxed(tokens.next_node_id(), span)
 }
 
 // <shape_tuple> ::= "(" <int_const> "," <int_const> ")"
 pub fn parse_shape_tuple<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    tokens.push_sync(LIST_SYNC);
+
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
-    let left = consume_int_const(tokens, diag, start);
+    let left = consume_int_const(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
 
-    let right = consume_int_const(tokens, diag, start);
+    let right = consume_int_const(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
 
-    let end = tokens.get_position();
+    let span = node_span(tokens, start);
 
-    let span = Span::new(start, end);
+    tokens.pop_sync();
 
-    Tuple::boxed(left, right, span, syntax)
+    Tuple::boxed(tokens.next_node_id(), left, right, span, syntax)
 }
 
 // <shape> ::= <shape_tuple> | <shape_tuple_compr> | <object_call> | <id>
 pub fn parse_shape<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    tokens.expect(&[TokenKind::LeftPar, TokenKind::Ident]);
+
     let kind = tokens.peek().get_kind();
 
     match kind {
@@ -412,133 +743,163 @@ pub fn parse_shape<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) ->
         _ => {
             diag!(
                 Parser,
-                E0003,
+                E0025,
                 ExpectedTypeofTuple(kind.to_string()),
                 tokens,
                 diag,
-                Span::default()
+                current_span(tokens)
             );
 
-            //TODO: Do some sort of skip here
-            while !match_next(tokens, TokenKind::Colon) {
-                tokens.advance();
-            }
+            recover(tokens);
 
-            PoisonedStmt::boxed(Span::default())
+            PoisonedStmt::boxed(tokens.next_node_id(), current_span(tokens))
         }
     }
 }
 
 // <object_color> ::= "color" ":" <int_const>
 pub fn parse_object_color<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(tokens, TokenKind::ObjectColor, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::ObjectColor, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::Colon, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Colon, diag));
 
-    let int_const = consume_int_const(tokens, diag, start);
+    let int_const = consume_int_const(tokens, diag);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    ObjectColor::boxed(int_const, span, syntax)
+    ObjectColor::boxed(tokens.next_node_id(), int_const, span, syntax)
 }
 
 // <list_of_shapes> ::= "[" <shape> ("," <shape>)* "]"
 pub fn parse_list_of_shapes<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (Vec<BoxAST<'a>>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
+    tokens.push_sync(LIST_SYNC);
 
     let mut shapes: Vec<BoxAST> = vec![];
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftBracket, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftBracket, diag));
 
     let shape = parse_shape(tokens, diag);
 
     shapes.push(shape);
 
     while next_is_comma(tokens) {
-        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
+        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
 
         let shape = parse_shape(tokens, diag);
 
         shapes.push(shape);
     }
-    syntax.push(consume_keyword(
-        tokens,
-        TokenKind::RightBracket,
-        diag,
-        start,
-    ));
+    syntax.push(consume_keyword(tokens, TokenKind::RightBracket, diag));
+
+    tokens.pop_sync();
 
     (shapes, syntax)
 }
 
 // <object_shape> ::= "shape" ":" <list_of_shapes>
 pub fn parse_object_shape<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(tokens, TokenKind::ObjectShape, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::ObjectShape, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::Colon, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Colon, diag));
 
     let (shapes, inner_syntax) = parse_list_of_shapes(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    ObjectShape::boxed(shapes, span, syntax)
+    ObjectShape::boxed(tokens.next_node_id(), shapes, span, syntax)
 }
 
 // <object_desc> ::= <object_shape> "," <object_color> | <object_color> "," <object_shape>
 pub fn parse_object_desc<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
-
-    let kind = tokens.peek().get_kind();
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
+    let desc = with_sync(tokens, OBJECT_DESC_SYNC, |tokens| {
+        let (first_kind, first) = parse_object_field(tokens, diag);
+
+        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
+
+        // The field left is the other one, or either when the first was not a field
+        let (second_kind, second) = match first_kind {
+            TokenKind::ObjectShape => (TokenKind::ObjectColor, parse_object_color(tokens, diag)),
+            TokenKind::ObjectColor => (TokenKind::ObjectShape, parse_object_shape(tokens, diag)),
+            _ => parse_object_field(tokens, diag),
+        };
+
+        let (shape, color) = match (first_kind, second_kind) {
+            (TokenKind::ObjectColor, _) | (_, TokenKind::ObjectShape) => (second, first),
+            _ => (first, second),
+        };
+
+        let span = node_span(tokens, start);
+
+        ObjectDesc::boxed(tokens.next_node_id(), shape, color, span, syntax)
+    });
+
+    // Whatever is left of a broken description, such as a field given twice, goes up to
+    // its `}`
+    if tokens.is_poisoned() {
+        synchronize(tokens);
+    }
+
+    desc
+}
+
+// <object_shape> | <object_color>, along with the keyword it starts with. Anything else is
+// reported and skipped up to the next field or the end of the description, so that each
+// field gets an error of its own.
+fn parse_object_field<'a>(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+) -> (TokenKind, BoxAST<'a>) {
+    let fields = [TokenKind::ObjectShape, TokenKind::ObjectColor];
+
+    tokens.expect(&fields);
+
+    let kind = tokens.peek().get_kind();
+
     match kind {
-        TokenKind::ObjectShape => {
-            let shape = parse_object_shape(tokens, diag);
-            syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
-            let color = parse_object_color(tokens, diag);
-            let end = tokens.get_position();
-            let span = Span::new(start, end);
-
-            ObjectDesc::boxed(shape, color, span, syntax)
-        }
-        TokenKind::ObjectColor => {
-            let color = parse_object_color(tokens, diag);
-            syntax.push(consume_keyword(tokens, TokenKind::Comma, diag, start));
-            let shape = parse_object_shape(tokens, diag);
-            let end = tokens.get_position();
-            let span = Span::new(start, end);
-
-            ObjectDesc::boxed(shape, color, span, syntax)
-        }
+        TokenKind::ObjectShape => (kind, parse_object_shape(tokens, diag)),
+        TokenKind::ObjectColor => (kind, parse_object_color(tokens, diag)),
         _ => {
-            diag!(
-                Parser,
-                E0003,
-                ExpectedShapeOrColor(kind.to_string()),
-                tokens,
-                diag,
-                Span::default()
-            );
+            if !tokens.is_poisoned() {
+                diag!(
+                    Parser,
+                    E0019,
+                    ExpectedShapeOrColor(tokens.token.text.to_string()),
+                    tokens,
+                    diag,
+                    current_span(tokens);
+                    with_did_you_mean(
+                        tokens.token.text,
+                        current_span(tokens),
+                        &keyword_spellings(&fields)
+                    )
+                );
+            }
+
+            recover(tokens);
 
-            PoisonedStmt::boxed(Span::default())
+            (
+                TokenKind::Poisoned,
+                PoisonedStmt::boxed(tokens.next_node_id(), current_span(tokens)),
+            )
         }
     }
 }
@@ -548,37 +909,29 @@ pub fn parse_problem_example<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(
-        tokens,
-        TokenKind::ProblemExample,
-        diag,
-        start,
-    ));
+    syntax.push(consume_keyword(tokens, TokenKind::ProblemExample, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
-    let int_const = consume_int_const(tokens, diag, start);
+    let int_const = consume_int_const(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
 
     let (stmts, inner_syntax) = parse_inner_compound_stmt(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    tokens.set_poisoned(false);
-
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    syntax.push(consume_terminator(tokens, diag));
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    ProblemExample::boxed(id, int_const, stmts, span, syntax)
+    ProblemExample::boxed(tokens.next_node_id(), id, int_const, stmts, span, syntax)
 }
 
 // <problem_solution> ::= "solution" <id> '(' <int_const> ')' <inner_compound_stmt> ';'
@@ -586,53 +939,40 @@ pub fn parse_problem_solution<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(
-        tokens,
-        TokenKind::ProblemSolution,
-        diag,
-        start,
-    ));
+    syntax.push(consume_keyword(tokens, TokenKind::ProblemSolution, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
-    let int_const = consume_int_const(tokens, diag, start);
+    let int_const = consume_int_const(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
 
     let (stmts, inner_syntax) = parse_inner_compound_stmt(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    tokens.set_poisoned(false);
+    syntax.push(consume_terminator(tokens, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    let span = node_span(tokens, start);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
-
-    ProblemSolution::boxed(id, int_const, stmts, span, syntax)
+    ProblemSolution::boxed(tokens.next_node_id(), id, int_const, stmts, span, syntax)
 }
 
 // <problem_input> ::= "input" <id> <tuple> <inner_compound_stmt> ';'
 pub fn parse_problem_input<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(
-        tokens,
-        TokenKind::ProblemInput,
-        diag,
-        start,
-    ));
+    syntax.push(consume_keyword(tokens, TokenKind::ProblemInput, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
     let tuple = parse_shape_tuple(tokens, diag);
 
@@ -640,14 +980,11 @@ pub fn parse_problem_input<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumula
 
     syntax.extend(inner_syntax);
 
-    tokens.set_poisoned(false);
+    syntax.push(consume_terminator(tokens, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    let span = node_span(tokens, start);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
-
-    ProblemInput::boxed(id, tuple, stmts, span, syntax)
+    ProblemInput::boxed(tokens.next_node_id(), id, tuple, stmts, span, syntax)
 }
 
 // <problem_output> ::= "output" <id> <tuple> <inner_compound_stmt> ';'
@@ -655,17 +992,12 @@ pub fn parse_problem_output<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(
-        tokens,
-        TokenKind::ProblemOutput,
-        diag,
-        start,
-    ));
+    syntax.push(consume_keyword(tokens, TokenKind::ProblemOutput, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
     let tuple = parse_shape_tuple(tokens, diag);
 
@@ -673,72 +1005,88 @@ pub fn parse_problem_output<'a>(
 
     syntax.extend(inner_syntax);
 
-    tokens.set_poisoned(false);
-
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    syntax.push(consume_terminator(tokens, diag));
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
+    let span = node_span(tokens, start);
 
-    ProblemOutput::boxed(id, tuple, stmts, span, syntax)
+    ProblemOutput::boxed(tokens.next_node_id(), id, tuple, stmts, span, syntax)
 }
 
 // <stmt> ::= <object_decl> | <example_decl> | <var_def> | <problem_solution> |
-// <problem_input> | <problem_output>
-pub fn parse_stmt<'a>(
-    tokens: &mut Lexer,
-    diag: &mut DiagnosticAccumulator,
-    start: PositionalOffset,
-) -> BoxAST<'a> {
+// <problem_input> | <problem_output> | <func_decl> | <func_call>
+pub fn parse_stmt<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
     let kind = tokens.peek().get_kind();
 
-    let end = tokens.get_previous_position();
+    tokens.push_sync(STMT_SYNC);
+
+    let keywords = keyword_spellings(STMT_START);
 
-    let span = Span::new(start, end);
+    // An identifier is a call, unless it is a misspelled keyword rather than a function
+    let misspelled = kind == TokenKind::Ident
+        && tokens.lookahead().0 != TokenKind::LeftPar
+        && did_you_mean(tokens.peek().text, &keywords).is_some();
 
     let node = match kind {
+        TokenKind::Ident if !misspelled => parse_func_call(tokens, diag),
         TokenKind::Object => parse_object_decl(tokens, diag),
         TokenKind::ProblemExample => parse_problem_example(tokens, diag),
         TokenKind::ProblemSolution => parse_problem_solution(tokens, diag),
         TokenKind::ProblemInput => parse_problem_input(tokens, diag),
         TokenKind::ProblemOutput => parse_problem_output(tokens, diag),
         TokenKind::LetKwd => parse_var_def(tokens, diag),
-        _ => {
-            diag!(
-                Parser,
-                E0002,
-                ExpectedStatement(kind.to_string()),
-                DidYouMeanStmtKeyword,
-                tokens,
-                diag,
-                span
-            );
-
-            // TODO: Do some sort of skip here
-            while !match_next(tokens, TokenKind::Colon) {
-                tokens.advance();
-            }
-
-            // Return a poisoned node
-            PoisonedStmt::boxed(span)
-        }
+        TokenKind::FuncKwd => parse_func_decl(tokens, diag),
+        _ => parse_unexpected_stmt(tokens, diag, &keywords),
     };
 
+    tokens.pop_sync();
+
     node
 }
 
+// Something in a block that starts no statement. It is skipped up to where the next
+// statement can start, so that the rest of the block is still parsed.
+fn parse_unexpected_stmt<'a>(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+    keywords: &[String],
+) -> BoxAST<'a> {
+    let span = current_span(tokens);
+
+    // Still recovering from an error reported further up
+    if !tokens.is_poisoned() {
+        diag!(
+            Parser,
+            E0024,
+            ExpectedStatement(tokens.token.text.to_string()),
+            DidYouMeanStmtKeyword,
+            tokens,
+            diag,
+            span;
+            with_did_you_mean(tokens.token.text, span, keywords)
+        );
+    }
+
+    recover(tokens);
+
+    consume_terminator(tokens, diag);
+
+    PoisonedStmt::boxed(tokens.next_node_id(), span)
+}
+
 // <stmts_list> ::= (<stmt>)*
+// Goes on up to the `}` of the block, so that a stray token is reported as such rather
+// than taken for its end.
 pub fn parse_stmt_list<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
 ) -> Vec<BoxAST<'a>> {
     let mut stmts: Vec<BoxAST> = vec![];
 
-    let start = tokens.get_previous_position();
-
-    while next_is_stmt(tokens) {
-        let stmt = parse_stmt(tokens, diag, start);
+    while next_is_in_block(tokens) {
+        let before = tokens.get_previous_position().get_cursor();
+        let stmt = parse_stmt(tokens, diag);
         stmts.push(stmt);
+        ensure_progress(tokens, before);
     }
 
     stmts
@@ -748,39 +1096,37 @@ pub fn parse_stmt_list<'a>(
 pub fn parse_object_compound_desc<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (BoxAST<'a>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (BoxAST<'a>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
-
-    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag));
     let object_desc = parse_object_desc(tokens, diag);
-    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag));
     (object_desc, syntax)
 }
 
 // <object_decl> ::= "object" <id> <object_compound_desc> ";"
 pub fn parse_object_decl<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(tokens, TokenKind::Object, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::Object, diag));
+
+    let id_span = current_span(tokens);
+    let id = consume_identifier(tokens, diag);
 
-    let id = consume_identifier(tokens, diag, start);
+    syntax.push(identifier_syntax(&id, id_span));
 
     let (object_desc, inner_syntax) = parse_object_compound_desc(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    tokens.set_poisoned(false);
+    syntax.push(consume_terminator(tokens, diag));
 
-    syntax.push(consume_keyword(tokens, TokenKind::SemiColon, diag, start));
+    let span = node_span(tokens, start);
 
-    let end = tokens.get_position();
-    let span = Span::new(start, end);
-
-    let object_decl = ObjectDecl::boxed(id, object_desc, span, syntax);
+    let object_decl = ObjectDecl::boxed(tokens.next_node_id(), id, object_desc, span, syntax);
 
     object_decl
 }
@@ -789,56 +1135,245 @@ pub fn parse_object_decl<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulato
 pub fn parse_compound_stmt<'a>(
     tokens: &mut Lexer,
     diag: &mut DiagnosticAccumulator,
-) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>) {
-    let mut syntax: Vec<ErminiaType> = vec![];
+) -> (Vec<BoxAST<'a>>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
-    let start = tokens.get_previous_position();
-
-    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftBrace, diag));
 
     let stmts = parse_stmt_list(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::RightBrace, diag));
 
     (stmts, syntax)
 }
 
-// <problem_declaration> ::= "def" <id> "(" <int_const> ")" <compound_stmt>
+// <fpar_def> ::= <data_type> <id>
+pub fn parse_func_param(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> FuncParam {
+    let data_type = consume_data_type(tokens, diag);
+
+    let id = consume_identifier(tokens, diag);
+
+    FuncParam { data_type, id }
+}
+
+// <fpar_list> ::= <fpar_def> ("," <fpar_def>)*
+pub fn parse_func_params(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+) -> (Vec<FuncParam>, Vec<SyntaxToken>) {
+    tokens.push_sync(LIST_SYNC);
+
+    let mut syntax: Vec<SyntaxToken> = vec![];
+    let mut params: Vec<FuncParam> = vec![];
+
+    params.push(parse_func_param(tokens, diag));
+
+    while match_next(tokens, TokenKind::Comma) {
+        syntax.push(consume_keyword(tokens, TokenKind::Comma, diag));
+        params.push(parse_func_param(tokens, diag));
+    }
+
+    tokens.pop_sync();
+
+    (params, syntax)
+}
+
+// <func_decl> ::= "func" <id> "(" [<fpar_list>] ")" ["->" <data_type>] <compound_stmt>
+pub fn parse_func_decl<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+
+    let start = tokens.get_previous_position();
+
+    syntax.push(consume_keyword(tokens, TokenKind::FuncKwd, diag));
+
+    let id = consume_identifier(tokens, diag);
+
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
+
+    let mut params: Vec<FuncParam> = vec![];
+
+    if !match_next(tokens, TokenKind::RightPar) {
+        let (inner_params, inner_syntax) = parse_func_params(tokens, diag);
+        params = inner_params;
+        syntax.extend(inner_syntax);
+    }
+
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
+
+    let mut return_type = ErminiaType::Void;
+
+    if match_next(tokens, TokenKind::RightArrow) {
+        syntax.push(consume_keyword(tokens, TokenKind::RightArrow, diag));
+        return_type = consume_data_type(tokens, diag);
+    }
+
+    let (stmts, inner_syntax) = parse_compound_stmt(tokens, diag);
+
+    syntax.extend(inner_syntax);
+
+    let span = node_span(tokens, start);
+
+    FuncDecl::boxed(
+        tokens.next_node_id(),
+        id,
+        params,
+        return_type,
+        stmts,
+        span,
+        syntax,
+    )
+}
+
+// <problem_declaration> ::= "def" <id> "(" [<int_const>] ")" <compound_stmt>
 pub fn parse_problem_decl<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
-    let mut syntax: Vec<ErminiaType> = vec![];
+    let mut syntax: Vec<SyntaxToken> = vec![];
 
     let start = tokens.get_previous_position();
 
-    syntax.push(consume_keyword(tokens, TokenKind::ProblemDef, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::ProblemDef, diag));
 
-    let id = consume_identifier(tokens, diag, start);
+    let id = consume_identifier(tokens, diag);
 
-    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag, start));
+    syntax.push(consume_keyword(tokens, TokenKind::LeftPar, diag));
 
-    let int_const = consume_int_const(tokens, diag, start);
+    let mut int_const = ErminiaType::Void;
 
-    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag, start));
+    if !match_next(tokens, TokenKind::RightPar) {
+        int_const = consume_int_const(tokens, diag);
+    }
+
+    syntax.push(consume_keyword(tokens, TokenKind::RightPar, diag));
 
     let (stmts, inner_syntax) = parse_compound_stmt(tokens, diag);
 
     syntax.extend(inner_syntax);
 
-    let end = tokens.get_position();
+    let span = node_span(tokens, start);
 
-    let span = Span::new(start, end);
+    ProblemDecl::boxed(tokens.next_node_id(), id, int_const, stmts, span, syntax)
+}
 
-    let program = Program::boxed(id, int_const, stmts, span, syntax);
+// <module_path> ::= <id> ("::" <id>)*
+pub fn parse_module_path(
+    tokens: &mut Lexer,
+    diag: &mut DiagnosticAccumulator,
+) -> (Vec<ErminiaType>, Vec<SyntaxToken>) {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+    let mut path: Vec<ErminiaType> = vec![];
 
-    program.check_poisoning(tokens, diag);
+    path.push(consume_identifier(tokens, diag));
 
-    program
+    while match_next(tokens, TokenKind::PathSep) {
+        syntax.push(consume_keyword(tokens, TokenKind::PathSep, diag));
+        path.push(consume_identifier(tokens, diag));
+    }
+
+    (path, syntax)
+}
+
+// <include_decl> ::= ("include" | "use") <module_path> ";"
+pub fn parse_include<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+
+    let start = tokens.get_previous_position();
+
+    if match_next(tokens, TokenKind::UseKwd) {
+        syntax.push(consume_keyword(tokens, TokenKind::UseKwd, diag));
+    } else {
+        syntax.push(consume_keyword(tokens, TokenKind::IncludeKwd, diag));
+    }
+
+    let (path, inner_syntax) = parse_module_path(tokens, diag);
+
+    syntax.extend(inner_syntax);
+
+    syntax.push(consume_terminator(tokens, diag));
+
+    let span = node_span(tokens, start);
+
+    Include::boxed(tokens.next_node_id(), path, span, syntax)
 }
 
-// <program> ::= <problem_declaration>
+// <program> ::= (<include_decl> | <problem_declaration> | <func_decl> | <object_decl>)* EOF
 pub fn parse_program<'a>(tokens: &mut Lexer, diag: &mut DiagnosticAccumulator) -> BoxAST<'a> {
+    let mut syntax: Vec<SyntaxToken> = vec![];
+    let mut items: Vec<BoxAST> = vec![];
+
     tokens.advance();
 
-    let program = parse_problem_decl(tokens, diag);
+    let start = tokens.get_previous_position();
+
+    tokens.push_sync(TOP_LEVEL_SYNC);
+
+    loop {
+        tokens.expect(TOP_LEVEL_START);
+
+        if match_next(tokens, TokenKind::EOF) {
+            break;
+        }
+
+        // Every declaration starts with a clean slate, so that an error in one of them
+        // does not silence the ones that follow.
+        tokens.set_poisoned(false);
+
+        let before = tokens.get_previous_position().get_cursor();
+
+        let kind = tokens.peek().get_kind();
+
+        match kind {
+            TokenKind::IncludeKwd
+            | TokenKind::UseKwd
+            | TokenKind::ProblemDef
+            | TokenKind::FuncKwd
+            | TokenKind::Object => {
+                let item = with_sync(tokens, STMT_SYNC, |tokens| match kind {
+                    TokenKind::ProblemDef => parse_problem_decl(tokens, diag),
+                    TokenKind::FuncKwd => parse_func_decl(tokens, diag),
+                    TokenKind::Object => parse_object_decl(tokens, diag),
+                    _ => parse_include(tokens, diag),
+                });
+
+                items.push(item);
+            }
+            _ => {
+                let span = current_span(tokens);
+
+                diag!(
+                    Parser,
+                    E0009,
+                    ExpectedTopLevelItem(tokens.peek().text.to_string()),
+                    DidYouMeanTopLevelKeyword,
+                    tokens,
+                    diag,
+                    span;
+                    with_did_you_mean(
+                        tokens.token.text,
+                        span,
+                        &keyword_spellings(TOP_LEVEL_START)
+                    )
+                );
+
+                syntax.push(SyntaxToken::poisoned(kind, span));
+
+                // Skip the garbage up to the next declaration
+                tokens.advance();
+
+                while !next_is_top_level_item(tokens) && !match_next(tokens, TokenKind::EOF) {
+                    tokens.advance();
+                }
+            }
+        }
+
+        ensure_progress(tokens, before);
+    }
+
+    tokens.pop_sync();
+
+    let span = node_span(tokens, start);
+
+    let program = Program::boxed(tokens.next_node_id(), items, span, syntax);
+
+    check_poisoning(&program, tokens, diag);
 
     program
 }
@@ -0,0 +1,147 @@
+use crate::lexer::lex::Lexer;
+use crate::lexer::token::TokenKind;
+use crate::syntax::consumers::{STMT_START, TOP_LEVEL_START};
+
+// ==================================================================================== //
+//  Synchronization Sets                                                                //
+// ==================================================================================== //
+
+/// Where a broken statement can be left behind: its `;`, the `}` of the enclosing block or
+/// the keyword of the next statement.
+pub const STMT_SYNC: &[TokenKind] = &[
+    TokenKind::SemiColon,
+    TokenKind::RightBrace,
+    TokenKind::LetKwd,
+    TokenKind::Object,
+    TokenKind::FuncKwd,
+    TokenKind::ProblemExample,
+    TokenKind::ProblemSolution,
+    TokenKind::ProblemInput,
+    TokenKind::ProblemOutput,
+];
+
+/// Comma separated lists resume at the next element.
+pub const LIST_SYNC: &[TokenKind] = &[TokenKind::Comma];
+
+/// An object description goes on at its next field, even when the `,` before it is missing.
+pub const OBJECT_DESC_SYNC: &[TokenKind] = &[
+    TokenKind::Comma,
+    TokenKind::ObjectShape,
+    TokenKind::ObjectColor,
+];
+
+/// A variable definition goes on at its `=`, so that a broken name or type does not keep
+/// errors in the value from being reported.
+pub const VAR_DEF_SYNC: &[TokenKind] = &[TokenKind::Equals];
+
+/// Top-level declarations.
+pub const TOP_LEVEL_SYNC: &[TokenKind] = &[
+    TokenKind::ProblemDef,
+    TokenKind::FuncKwd,
+    TokenKind::Object,
+    TokenKind::IncludeKwd,
+    TokenKind::UseKwd,
+];
+
+// ==================================================================================== //
+//  Recovery                                                                            //
+// ==================================================================================== //
+
+fn is_opening(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::LeftPar | TokenKind::LeftBracket | TokenKind::LeftBrace
+    )
+}
+
+fn is_closing(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::RightPar | TokenKind::RightBracket | TokenKind::RightBrace
+    )
+}
+
+/// A declaration keyword only starts a new declaration when the name follows it, a bare
+/// `object` is just as likely a data type. Likewise a `;` only ends a statement when what
+/// follows it could come after one, a stray one as in `(;, 0)` is skipped.
+fn is_sync_point(tokens: &Lexer, kind: TokenKind) -> bool {
+    if !tokens.is_sync_kind(kind) {
+        return false;
+    }
+
+    match kind {
+        TokenKind::ProblemDef | TokenKind::FuncKwd | TokenKind::Object => {
+            tokens.lookahead().0 == TokenKind::Ident
+        }
+        TokenKind::SemiColon => {
+            let next = tokens.lookahead().0;
+
+            matches!(
+                next,
+                TokenKind::SemiColon | TokenKind::RightBrace | TokenKind::EOF
+            ) || STMT_START.contains(&next)
+                || TOP_LEVEL_START.contains(&next)
+        }
+        _ => true,
+    }
+}
+
+/// Skips tokens until the parser reaches a point where one of the productions being parsed
+/// can continue. Groups opened while skipping are skipped as a whole, and a closing
+/// delimiter that was opened before the error always stops the skip, so an error never
+/// swallows the rest of its enclosing block.
+pub fn synchronize(tokens: &mut Lexer) {
+    let mut depth = 0;
+
+    loop {
+        let kind = tokens.peek().get_kind();
+
+        if kind == TokenKind::EOF {
+            return;
+        }
+
+        if depth == 0 && (is_closing(kind) || is_sync_point(tokens, kind)) {
+            return;
+        }
+
+        if is_opening(kind) {
+            depth += 1;
+        } else if is_closing(kind) {
+            depth -= 1;
+        }
+
+        tokens.advance();
+    }
+}
+
+/// Called right after an error has been reported. Later errors stay silent until the
+/// parser has resynchronized.
+pub fn recover(tokens: &mut Lexer) {
+    tokens.set_poisoned(true);
+
+    synchronize(tokens);
+}
+
+/// Parses with `set` added to the synchronization points.
+pub fn with_sync<T, F>(tokens: &mut Lexer, set: &'static [TokenKind], parse: F) -> T
+where
+    F: FnOnce(&mut Lexer) -> T,
+{
+    tokens.push_sync(set);
+
+    let res = parse(tokens);
+
+    tokens.pop_sync();
+
+    res
+}
+
+/// Loops over repeated productions must make progress, otherwise a token that none of
+/// them accepts would be looked at forever. Skips that token if nothing was consumed.
+pub fn ensure_progress(tokens: &mut Lexer, before: usize) {
+    if tokens.get_previous_position().get_cursor() == before
+        && tokens.peek().get_kind() != TokenKind::EOF
+    {
+        tokens.advance();
+    }
+}
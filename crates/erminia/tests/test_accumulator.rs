@@ -0,0 +1,127 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::code::DiagnosticLevel;
+use erminia::diagnostics::{Code, DiagnosticAccumulator};
+use erminia::lexer::lex::Lexer;
+use erminia::loader::Loader;
+use erminia::syntax::parse::parse_program;
+
+#[cfg(test)]
+mod test_accumulator {
+    use super::*;
+
+    // Three statements missing their `;`
+    const MISSING_TERMINATORS: &str =
+        "def p () {\n    let a: int = 1\n    let b: int = 2\n    let c: int = 3\n}";
+
+    fn accumulate(text: &str, diag: &mut DiagnosticAccumulator) {
+        let mut tokens = Lexer::new(text);
+
+        parse_program(&mut tokens, diag);
+    }
+
+    fn lint(text: &str) -> DiagnosticAccumulator {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", text);
+        loader.load("main.erm").unwrap();
+
+        loader.get_diagnostics().clone()
+    }
+
+    #[test]
+    fn test_duplicates_are_dropped() {
+        let mut diag = DiagnosticAccumulator::new();
+        accumulate(MISSING_TERMINATORS, &mut diag);
+
+        let count = diag.diagnostics.len();
+
+        for diagnostic in diag.diagnostics.clone() {
+            diag.add_diag(diagnostic);
+        }
+
+        assert_eq!(count, diag.diagnostics.len());
+    }
+
+    #[test]
+    fn test_sorted_by_level_then_position() {
+        let mut diag = lint(
+            "def p () {
+                object B { shape: [(x, 0) | x <- [0..1], x > 1], color: 1 };
+                object A { shape: [(0, 0)], color: 1 };
+            }",
+        );
+
+        accumulate("objet", &mut diag);
+
+        let sorted = diag.sorted();
+        let codes: Vec<Code> = sorted.iter().map(|d| d.code).collect();
+
+        assert_eq!(DiagnosticLevel::Error, sorted[0].level);
+        assert_eq!(
+            vec![Code::W0001, Code::W0002, Code::W0001],
+            codes[codes.len() - 3..]
+        );
+
+        let starts: Vec<usize> = sorted[sorted.len() - 3..]
+            .iter()
+            .map(|d| d.window.span.start.get_cursor())
+            .collect();
+        assert!(starts.windows(2).all(|w| w[0] <= w[1]));
+
+        // Sorting a copy leaves the order things were found in alone
+        assert_ne!(sorted, diag.diagnostics);
+
+        diag.sort();
+        assert_eq!(sorted, diag.diagnostics);
+    }
+
+    #[test]
+    fn test_is_blocking() {
+        let mut diag = DiagnosticAccumulator::new();
+        accumulate(MISSING_TERMINATORS, &mut diag);
+
+        let before = diag.diagnostics.clone();
+
+        assert!(diag.is_blocking(CompilerPass::AST));
+        assert!(!diag.is_blocking(CompilerPass::Parser));
+        assert_eq!(before, diag.diagnostics);
+    }
+
+    #[test]
+    fn test_error_limit() {
+        let mut unlimited = DiagnosticAccumulator::new();
+        accumulate(MISSING_TERMINATORS, &mut unlimited);
+
+        let mut diag = DiagnosticAccumulator::new();
+        diag.set_error_limit(Some(2));
+        accumulate(MISSING_TERMINATORS, &mut diag);
+
+        assert_eq!(2, diag.error_count());
+        assert_eq!(unlimited.error_count() - 2, diag.suppressed());
+        assert_eq!(unlimited.diagnostics[..2], diag.diagnostics[..]);
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(None, DiagnosticAccumulator::new().summary());
+
+        let warnings = lint(
+            "def p () {
+                object A { shape: [(0, 0)], color: 1 };
+                object B { shape: [(0, 0)], color: 1 };
+            }",
+        );
+        assert_eq!(Some("2 warnings emitted"), warnings.summary().as_deref());
+
+        let mut diag = DiagnosticAccumulator::new();
+        diag.set_error_limit(Some(1));
+        accumulate(MISSING_TERMINATORS, &mut diag);
+
+        let summary = diag.summary().unwrap();
+        assert!(
+            summary.starts_with("1 error emitted, ")
+                && summary.ends_with("past the error limit not shown"),
+            "{}",
+            summary
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::catalog::{self, placeholders};
+use erminia::diagnostics::{Catalog, Code, Help, Note};
+use erminia::syntax::Parser;
+
+use std::collections::BTreeSet;
+
+#[cfg(test)]
+mod test_catalog {
+    use super::*;
+
+    fn s() -> String {
+        "x".to_string()
+    }
+
+    // One of every note, to be kept in step with the enum
+    fn notes() -> Vec<Note> {
+        vec![
+            Note::ExpectedLeftInclusive(s()),
+            Note::ExpectedRightInclusive(s()),
+            Note::ExpectedDataType(s()),
+            Note::ExpectedInteger(s()),
+            Note::ExpectedIdentifier(s()),
+            Note::ExpectedSomethingElse(s(), s()),
+            Note::ExpectedASTNode(s(), s()),
+            Note::ExpectedStatement(s()),
+            Note::ExpectedIDorInteger(s()),
+            Note::ExpectedShapeOrColor(s()),
+            Note::ExpectedTypeofTuple(s()),
+            Note::UnknownObjectArgument(s(), s()),
+            Note::DuplicateObjectArgument(s(), s()),
+            Note::MixedObjectArguments(s()),
+            Note::TooManyObjectArguments(s(), s()),
+            Note::ExpectedTopLevelItem(s()),
+            Note::ModuleNotFound(s()),
+            Note::IncludeCycle(s()),
+            Note::ConflictingInclude(s(), s()),
+            Note::ExpectedEndOfInput(s(), s()),
+            Note::UnknownObject(s()),
+            Note::UnusedObject(s()),
+            Note::EmptyShape(s()),
+            Note::UnplacedObject(s()),
+            Note::OverlappingObjects(s(), s()),
+            Note::UnknownLint(s()),
+            Note::MalformedLintAttribute(s()),
+            Note::CellsOutsideGrid(s(), s(), s(), s()),
+            Note::GridSizeOutOfLimits(s(), s()),
+            Note::EmptyRange(s()),
+            Note::NestedTooDeeply(s()),
+            Note::NotAnObject(s(), s()),
+            Note::NonPositiveStep(s()),
+        ]
+    }
+
+    fn helps() -> Vec<Help> {
+        vec![
+            Help::ConsiderChangingToInclusive,
+            Help::DidYouMeanDataType,
+            Help::DidYouMeanStmtKeyword,
+            Help::DidYouMeanShapeOrColor,
+            Help::DidYouMeanTupleorObject,
+            Help::DidYouMeanOffsetArgument,
+            Help::RemoveDuplicateArgument,
+            Help::UseEitherPositionalOrNamed,
+            Help::DidYouMeanTopLevelKeyword,
+            Help::CheckModuleSearchPath,
+            Help::BreakIncludeCycle,
+            Help::RenameConflictingDeclaration,
+            Help::RemoveTrailingInput,
+            Help::DidYouMean(s()),
+            Help::DeclareOrIncludeObject,
+            Help::RemoveOrPlaceObject,
+            Help::CheckShapeRanges,
+            Help::PlaceObjectInGrid,
+            Help::MoveOverlappingObject,
+            Help::KnownLints(s()),
+            Help::UseLintLevel,
+            Help::MoveObjectIntoGrid,
+            Help::KeepGridSizeInLimits,
+            Help::SplitNestedExpression,
+            Help::CallDeclaredObject,
+            Help::UsePositiveStep,
+        ]
+    }
+
+    #[test]
+    fn test_english_has_every_message() {
+        let english = Catalog::english();
+
+        let mut parts: Vec<(&str, Vec<&str>)> = vec![];
+        let notes = notes();
+        let helps = helps();
+
+        for note in &notes {
+            let (key, args) = note.parts();
+            parts.push((key, args.iter().map(|(name, _)| *name).collect()));
+        }
+        for help in &helps {
+            let (key, args) = help.parts();
+            parts.push((key, args.iter().map(|(name, _)| *name).collect()));
+        }
+
+        for (key, args) in &parts {
+            let template = english
+                .get(key)
+                .unwrap_or_else(|| panic!("no message {}", key));
+            let expected: BTreeSet<&str> = args.iter().copied().collect();
+
+            assert_eq!(expected, placeholders(template).unwrap(), "{}", key);
+        }
+
+        // Nothing in the catalog that no note or help uses
+        let used: BTreeSet<&str> = parts.iter().map(|(key, _)| *key).collect();
+        for key in english.keys().filter(|k| !k.starts_with("code-")) {
+            assert!(used.contains(key), "unused message {}", key);
+        }
+
+        for code in Code::ALL {
+            assert!(!code.summary().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_arguments_are_filled_in() {
+        let note = Note::ConflictingInclude("Dot".to_string(), "lib".to_string());
+
+        assert_eq!(
+            "'Dot' from module 'lib' is already declared in this scope.",
+            note.stringify()
+        );
+        assert_eq!(2, note.args_count());
+        assert!(Help::UseLintLevel.parts().1.is_empty());
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let names: Vec<&str> = placeholders("{b} and {a}, {b} again, {{literal}}")
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        assert_eq!(vec!["a", "b"], names);
+
+        assert!(placeholders("{open").is_err());
+        assert!(placeholders("close}").is_err());
+        assert!(placeholders("{two words}").is_err());
+    }
+
+    #[test]
+    fn test_alternate_catalog() {
+        let catalog = Catalog::parse(
+            "# Ελληνικά\n\
+             code-E0023 = Αναμενόταν αναγνωριστικό ή ακέραιος\n\
+             note-expected-id-or-integer = Αναμενόταν αναγνωριστικό ή ακέραιος, βρέθηκε '{found}'.\n",
+        )
+        .unwrap();
+
+        catalog::install(catalog);
+
+        let mut parser = Parser::new("def p () {\n  let a: int = 1 +;\n}");
+        parser.parse();
+
+        catalog::uninstall();
+
+        let diagnostic = &parser.get_diagnostics().get(CompilerPass::Parser)[0];
+
+        assert_eq!("Αναμενόταν αναγνωριστικό ή ακέραιος", diagnostic.message);
+        assert_eq!(
+            "Αναμενόταν αναγνωριστικό ή ακέραιος, βρέθηκε ';'.",
+            diagnostic.note
+        );
+
+        // Back to English
+        assert_eq!(
+            "Expected identifier or integer constant but something else was found",
+            catalog::message("code-E0023", &[])
+        );
+    }
+
+    #[test]
+    fn test_catalog_errors() {
+        assert!(Catalog::parse("note-unknown = {object}").is_err());
+        assert!(Catalog::parse("note-unused-object = '{name}' is unused").is_err());
+        assert!(Catalog::parse("note-unused-object = '{object}' is unused").is_ok());
+        assert!(Catalog::parse("no template here").is_err());
+        assert!(Catalog::parse("code-E0001 = a\ncode-E0001 = b").is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use erminia::config::CompilerPass;
 use erminia::diagnostics::code::*;
+use erminia::loader::Loader;
 
 mod test_code {
 
@@ -6,22 +8,16 @@ mod test_code {
     fn test_diagnostic_level_hierarchy() {
         use super::{Code, DiagnosticLevel};
 
-        let internal_code = Code::I0001;
-        let error_code = Code::E0001;
-        let warning_code = Code::W000X;
-        let note_code = Code::N000X;
-        let help_code = Code::H000X;
+        let internal_level = DiagnosticLevel::from_code(&Code::I0001);
+        let error_level = DiagnosticLevel::from_code(&Code::E0001);
 
-        let internal_level = DiagnosticLevel::from_code(&internal_code);
-        let error_level = DiagnosticLevel::from_code(&error_code);
-        let warning_level = DiagnosticLevel::from_code(&warning_code);
-        let note_level = DiagnosticLevel::from_code(&note_code);
-        let help_level = DiagnosticLevel::from_code(&help_code);
+        assert_eq!(DiagnosticLevel::Internal, internal_level);
+        assert_eq!(DiagnosticLevel::Error, error_level);
 
         assert!(internal_level < error_level);
-        assert!(error_level < warning_level);
-        assert!(warning_level < note_level);
-        assert!(note_level < help_level);
+        assert!(error_level < DiagnosticLevel::Warning);
+        assert!(DiagnosticLevel::Warning < DiagnosticLevel::Note);
+        assert!(DiagnosticLevel::Note < DiagnosticLevel::Help);
     }
 
     #[test]
@@ -31,12 +27,117 @@ mod test_code {
         let code0 = Code::I0001;
         let code1 = Code::E0001;
         let code2 = Code::E0002;
-        let code3 = Code::W000X;
-        let code4 = Code::N000X;
+        let code3 = Code::E0014;
 
         assert!(code0 < code1);
         assert!(code1 < code2);
         assert!(code2 < code3);
-        assert!(code3 < code4);
+    }
+
+    #[test]
+    fn test_registry_lists_every_code_once() {
+        use super::Code;
+
+        let mut sorted = Code::ALL.to_vec();
+        sorted.sort();
+        sorted.dedup();
+
+        assert_eq!(Code::ALL.to_vec(), sorted);
+
+        for code in Code::ALL {
+            assert_eq!(Ok(code), code.to_string().parse::<Code>());
+            assert!(!code.summary().is_empty());
+        }
+
+        assert!("E9999".parse::<Code>().is_err());
+    }
+
+    // Code blocks of an explanation, with the attributes after "```erminia"
+    fn examples(explanation: &str) -> Vec<(String, String)> {
+        let mut examples = vec![];
+        let mut current: Option<(String, String)> = None;
+
+        for line in explanation.lines() {
+            match current.take() {
+                Some(example) if line == "```" => examples.push(example),
+                Some((attrs, mut text)) => {
+                    text.push_str(line);
+                    text.push('\n');
+                    current = Some((attrs, text));
+                }
+                None => {
+                    if let Some(attrs) = line.strip_prefix("```erminia") {
+                        current = Some((attrs.to_string(), String::new()));
+                    }
+                }
+            }
+        }
+
+        examples
+    }
+
+    fn check(text: &str) -> Vec<super::Code> {
+        let mut loader = super::Loader::new();
+        loader.add_virtual_file("main.erm", text);
+        let _ = loader.load("main.erm");
+
+        loader
+            .get_diagnostics()
+            .get(super::CompilerPass::ALL)
+            .iter()
+            .map(|d| d.code)
+            .collect()
+    }
+
+    #[test]
+    fn test_explanation_examples() {
+        use super::Code;
+
+        for code in Code::ALL {
+            for (attrs, text) in examples(code.explanation()) {
+                match attrs.as_str() {
+                    ",ignore" => {}
+                    ",compile_fail" | ",warn" => {
+                        let codes = check(&text);
+                        assert!(
+                            codes.contains(&code),
+                            "{}: got {:?} for\n{}",
+                            code,
+                            codes,
+                            text
+                        );
+                    }
+                    "" => {
+                        let codes = check(&text);
+                        assert!(codes.is_empty(), "{}: got {:?} for\n{}", code, codes, text);
+                    }
+                    _ => panic!("{}: unknown example attributes '{}'", code, attrs),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_explanations_have_examples() {
+        use super::{Code, DiagnosticLevel};
+
+        for code in Code::ALL {
+            if DiagnosticLevel::from_code(&code) != DiagnosticLevel::Error {
+                continue;
+            }
+
+            let examples = examples(code.explanation());
+
+            assert!(
+                examples.iter().any(|(attrs, _)| !attrs.is_empty()),
+                "{} has no erroneous example",
+                code
+            );
+            assert!(
+                examples.iter().any(|(attrs, _)| attrs != ",compile_fail"),
+                "{} has no corrected example",
+                code
+            );
+        }
     }
 }
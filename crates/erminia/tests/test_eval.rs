@@ -0,0 +1,196 @@
+use erminia::ast::eval::*;
+use erminia::diagnostics::DiagnosticAccumulator;
+use erminia::lexer::lex::Lexer;
+use erminia::syntax::parse::*;
+
+#[cfg(test)]
+mod test_eval {
+    use super::*;
+
+    fn eval_range(text: &str, env: &Env) -> Option<RangeSet> {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        tokens.advance();
+
+        let range = parse_range_expr(&mut tokens, &mut diag);
+
+        assert!(range.is_ok());
+
+        range.eval_range(env)
+    }
+
+    fn check_range(text: &str, expected: Vec<i32>) {
+        let actual = eval_range(text, &Env::new()).expect("range should evaluate");
+
+        assert_eq!(expected, actual.values());
+    }
+
+    fn check_expr(text: &str, env: &Env, expected: Option<i32>) {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        tokens.advance();
+
+        let expr = parse_expr(&mut tokens, &mut diag);
+
+        assert!(expr.is_ok());
+        assert_eq!(expected, expr.eval(env));
+    }
+
+    #[test]
+    fn test_inclusive_range() {
+        check_range("[0..3]", vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_exclusive_range() {
+        check_range("(0..3)", vec![1, 2]);
+    }
+
+    #[test]
+    fn test_empty_exclusive_range() {
+        let set = eval_range("(0..0)", &Env::new()).unwrap();
+
+        assert!(set.is_empty());
+        assert_eq!(0, set.len());
+    }
+
+    #[test]
+    fn test_single_point_range() {
+        check_range("[0..0]", vec![0]);
+    }
+
+    #[test]
+    fn test_descending_range() {
+        check_range("[3..0)", vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_stepped_range() {
+        check_range("[0..10 by 3]", vec![0, 3, 6, 9]);
+    }
+
+    #[test]
+    fn test_stepped_descending_range() {
+        check_range("[10..0 by 5]", vec![10, 5, 0]);
+    }
+
+    #[test]
+    fn test_stepped_range_leaving_out_left_end() {
+        check_range("(0..10 by 2]", vec![2, 4, 6, 8, 10]);
+        check_range("(10..0 by 5]", vec![5, 0]);
+        check_range("(0..10 by 3)", vec![3, 6, 9]);
+        check_range("(2147483600..2147483647 by 100]", vec![]);
+    }
+
+    #[test]
+    fn test_non_positive_step() {
+        assert_eq!(None, eval_range("[0..10 by 0]", &Env::new()));
+        assert_eq!(None, eval_range("[0..10 by -1]", &Env::new()));
+    }
+
+    #[test]
+    fn test_range_union() {
+        check_range("[1..3] U [14..12] U [2..4]", vec![1, 2, 3, 14, 13, 12, 4]);
+    }
+
+    #[test]
+    fn test_range_with_expression_bounds() {
+        let mut env = Env::new();
+        env.insert("n".to_string(), 4);
+
+        let set = eval_range("[n - 2..n * 2)", &env).unwrap();
+
+        assert_eq!(vec![2, 3, 4, 5, 6, 7], set.values());
+    }
+
+    #[test]
+    fn test_range_with_unbound_variable() {
+        assert_eq!(None, eval_range("[0..n]", &Env::new()));
+    }
+
+    #[test]
+    fn test_int_range_contains() {
+        let range = IntRange::new(10, 0, true, true, 3).unwrap();
+
+        assert!(range.contains(10));
+        assert!(range.contains(1));
+        assert!(!range.contains(0));
+        assert!(!range.contains(13));
+    }
+
+    #[test]
+    fn test_expr_precedence() {
+        check_expr("1 + 2 * 3 - 4", &Env::new(), Some(3));
+    }
+
+    #[test]
+    fn test_expr_guard() {
+        let mut env = Env::new();
+        env.insert("x".to_string(), 3);
+        env.insert("y".to_string(), 5);
+
+        check_expr("(x + y) % 2 == 0 && !(x > y)", &env, Some(1));
+    }
+
+    #[test]
+    fn test_expr_division_by_zero() {
+        check_expr("1 // 0", &Env::new(), None);
+    }
+
+    fn eval_cells(text: &str) -> Option<Cells> {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        tokens.advance();
+
+        let shape = parse_shape(&mut tokens, &mut diag);
+
+        assert!(shape.is_ok());
+
+        shape.eval_cells(&Env::new())
+    }
+
+    #[test]
+    fn test_tuple_cells() {
+        assert_eq!(Some(Cells::from([(2, 3)])), eval_cells("(2, 3)"));
+    }
+
+    #[test]
+    fn test_comprehension_cells() {
+        let cells = eval_cells("(x, y) | x <- [0..2], y <- [0..1], x != y").unwrap();
+
+        assert_eq!(Cells::from([(0, 1), (1, 0), (2, 0), (2, 1)]), cells);
+    }
+
+    #[test]
+    fn test_huge_comprehension_is_given_up_on() {
+        let start = std::time::Instant::now();
+
+        assert_eq!(None, eval_cells("(x, 0) | x <- [0..2000000000]"));
+        assert_eq!(None, eval_cells("(x, y) | x <- [0..400], y <- [0..400]"));
+        assert!(start.elapsed().as_secs() < 5);
+
+        let range = eval_range("[0..2000000000] U [0..10]", &Env::new()).unwrap();
+        assert_eq!(vec![0, 1, 2], range.iter().take(3).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_range_ends_at_integer_limits() {
+        check_range("(2147483647..2147483647]", vec![]);
+        check_range("[2147483647..2147483647]", vec![2147483647]);
+        check_range("[2147483646..2147483647]", vec![2147483646, 2147483647]);
+        check_range("[-2147483647 - 1..-2147483647 - 1)", vec![]);
+        check_range(
+            "[-2147483647 - 1..2147483647 by 2147483647]",
+            vec![-2147483648, -1, 2147483646],
+        );
+    }
+
+    #[test]
+    fn test_empty_comprehension_cells() {
+        assert_eq!(Some(Cells::new()), eval_cells("(x, 0) | x <- (0..1)"));
+        assert_eq!(None, eval_cells("(x, 0) | x <- [0..n]"));
+    }
+}
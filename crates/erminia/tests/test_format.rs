@@ -0,0 +1,107 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::format::*;
+use erminia::diagnostics::Diagnostic;
+use erminia::syntax::parse;
+use serde_json::json;
+
+#[cfg(test)]
+mod test_format {
+    use super::*;
+
+    fn diagnostics(text: &str) -> (Vec<Diagnostic>, erminia::diagnostics::SourceMap) {
+        let output = parse(text).unwrap();
+
+        (output.diagnostics.get(CompilerPass::ALL), output.source_map)
+    }
+
+    #[test]
+    fn test_message_format_from_str() {
+        assert_eq!(Ok(MessageFormat::Human), "human".parse());
+        assert_eq!(Ok(MessageFormat::Json), "json".parse());
+        assert_eq!(Ok(MessageFormat::Sarif), "sarif".parse());
+        assert!("xml".parse::<MessageFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_output() {
+        let (diagnostics, source_map) = diagnostics("def p () {\n  let a: int = 1 +;\n}");
+
+        let value = to_json(&diagnostics[0], &source_map);
+
+        assert_eq!(json!("E0023"), value["code"]);
+        assert_eq!(json!("error"), value["level"]);
+        assert_eq!(json!("Parser"), value["pass"]);
+        assert_eq!(
+            json!("Expected an identifier or integer constant, but found ';'."),
+            value["note"]
+        );
+        assert_eq!(json!(null), value["help"]);
+        assert_eq!(
+            json!([{
+                "file": "<input>",
+                "byte_start": 29,
+                "byte_end": 30,
+                "line_start": 2,
+                "column_start": 19,
+                "line_end": 2,
+                "column_end": 20,
+                "is_primary": true,
+                "label": null,
+                "text": ";",
+            }]),
+            value["spans"]
+        );
+        assert!(value["rendered"]
+            .as_str()
+            .unwrap()
+            .contains("2 |   let a: int = 1 +;"));
+    }
+
+    #[test]
+    fn test_json_labels() {
+        let (diagnostics, source_map) =
+            diagnostics("object A { shape: [Dot(x: 1, x: 3)], color: 1 };");
+
+        let spans = &to_json(&diagnostics[0], &source_map)["spans"];
+
+        assert_eq!(json!(false), spans[1]["is_primary"]);
+        assert_eq!(json!("first given here"), spans[1]["label"]);
+        assert_eq!(json!(24), spans[1]["column_start"]);
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let (diagnostics, source_map) = diagnostics(
+            "object A { shape: [Dot(x: 1, x: 3)], color: 1 };\ndef p () { let a: int = ; }",
+        );
+
+        let log = to_sarif(&diagnostics, &source_map);
+
+        assert_eq!(json!("2.1.0"), log["version"]);
+
+        let run = &log["runs"][0];
+        let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(vec!["E0006", "E0023"], rules);
+
+        let result = &run["results"][0];
+        assert_eq!(json!("E0006"), result["ruleId"]);
+        assert_eq!(json!(0), result["ruleIndex"]);
+        assert_eq!(json!("error"), result["level"]);
+
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(json!("<input>"), location["artifactLocation"]["uri"]);
+        assert_eq!(json!(1), location["region"]["startLine"]);
+        assert_eq!(json!(30), location["region"]["startColumn"]);
+        assert_eq!(json!(34), location["region"]["endColumn"]);
+
+        assert_eq!(
+            json!("first given here"),
+            result["relatedLocations"][0]["message"]["text"]
+        );
+    }
+}
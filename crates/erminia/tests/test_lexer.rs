@@ -119,6 +119,43 @@ mod test_lexer {
         check_lex(text, expected);
     }
 
+    #[test]
+    fn test_lex_func_signature() {
+        let text = "func f(int n) -> void";
+
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::FuncKwd, "func", 1, 0),
+            Token::new(TokenKind::Ident, "f", 1, 5),
+            Token::new(TokenKind::LeftPar, "(", 1, 6),
+            Token::new(TokenKind::IntType, "int", 1, 7),
+            Token::new(TokenKind::Ident, "n", 1, 11),
+            Token::new(TokenKind::RightPar, ")", 1, 12),
+            Token::new(TokenKind::RightArrow, "->", 1, 14),
+            Token::new(TokenKind::VoidType, "void", 1, 17),
+            Token::new(TokenKind::EOF, "", 1, 21),
+        ];
+
+        check_lex(text, expected);
+    }
+
+    #[test]
+    fn test_lex_comparison_and_logic_operators() {
+        let text = "== <= >= && || | &";
+
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::DoubleEquals, "==", 1, 0),
+            Token::new(TokenKind::LesserEquals, "<=", 1, 3),
+            Token::new(TokenKind::GreaterEquals, ">=", 1, 6),
+            Token::new(TokenKind::And, "&&", 1, 9),
+            Token::new(TokenKind::Or, "||", 1, 12),
+            Token::new(TokenKind::Pipe, "|", 1, 15),
+            Token::new(TokenKind::Poisoned, "&", 1, 17),
+            Token::new(TokenKind::EOF, "", 1, 18),
+        ];
+
+        check_lex(text, expected);
+    }
+
     #[test]
     fn test_poisoned_token() {
         let text = "@   object";
@@ -156,4 +193,40 @@ mod test_lexer {
 
         assert!(text.starts_with("color"))
     }
+
+    #[test]
+    fn test_attributes_are_skipped() {
+        let text = "#![deny(warnings)]\n#[allow(unused_objects)] object";
+
+        let mut lexer = Lexer::new(text);
+        let kinds: Vec<TokenKind> = lexer
+            .lex_with_separate_pass()
+            .iter()
+            .map(|t| t.get_kind())
+            .collect();
+
+        assert_eq!(vec![TokenKind::Object, TokenKind::EOF], kinds);
+
+        let attributes = lexer.attributes();
+        let snippets: Vec<&str> = attributes
+            .iter()
+            .map(|a| lexer.get_snippet(a.span))
+            .collect();
+
+        assert_eq!(
+            vec!["#![deny(warnings)]", "#[allow(unused_objects)]"],
+            snippets
+        );
+        assert!(attributes.iter().all(|a| a.next.get_cursor() == 44));
+    }
+
+    #[test]
+    fn test_unclosed_attribute_is_not_skipped() {
+        let mut lexer = Lexer::new("#[allow(warnings)\nobject");
+
+        assert!(lexer.attributes().is_empty());
+
+        lexer.advance();
+        assert_eq!(TokenKind::Poisoned, lexer.token.get_kind());
+    }
 }
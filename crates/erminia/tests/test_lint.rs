@@ -0,0 +1,198 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::code::DiagnosticLevel;
+use erminia::diagnostics::{Code, Diagnostic};
+use erminia::lint::{LintConfig, LintLevel, UNUSED_OBJECTS};
+use erminia::loader::Loader;
+
+#[cfg(test)]
+mod test_lint {
+    use super::*;
+
+    fn lint_with(text: &str, config: LintConfig) -> Vec<Diagnostic> {
+        let mut loader = Loader::new();
+        loader.set_lint_config(config);
+        loader.add_virtual_file("main.erm", text);
+        loader.load("main.erm").unwrap();
+
+        loader.get_diagnostics().get(CompilerPass::ALL)
+    }
+
+    fn lint(text: &str) -> Vec<Diagnostic> {
+        lint_with(text, LintConfig::new())
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<Code> {
+        diagnostics.iter().map(|d| d.code).collect()
+    }
+
+    const PROBLEM: &str = "def p () {
+        object Dot { shape: [(0, 0)], color: 1 };
+        object Spare { shape: [(1, 1)], color: 2 };
+
+        input i1 (3, 3) {
+            let d: object = Dot(0, 0);
+        };
+    }";
+
+    #[test]
+    fn test_unused_object() {
+        let diagnostics = lint(PROBLEM);
+
+        assert_eq!(vec![Code::W0001], codes(&diagnostics));
+        assert_eq!(DiagnosticLevel::Warning, diagnostics[0].level);
+        assert_eq!(CompilerPass::Lint, diagnostics[0].pass);
+        assert_eq!("Spare", diagnostics[0].window.snippet);
+    }
+
+    #[test]
+    fn test_top_level_objects_are_exported() {
+        assert!(lint("object Dot { shape: [(0, 0)], color: 1 };").is_empty());
+    }
+
+    #[test]
+    fn test_empty_shape() {
+        let diagnostics = lint("object Row { shape: [(x, 0) | x <- [0..1], x > 1], color: 1 };");
+
+        assert_eq!(vec![Code::W0002], codes(&diagnostics));
+        assert!(lint("object Row { shape: [(x, 0) | x <- [0..1]], color: 1 };").is_empty());
+
+        // An empty range is an error of its own, which no lint runs after
+        let diagnostics = lint("object Row { shape: [(x, 0) | x <- (0..1)], color: 1 };");
+        assert_eq!(vec![Code::E0018], codes(&diagnostics));
+    }
+
+    #[test]
+    fn test_unplaced_object() {
+        let diagnostics = lint(
+            "def p () {
+                object Dot { shape: [(0, 0)], color: 1 };
+                object Pair { shape: [Dot(0, 0), Dot(1, 0)], color: 1 };
+                let pair: object = Pair(0, 1);
+
+                input i1 (3, 3) {
+                    let d: object = Dot(0, 0);
+                };
+            }",
+        );
+
+        assert_eq!(vec![Code::W0003], codes(&diagnostics));
+        assert_eq!("Pair", diagnostics[0].window.snippet);
+    }
+
+    #[test]
+    fn test_object_placed_through_another() {
+        let diagnostics = lint(
+            "def p () {
+                object Dot { shape: [(0, 0)], color: 1 };
+                object Pair { shape: [Dot(0, 0), Dot(1, 0)], color: 1 };
+
+                output o1 (3, 3) {
+                    let pair: object = Pair(0, 1);
+                };
+            }",
+        );
+
+        assert!(diagnostics.is_empty(), "{:?}", codes(&diagnostics));
+    }
+
+    #[test]
+    fn test_overlapping_objects() {
+        let diagnostics = lint(
+            "object Dot { shape: [(0, 0)], color: 1 };
+            object Line { shape: [(x, 0) | x <- [0..2]], color: 2 };
+            object Cross { shape: [Line(0, 1), Dot(1, 1)], color: 3 };
+            object Apart { shape: [Line(0, 1), Dot(1, 0)], color: 3 };",
+        );
+
+        assert_eq!(vec![Code::W0004], codes(&diagnostics));
+        assert_eq!("Dot(1, 1)", diagnostics[0].window.snippet);
+        assert_eq!(1, diagnostics[0].window.labels.len());
+    }
+
+    #[test]
+    fn test_overlapping_placements() {
+        let diagnostics = lint(
+            "def p () {
+                object Dot { shape: [(0, 0)], color: 1 };
+
+                input i1 (3, 3) {
+                    let a: object = Dot(1, 1);
+                    let b: object = Dot(1, 1);
+                };
+            }",
+        );
+
+        assert_eq!(vec![Code::W0004], codes(&diagnostics));
+    }
+
+    #[test]
+    fn test_config_levels() {
+        let mut config = LintConfig::new();
+        config.set("unused_objects", LintLevel::Deny).unwrap();
+
+        let diagnostics = lint_with(PROBLEM, config);
+        assert_eq!(DiagnosticLevel::Error, diagnostics[0].level);
+
+        let mut config = LintConfig::new();
+        config.set("warnings", LintLevel::Allow).unwrap();
+        assert!(lint_with(PROBLEM, config.clone()).is_empty());
+
+        // Later settings win
+        config.set("unused_objects", LintLevel::Warn).unwrap();
+        assert_eq!(LintLevel::Warn, config.level(&UNUSED_OBJECTS));
+
+        assert!(config.set("unused_object", LintLevel::Allow).is_err());
+    }
+
+    #[test]
+    fn test_deny_blocks_later_passes() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", &format!("#![deny(warnings)]\n{}", PROBLEM));
+        loader.load("main.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics();
+
+        assert!(diagnostics.has_errors());
+        assert!(diagnostics.is_blocking(CompilerPass::ALL));
+        assert!(!diagnostics.is_blocking(CompilerPass::Lint));
+    }
+
+    #[test]
+    fn test_attributes() {
+        let allowed = PROBLEM.replace(
+            "object Spare",
+            "#[allow(unused_objects)]\n        object Spare",
+        );
+        assert!(lint(&allowed).is_empty());
+
+        // The innermost attribute wins over the one for the whole file
+        let mut config = LintConfig::new();
+        config.set("unused_objects", LintLevel::Allow).unwrap();
+
+        let denied = format!("#![allow(warnings)]\n#[deny(unused_objects)]\n{}", PROBLEM);
+        let diagnostics = lint_with(&denied, config);
+
+        assert_eq!(vec![Code::W0001], codes(&diagnostics));
+        assert_eq!(DiagnosticLevel::Error, diagnostics[0].level);
+    }
+
+    #[test]
+    fn test_unknown_lint() {
+        let diagnostics = lint(&format!("#[allow(unused_object)]\n{}", PROBLEM));
+
+        assert_eq!(vec![Code::W0005, Code::W0001], codes(&diagnostics));
+        assert_eq!("unused_object", diagnostics[0].window.snippet);
+        assert_eq!("Did you mean 'unused_objects'?", diagnostics[0].help);
+
+        let diagnostics = lint("#[forbid(unused_objects)]\ndef p () {}");
+        assert_eq!(vec![Code::W0005], codes(&diagnostics));
+    }
+
+    #[test]
+    fn test_no_lints_after_errors() {
+        let diagnostics = lint(&PROBLEM.replace("color: 2", "color: 2,"));
+
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().all(|d| d.pass != CompilerPass::Lint));
+    }
+}
@@ -0,0 +1,284 @@
+use erminia::ast::decl::DeclKind;
+use erminia::config::CompilerPass;
+use erminia::diagnostics::{Code, FileId};
+use erminia::loader::Loader;
+
+#[cfg(test)]
+mod test_loader {
+    use super::*;
+
+    fn codes(loader: &Loader) -> Vec<Code> {
+        loader
+            .get_diagnostics()
+            .get(CompilerPass::ALL)
+            .iter()
+            .map(|d| d.code)
+            .collect()
+    }
+
+    #[test]
+    fn test_load_single_file() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", "def p () {}");
+
+        let id = loader.load("main.erm").unwrap();
+        let module = loader.get_module(id).unwrap();
+
+        assert!(module.program.is_ok());
+        assert!(module.includes.is_empty());
+        assert!(codes(&loader).is_empty());
+    }
+
+    #[test]
+    fn test_load_missing_entry_file() {
+        let mut loader = Loader::new();
+
+        assert!(loader.load("does/not/exist.erm").is_err());
+    }
+
+    #[test]
+    fn test_include_merges_exports() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file(
+            "std/shapes.erm",
+            "object Line { shape: [(0,0), (1,0)], color: 1 };
+            func draw(object o) -> void {}
+            def internal () {}",
+        );
+        loader.add_virtual_file("main.erm", "use std::shapes; def p () {}");
+
+        let id = loader.load("main.erm").unwrap();
+        let module = loader.get_module(id).unwrap();
+
+        assert!(codes(&loader).is_empty());
+        assert_eq!(1, module.includes.len());
+        assert_eq!(DeclKind::Object, module.scope["Line"].kind);
+        assert_eq!(DeclKind::Func, module.scope["draw"].kind);
+        assert!(!module.scope.contains_key("internal"));
+        assert_eq!(module.includes[0], module.scope["Line"].span.file());
+    }
+
+    #[test]
+    fn test_include_relative_to_including_file() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("tasks/lib.erm", "object Dot { shape: [(0,0)], color: 1 };");
+        loader.add_virtual_file("tasks/main.erm", "include lib;");
+
+        let id = loader.load("tasks/main.erm").unwrap();
+
+        assert!(codes(&loader).is_empty());
+        assert!(loader.get_module(id).unwrap().scope.contains_key("Dot"));
+    }
+
+    #[test]
+    fn test_include_from_search_path() {
+        let mut loader = Loader::new();
+        loader.add_search_path("vendor");
+        loader.add_virtual_file("vendor/std/shapes.erm", "func draw(object o) {}");
+        loader.add_virtual_file("tasks/main.erm", "use std::shapes;");
+
+        let id = loader.load("tasks/main.erm").unwrap();
+
+        assert!(codes(&loader).is_empty());
+        assert!(loader.get_module(id).unwrap().scope.contains_key("draw"));
+    }
+
+    #[test]
+    fn test_each_file_parsed_once() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("base.erm", "object Dot { shape: [(0,0)], color: 1 };");
+        loader.add_virtual_file("left.erm", "include base;");
+        loader.add_virtual_file("right.erm", "include base;");
+        loader.add_virtual_file("main.erm", "include left; include right; include base;");
+
+        loader.load("main.erm").unwrap();
+
+        assert!(codes(&loader).is_empty());
+        assert_eq!(4, loader.get_files().len());
+    }
+
+    #[test]
+    fn test_include_missing_module() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", "include nowhere; def p () {}");
+
+        loader.load("main.erm").unwrap();
+
+        assert_eq!(vec![Code::E0010], codes(&loader));
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("a.erm", "include b;");
+        loader.add_virtual_file("b.erm", "include c;");
+        loader.add_virtual_file("c.erm", "include a;");
+
+        loader.load("a.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics().get(CompilerPass::Loader);
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Code::E0011, diagnostics[0].code);
+        assert!(diagnostics[0]
+            .note
+            .contains("a.erm -> b.erm -> c.erm -> a.erm"));
+    }
+
+    #[test]
+    fn test_include_conflicting_names() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("lib.erm", "object Dot { shape: [(0,0)], color: 1 };");
+        loader.add_virtual_file(
+            "main.erm",
+            "include lib; object Dot { shape: [(1,1)], color: 2 };",
+        );
+
+        loader.load("main.erm").unwrap();
+
+        assert_eq!(vec![Code::E0012], codes(&loader));
+    }
+
+    #[test]
+    fn test_diagnostic_points_into_included_file() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("lib.erm", "object Dot { shape: [(0,0)], color: 1 }; 42");
+        loader.add_virtual_file("main.erm", "include lib;");
+
+        loader.load("main.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics().get(CompilerPass::Parser);
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(FileId(1), diagnostics[0].window.span.file());
+        assert_eq!("42", diagnostics[0].window.snippet);
+    }
+
+    #[test]
+    fn test_rendered_with_path() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file(
+            "tasks/lib.erm",
+            "object Dot { shape: [(0,0)], color: 1 };\n42",
+        );
+        loader.add_virtual_file("tasks/main.erm", "include lib;");
+
+        loader.load("tasks/main.erm").unwrap();
+
+        let rendered = loader
+            .get_diagnostics()
+            .render_with(loader.get_source_map(), false);
+
+        assert!(
+            rendered.contains(" --> tasks/lib.erm:2:1\n"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_label_in_another_file() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("a.erm", "object Dot { shape: [(0,0)], color: 1 };");
+        loader.add_virtual_file("b.erm", "object Dot { shape: [(1,1)], color: 2 };");
+        loader.add_virtual_file("main.erm", "include a;\ninclude b;");
+
+        loader.load("main.erm").unwrap();
+
+        let rendered = loader
+            .get_diagnostics()
+            .render_with(loader.get_source_map(), false);
+
+        assert!(rendered.contains(" --> main.erm:2:1\n"), "{}", rendered);
+        assert!(
+            rendered.contains("= note: first declared here at a.erm:1:1\n"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_object_call_to_included_object() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("lib.erm", "object Dot { shape: [(0,0)], color: 1 };");
+        loader.add_virtual_file(
+            "main.erm",
+            "include lib; object Pair { shape: [Dot(1, 1)], color: 2 };",
+        );
+
+        loader.load("main.erm").unwrap();
+
+        assert!(codes(&loader).is_empty());
+    }
+
+    #[test]
+    fn test_object_call_to_unknown_object() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file(
+            "main.erm",
+            "object Dot { shape: [(0,0)], color: 1 };
+            object Pair { shape: [Dto(1, 1)], color: 2 };",
+        );
+
+        loader.load("main.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics().get(CompilerPass::Loader);
+
+        assert_eq!(vec![Code::E0015], codes(&loader));
+        assert_eq!("Dto", diagnostics[0].window.snippet);
+        assert_eq!("Did you mean 'Dot'?", diagnostics[0].help);
+        assert_eq!("Dot", diagnostics[0].suggestions[0].replacement);
+    }
+
+    #[test]
+    fn test_object_call_to_variable() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file(
+            "main.erm",
+            "object B { shape: [(0,0)], color: 1 };
+            def p () {
+                let b: object = B(0, 0);
+                let c: object = b(1, 1);
+            }",
+        );
+
+        loader.load("main.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics().get(CompilerPass::Loader);
+
+        assert_eq!(vec![Code::E0020], codes(&loader));
+        assert_eq!("b", diagnostics[0].window.snippet);
+        assert_eq!(
+            "'b' is a variable, not an object, so it cannot be called.",
+            diagnostics[0].note
+        );
+        assert_eq!("declared here", diagnostics[0].window.labels[0].message);
+    }
+
+    #[test]
+    fn test_variables_are_not_suggested_for_unknown_objects() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file(
+            "main.erm",
+            "def p () { let dto: int = 1; let b = Dto(0, 0); }",
+        );
+
+        loader.load("main.erm").unwrap();
+
+        let diagnostics = loader.get_diagnostics().get(CompilerPass::Loader);
+
+        assert_eq!(vec![Code::E0015], codes(&loader));
+        assert!(diagnostics[0].suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_long_operator_chain() {
+        let text = format!("def p () {{ let a: int = 1{}; }}", " + 1".repeat(100_000));
+
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", &text);
+        loader.load("main.erm").unwrap();
+
+        assert_eq!(vec![Code::I0002], codes(&loader));
+    }
+}
@@ -0,0 +1,173 @@
+use erminia::ast::ast::BoxAST;
+use erminia::ast::node::{NodeId, NodeTable};
+use erminia::ast::printon::PrintOpt;
+use erminia::config::CompilerPass;
+use erminia::diagnostics::DiagnosticAccumulator;
+use erminia::lexer::lex::Lexer;
+use erminia::loader::Loader;
+use erminia::syntax::parse::*;
+use std::fmt::{self, Write};
+
+#[cfg(test)]
+mod test_node {
+    use super::*;
+
+    fn parse(text: &str) -> BoxAST<'_> {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        parse_program(&mut tokens, &mut diag)
+    }
+
+    fn collect_ids(node: &BoxAST, ids: &mut Vec<NodeId>) {
+        if node.get_ast_id().is_assigned() {
+            ids.push(node.get_ast_id());
+        }
+
+        for child in node.children() {
+            collect_ids(child, ids);
+        }
+    }
+
+    fn find<'t, 'a>(node: &'t BoxAST<'a>, name: &str) -> Option<&'t BoxAST<'a>> {
+        if node.to_string() == name {
+            return Some(node);
+        }
+
+        node.children()
+            .into_iter()
+            .find_map(|child| find(child, name))
+    }
+
+    // The JSON of `node`, or the error it gave writing it
+    fn to_json(node: &BoxAST) -> Result<String, fmt::Error> {
+        struct Json<'n, 'a>(&'n BoxAST<'a>);
+
+        impl fmt::Display for Json<'_, '_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.to_json(&mut PrintOpt::new(f, 0))
+            }
+        }
+
+        let mut out = String::new();
+        write!(out, "{}", Json(node))?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_node_ids_are_unique() {
+        let program = parse(
+            "object Dot { shape: [(0,0)], color: 1 };
+            def p () { let a: object = Dot(1, 2) + Dot; }",
+        );
+
+        let mut ids = vec![];
+        collect_ids(&program, &mut ids);
+
+        let count = ids.len();
+
+        ids.sort();
+        ids.dedup();
+
+        assert_eq!(count, ids.len());
+        assert!(!ids.contains(&NodeId::UNASSIGNED));
+        assert_eq!(count, NodeTable::build(&program).len());
+    }
+
+    #[test]
+    fn test_root_has_no_parent() {
+        let program = parse("def p () {}");
+
+        let table = NodeTable::build(&program);
+        let root = program.get_ast_id();
+
+        assert!(root.is_assigned());
+        assert_eq!(None, table.get_parent(root));
+        assert_eq!("Program", table.get_name(root).unwrap());
+    }
+
+    #[test]
+    fn test_parent_and_children() {
+        let program = parse("def p () { let a: object = Dot(1, 2); }");
+
+        let table = NodeTable::build(&program);
+
+        let problem = table.get_children(program.get_ast_id())[0];
+        let var_def = table.get_children(problem)[0];
+        let call = table.get_children(var_def)[0];
+
+        assert_eq!("ProblemDecl", table.get_name(problem).unwrap());
+        assert_eq!("VarDef", table.get_name(var_def).unwrap());
+        assert_eq!("ObjectCall", table.get_name(call).unwrap());
+        assert_eq!(Some(var_def), table.get_parent(call));
+    }
+
+    #[test]
+    fn test_table_covers_poisoned_tree() {
+        let text = "def p () { let a: object = Dot(1 2); }";
+
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        let program = parse_program(&mut tokens, &mut diag);
+
+        let table = NodeTable::build(&program);
+
+        let mut ids = vec![];
+        collect_ids(&program, &mut ids);
+        ids.sort();
+
+        assert_eq!(ids, table.ids());
+        assert_eq!(1, diag.get(CompilerPass::Parser).len());
+    }
+
+    #[test]
+    fn test_loaded_module_has_node_table() {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", "def p () { let a: object = Dot(1, 2); }");
+
+        let id = loader.load("main.erm").unwrap();
+        let module = loader.get_module(id).unwrap();
+
+        let root = module.program.get_ast_id();
+
+        assert_eq!(Some("Program"), module.nodes.get_name(root));
+        assert_eq!(Some(module.program.get_span()), module.nodes.get_span(root));
+        assert_eq!(NodeTable::build(&module.program).ids(), module.nodes.ids());
+    }
+
+    #[test]
+    fn test_expressions_fail_without_panicking() {
+        let program = parse("def p () { let a: int = -(1 + 2); }");
+
+        for name in ["BinaryExpr", "UnaryExpr"] {
+            let node = find(&program, name).unwrap();
+
+            assert!(node.sem().is_err());
+            assert!(to_json(node).is_err());
+        }
+    }
+
+    #[test]
+    fn test_range_union_fails_without_panicking() {
+        let program = parse("object A { shape: [(x, 0) | x <- [0..1] U [3..4]], color: 1 };");
+
+        let node = find(&program, "RangeUnion").unwrap();
+
+        assert!(node.sem().is_err());
+        assert!(to_json(node).is_err());
+    }
+
+    #[test]
+    fn test_include_json() {
+        let program = parse("include shapes::basic;");
+
+        let node = find(&program, "Include").unwrap();
+
+        assert!(node.sem().is_err());
+        assert_eq!(
+            Ok(r#"{"include":["shapes","basic"]}"#.to_string()),
+            to_json(node)
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use erminia::lexer::token::TokenKind;
+use erminia::syntax::oracle::*;
+
+#[cfg(test)]
+mod test_oracle {
+    use super::*;
+
+    #[test]
+    fn test_next_tokens_of_empty_input() {
+        let next = next_tokens("");
+
+        assert_eq!(
+            vec![
+                TokenKind::ProblemDef,
+                TokenKind::FuncKwd,
+                TokenKind::Object,
+                TokenKind::IncludeKwd,
+                TokenKind::UseKwd,
+                TokenKind::EOF,
+            ],
+            next.kinds
+        );
+        assert_eq!(
+            vec!["def", "func", "object", "include", "use"],
+            next.spellings()
+        );
+        assert!(next.identifiers.is_empty());
+        assert!(next.valid);
+    }
+
+    #[test]
+    fn test_next_tokens_inside_expression() {
+        let next = next_tokens("def p () { let a: int = 1 + ");
+
+        assert_eq!(
+            vec![
+                TokenKind::Ident,
+                TokenKind::Int,
+                TokenKind::LeftPar,
+                TokenKind::Minus,
+                TokenKind::Not,
+            ],
+            next.kinds
+        );
+        assert!(!next.allows(TokenKind::SemiColon));
+    }
+
+    #[test]
+    fn test_next_tokens_after_comprehension_comma() {
+        let next = next_tokens("object A { shape: [(x, y) | x <- [0..3], ");
+
+        assert!(next.allows(TokenKind::Int));
+        assert!(next.allows(TokenKind::Ident));
+        assert!(next.allows(TokenKind::LeftPar));
+        assert_eq!(vec!["A", "x"], next.identifiers);
+    }
+
+    #[test]
+    fn test_identifiers_in_scope() {
+        let text = "object A { shape: [(0, 0)], color: 1 };
+                    def p () { let hidden: int = 1; }
+                    func f (int x) { let y: int = 1; let z: int = ";
+
+        let next = next_tokens(text);
+
+        assert_eq!(vec!["A", "f", "x", "y"], next.identifiers);
+    }
+
+    #[test]
+    fn test_identifiers_after_finished_item() {
+        let next = next_tokens("func f (int x) { let y: int = x; } ");
+
+        assert!(next.allows(TokenKind::EOF));
+        assert_eq!(vec!["f"], next.identifiers);
+    }
+
+    #[test]
+    fn test_next_tokens_after_syntax_error() {
+        assert!(next_tokens("def p () { let a: int = 1 + ").valid);
+
+        // Recovered up to the next declaration, or nowhere at all
+        for prefix in [
+            "object A { shape: [(0,0)], colr: 1 };",
+            "object A { shape: [(0,0)], colr: 1 ",
+            "def p () { let a: @ = ",
+        ] {
+            assert!(!next_tokens(prefix).valid, "{}", prefix);
+        }
+    }
+
+    #[test]
+    fn test_spellings_leave_out_open_kinds() {
+        assert_eq!(Some("let".to_string()), TokenKind::LetKwd.spelling());
+        assert_eq!(Some("<-".to_string()), TokenKind::LeftArrow.spelling());
+        assert_eq!(None, TokenKind::Ident.spelling());
+        assert_eq!(None, TokenKind::EOF.spelling());
+    }
+}
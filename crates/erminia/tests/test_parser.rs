@@ -1,10 +1,12 @@
 use erminia::ast::ast::BoxAST;
 use erminia::config::CompilerPass;
-use erminia::diagnostics::DiagnosticAccumulator;
-use erminia::lexer::lex::Lexer;
-use erminia::lexer::token::TokenKind;
+use erminia::diagnostics::{Code, DiagnosticAccumulator};
+use erminia::error::parser_error::ParserError;
+use erminia::lexer::lex::{Lexer, MAX_NESTING};
+use erminia::lexer::token::{SyntaxToken, TokenKind};
 use erminia::syntax::consumers::*;
 use erminia::syntax::parse::*;
+use erminia::syntax::{parse, Parser, INPUT_PATH};
 use erminia::types::ErminiaType;
 
 #[cfg(test)]
@@ -59,7 +61,7 @@ mod test_parser {
     //     text: &'a str,
     //     parser: F,
     // ) where
-    //     F: FnOnce(&mut Lexer, &mut DiagnosticAccumulator) -> (BoxAST<'a>, Vec<ErminiaType>),
+    //     F: FnOnce(&mut Lexer, &mut DiagnosticAccumulator) -> (BoxAST<'a>, Vec<SyntaxToken>),
     // {
     //     let mut tokens = Lexer::new(text);
     //     let mut diag = DiagnosticAccumulator::new();
@@ -80,7 +82,7 @@ mod test_parser {
 
     fn check_no_err_multiple_ast_with_syntax_ret<'a, F>(text: &'a str, parser: F)
     where
-        F: FnOnce(&mut Lexer, &mut DiagnosticAccumulator) -> (Vec<BoxAST<'a>>, Vec<ErminiaType>),
+        F: FnOnce(&mut Lexer, &mut DiagnosticAccumulator) -> (Vec<BoxAST<'a>>, Vec<SyntaxToken>),
     {
         let mut tokens = Lexer::new(text);
         let mut diag = DiagnosticAccumulator::new();
@@ -99,20 +101,67 @@ mod test_parser {
         assert!(res.iter().all(|ast| ast.is_ok()))
     }
 
-    fn check_type(text: &str, expected_type: ErminiaType) {
+    fn check_err_codes<'a, F>(text: &'a str, parser: F, expected: Vec<Code>)
+    where
+        F: FnOnce(&mut Lexer, &mut DiagnosticAccumulator) -> BoxAST<'a>,
+    {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        tokens.advance();
+
+        let res = parser(&mut tokens, &mut diag);
+
+        let actual: Vec<Code> = diag
+            .get(CompilerPass::Parser)
+            .iter()
+            .map(|d| d.code)
+            .collect();
+
+        assert!(res.is_err());
+        assert_eq!(expected, actual);
+    }
+
+    fn check_program(text: &str, expected: Vec<Code>) {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        let res = parse_program(&mut tokens, &mut diag);
+
+        let actual: Vec<Code> = diag
+            .get(CompilerPass::Parser)
+            .iter()
+            .map(|d| d.code)
+            .collect();
+
+        assert_eq!(expected.is_empty(), res.is_ok());
+        assert_eq!(expected, actual);
+    }
+
+    // Like `check_program`, but over the diagnostics of every pass
+    fn check_program_all_passes(text: &str, expected: Vec<Code>) {
         let mut tokens = Lexer::new(text);
         let mut diag = DiagnosticAccumulator::new();
 
-        let start = tokens.get_previous_position();
+        let _ = parse_program(&mut tokens, &mut diag);
+
+        let actual: Vec<Code> = diag.get(CompilerPass::ALL).iter().map(|d| d.code).collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    fn check_type(text: &str, expected_type: ErminiaType) {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
 
         tokens.advance();
 
-        let _ = consume_keyword(&mut tokens, TokenKind::LetKwd, &mut diag, start);
-        let _ = consume_identifier(&mut tokens, &mut diag, start);
+        let _ = consume_keyword(&mut tokens, TokenKind::LetKwd, &mut diag);
+        let _ = consume_identifier(&mut tokens, &mut diag);
 
         let actual_type = if match_next(&mut tokens, TokenKind::Colon) {
-            let _ = consume_keyword(&mut tokens, TokenKind::Colon, &mut diag, start);
-            consume_data_type(&mut tokens, &mut diag, start)
+            let _ = consume_keyword(&mut tokens, TokenKind::Colon, &mut diag);
+            consume_data_type(&mut tokens, &mut diag)
         // TODO: Add logic for type inference
         } else {
             ErminiaType::default()
@@ -171,6 +220,41 @@ mod test_parser {
         check_no_err_single_ast(text, parse_shape)
     }
 
+    #[test]
+    fn test_parse_shape_tuple_compr_with_guard() {
+        let text = "(x, y) | x <- [0..9], y <- [0..9], (x + y) % 2 == 0";
+
+        check_no_err_single_ast(text, parse_shape)
+    }
+
+    #[test]
+    fn test_parse_shape_tuple_compr_many_iterators() {
+        let text = "(x, y) | x <- [0..3], z <- [0..2], y <- [0..3], x != z && !(y < 1)";
+
+        check_no_err_single_ast(text, parse_shape)
+    }
+
+    #[test]
+    fn test_parse_list_of_shapes_with_guards() {
+        let text = "[(x,y) | x <- [0..4], y <- [0..4], x == 0 || y == 0 || x == 4 || y == 4, (x, y) | x <- [1..3], y <- [1..3], (1, 1), obj]";
+
+        check_no_err_multiple_ast_with_syntax_ret(text, parse_list_of_shapes)
+    }
+
+    #[test]
+    fn test_parse_shape_tuple_compr_range_algebra() {
+        let text = "(x, 1) | x <- [1..10 by 2] U [14..10] U (n..n + 3]";
+
+        check_no_err_single_ast(text, parse_shape)
+    }
+
+    #[test]
+    fn test_parse_var_def_expr() {
+        let text = "let x = -(1 + 2) * 3 // 2;";
+
+        check_no_err_single_ast(text, parse_var_def)
+    }
+
     #[test]
     fn test_parse_var_def() {
         let text = "let x: object = HA(0,1);";
@@ -178,6 +262,61 @@ mod test_parser {
         check_no_err_single_ast(text, parse_var_def)
     }
 
+    #[test]
+    fn test_parse_var_def_named_offsets() {
+        let text = "let back = Background(offset_x: 0, offset_y: 2);";
+
+        check_no_err_single_ast(text, parse_var_def)
+    }
+
+    #[test]
+    fn test_parse_var_def_partial_named_offsets() {
+        let text = "let so = Object(y : 2);";
+
+        check_no_err_single_ast(text, parse_var_def)
+    }
+
+    #[test]
+    fn test_parse_var_def_empty_object_call() {
+        let text = "let o = Object();";
+
+        check_no_err_single_ast(text, parse_var_def)
+    }
+
+    #[test]
+    fn test_parse_object_call_negative_offsets() {
+        check_no_err_single_ast("Dot(-1, 0)", parse_object_call);
+        check_no_err_single_ast("Dot(offset_x: 2, y: -3)", parse_object_call);
+    }
+
+    #[test]
+    fn test_parse_object_call_unknown_argument() {
+        let text = "Object(offset_z: 1)";
+
+        check_err_codes(text, parse_object_call, vec![Code::E0005])
+    }
+
+    #[test]
+    fn test_parse_object_call_duplicate_argument() {
+        let text = "Object(x: 1, offset_x: 2)";
+
+        check_err_codes(text, parse_object_call, vec![Code::E0006])
+    }
+
+    #[test]
+    fn test_parse_object_call_mixed_arguments() {
+        let text = "Object(1, offset_y: 2)";
+
+        check_err_codes(text, parse_object_call, vec![Code::E0007])
+    }
+
+    #[test]
+    fn test_parse_object_call_too_many_arguments() {
+        let text = "Object(1, 2, 3, 4)";
+
+        check_err_codes(text, parse_object_call, vec![Code::E0008])
+    }
+
     #[test]
     fn test_parse_var_def_explicit_object_type() {
         let text = "let x: object = HA(0,1);";
@@ -339,4 +478,441 @@ mod test_parser {
 
         check_no_err_single_ast(text, parse_problem_decl)
     }
+
+    #[test]
+    fn test_parse_func_decl() {
+        let text = "func move(object o, string direction, int amount) -> void {}";
+
+        check_no_err_single_ast(text, parse_func_decl)
+    }
+
+    #[test]
+    fn test_parse_func_decl_no_params() {
+        let text = "func noop() {}";
+
+        check_no_err_single_ast(text, parse_func_decl)
+    }
+
+    #[test]
+    fn test_parse_problem_decl_without_int_const() {
+        let text = "def std () { func draw(object o) -> void {} }";
+
+        check_no_err_single_ast(text, parse_problem_decl)
+    }
+
+    #[test]
+    fn test_parse_program_many_items() {
+        let text = "def std () {
+            func draw(object o) -> void {}
+            func scale(object o, int factor) -> void {}
+        }
+
+        object Dot { shape: [(0,0)], color: 1 };
+
+        func identity(object o) -> object {}
+
+        def problem1 (7) {
+            let d: object = Dot(1, 1);
+        }
+
+        def problem2 (3) {}";
+
+        check_program(text, vec![])
+    }
+
+    #[test]
+    fn test_parse_empty_program() {
+        check_program("", vec![])
+    }
+
+    #[test]
+    fn test_parse_program_trailing_garbage() {
+        let text = "def hello (2) {} ; 42";
+
+        check_program(text, vec![Code::E0009])
+    }
+
+    #[test]
+    fn test_parse_program_recovers_after_garbage() {
+        let text = "def a () {} let x: object = A; def b () {} 42 def c () {}";
+
+        check_program(text, vec![Code::E0009, Code::E0009])
+    }
+
+    #[test]
+    fn test_parse_include() {
+        check_no_err_single_ast("include shapes;", parse_include)
+    }
+
+    #[test]
+    fn test_parse_use_module_path() {
+        check_no_err_single_ast("use std::shapes;", parse_include)
+    }
+
+    #[test]
+    fn test_parse_program_with_includes() {
+        let text = "use std::shapes;
+        include helpers;
+
+        def problem1 (1) {}";
+
+        check_program(text, vec![])
+    }
+
+    #[test]
+    fn test_recovery_reports_each_broken_statement() {
+        let text = "def p () {
+            let a: object = Dot(1 2);
+            let b: object = 5 +;
+            let c: object = Dot(1, 2);
+        }";
+
+        check_program(text, vec![Code::E0001, Code::E0023])
+    }
+
+    #[test]
+    fn test_recovery_continues_with_next_declaration() {
+        let text = "def a () { let x: object = ; } def b () { let y: object = ; }";
+
+        check_program(text, vec![Code::E0023, Code::E0023])
+    }
+
+    #[test]
+    fn test_recovery_inside_shape_list() {
+        let text = "object A { shape: [(0 0), (1,1), (2,)], color: 1 };";
+
+        check_program(text, vec![Code::E0001, Code::E0003])
+    }
+
+    #[test]
+    fn test_recovery_missing_closing_par() {
+        let text = "def p () { foo(1, 2; let a: int = 3; }";
+
+        check_program(text, vec![Code::E0001])
+    }
+
+    #[test]
+    fn test_recovery_terminates_on_garbage() {
+        let text = "def p () { ) ) ] ; , let } } ] def";
+
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        assert!(parse_program(&mut tokens, &mut diag).is_err());
+        assert!(!diag.get(CompilerPass::Parser).is_empty());
+    }
+
+    #[test]
+    fn test_independent_errors_in_one_statement() {
+        let text = "def p () { let a: object = Dot(1 2) + Dot(3 4); }";
+
+        check_program(text, vec![Code::E0001, Code::E0001])
+    }
+
+    #[test]
+    fn test_poisoned_nodes_do_not_cascade() {
+        let text = "def p () { example e (1) { input i (0 0) { let x: object = A; }; }; }";
+
+        check_program_all_passes(text, vec![Code::E0001])
+    }
+
+    #[test]
+    fn test_missing_comma_between_object_fields() {
+        let text = "object B { shape: [(0,0)] color: 1 };";
+
+        check_program_all_passes(text, vec![Code::E0001])
+    }
+
+    #[test]
+    fn test_each_misspelled_object_field_reported() {
+        let text = "object A { shpae: [(0,0)], colour: 1 }; object B { shape: [(0,0)], color: 1 };";
+
+        check_program_all_passes(text, vec![Code::E0019, Code::E0019]);
+
+        let text =
+            "object A { shape: [(0,0)], shape: [(1,1)] }; object B { color: 1, shape: [(0,0)] };";
+
+        check_program_all_passes(text, vec![Code::E0001]);
+    }
+
+    #[test]
+    fn test_stray_token_in_block_reported_alone() {
+        let text = "def p () { 5; let a = 1; let b: 7 = 2; } def q () { let c: 9 = 1; }";
+
+        check_program_all_passes(text, vec![Code::E0024, Code::E0002, Code::E0002]);
+
+        let text = "def p () { input i (1, 1) { 5; let a: 7 = Dot(0, 0); }; }";
+
+        check_program_all_passes(text, vec![Code::E0024, Code::E0002]);
+
+        // A block left open ends at the next declaration that can only be a top-level one
+        let text = "def p () { let a = 1; def q () { let b: 9 = 1; }";
+
+        check_program_all_passes(text, vec![Code::E0001, Code::E0002]);
+    }
+
+    #[test]
+    fn test_stray_semicolon_in_shape_list() {
+        let text = "object A { shape: [(;, 0), (1 1), (2, 2)], color: 1 };
+            object B { shape: [(0, 0)], color: x };";
+
+        check_program_all_passes(text, vec![Code::E0003, Code::E0001, Code::E0003]);
+    }
+
+    #[test]
+    fn test_expected_operand_has_one_code() {
+        // In an expression and in a comprehension tuple alike
+        let text = "def p () { let a: int = 1 + ; }
+            object A { shape: [(x, ;) | x <- [0..1]], color: 1 };";
+
+        check_program_all_passes(text, vec![Code::E0023, Code::E0023]);
+    }
+
+    #[test]
+    fn test_var_def_value_checked_after_broken_type() {
+        let text = "def p () { let a: 5 = 1 +; }";
+
+        check_program_all_passes(text, vec![Code::E0002, Code::E0023]);
+
+        let text = "def p () { let 3 = 2 * ; let c: int = 1; }";
+
+        check_program_all_passes(text, vec![Code::E0022, Code::E0023]);
+    }
+
+    #[test]
+    fn test_lexer_error_reported_with_later_parser_error() {
+        let text = "def p () { let a: @ = Dot(1 2); }";
+
+        check_program_all_passes(text, vec![Code::E0014, Code::E0001])
+    }
+
+    #[test]
+    fn test_missing_semicolon_at_end_of_line() {
+        let text = "def p () {\n  let a: int = 1\n  let b: int = 2 + 3;\n}";
+
+        check_program_all_passes(text, vec![Code::E0001]);
+
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        let _ = parse_program(&mut tokens, &mut diag);
+
+        // Right after the `1`, where the `;` goes
+        let span = diag.get(CompilerPass::Parser)[0].window.span;
+        let at = text.find("1\n").unwrap() + 1;
+
+        assert_eq!((at, at), (span.start.get_cursor(), span.end.get_cursor()));
+
+        // Nodes poisoned along with the statement are not reported on their own
+        let text = "def p () {\n  let a: int = 1 +\n  let b: int = 2;\n}";
+
+        check_program_all_passes(text, vec![Code::E0023]);
+    }
+
+    #[test]
+    fn test_parse_output_without_errors() {
+        let text = "object Dot { shape: [(0,0)], color: 1 };";
+
+        let output = parse(text).unwrap();
+
+        assert!(!output.has_errors());
+        assert!(output.ast.is_ok());
+        assert_eq!(TokenKind::Object, output.tokens[0].get_kind());
+        assert_eq!(TokenKind::EOF, output.tokens.last().unwrap().get_kind());
+        assert_eq!(1, output.source_map.len());
+        assert_eq!(
+            INPUT_PATH,
+            output.source_map.files()[0].path.to_str().unwrap()
+        );
+        assert_eq!(text, output.source_map.files()[0].content);
+    }
+
+    #[test]
+    fn test_parse_output_with_recoverable_errors() {
+        let text = "def p () { let a: object = Dot(1 2); }";
+
+        let output = parse(text).unwrap();
+
+        assert!(output.has_errors());
+        assert!(output.ast.is_err());
+        assert_eq!(
+            vec![Code::E0001],
+            output
+                .diagnostics
+                .get(CompilerPass::Parser)
+                .iter()
+                .map(|d| d.code)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_output_nested_too_deeply() {
+        let nested = |depth: usize| {
+            format!(
+                "def p () {{ let a: int = {}1{}; }} object Dot {{ shape: [(0,0)], color: x }};",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+
+        // The parser goes as deep as the limit
+        let text = nested(MAX_NESTING - 1);
+        let output = parse(&text).unwrap();
+        assert!(output.has_errors());
+
+        // Past it, it stops with nothing more to say about the rest of the input
+        let Err(ParserError::Fatal(diagnostics)) = parse(&nested(100_000)) else {
+            panic!("parsing went on past the nesting limit");
+        };
+
+        assert_eq!(
+            vec![Code::I0002],
+            diagnostics.iter().map(|d| d.code).collect::<Vec<_>>()
+        );
+
+        let text = format!("def p () {{ let a: int = {}1; }}", "- ".repeat(100_000));
+        assert!(parse(&text).is_err());
+    }
+
+    #[test]
+    fn test_parse_output_operator_chain_too_long() {
+        let chain =
+            |terms: usize| format!("def p () {{ let a: int = 1{}; }}", " + 1".repeat(terms));
+
+        let text = chain(MAX_NESTING - 1);
+        assert!(!parse(&text).unwrap().has_errors());
+
+        let Err(ParserError::Fatal(diagnostics)) = parse(&chain(100_000)) else {
+            panic!("parsing went on past the nesting limit");
+        };
+
+        assert_eq!(
+            vec![Code::I0002],
+            diagnostics.iter().map(|d| d.code).collect::<Vec<_>>()
+        );
+
+        // Mixed precedences count toward the same limit
+        let text = format!(
+            "def p () {{ let a: int = 1{}; }}",
+            " * 2 + 1".repeat(100_000)
+        );
+        assert!(parse(&text).is_err());
+    }
+
+    fn codes(parser: &Parser) -> Vec<Code> {
+        parser
+            .get_diagnostics()
+            .get(CompilerPass::ALL)
+            .iter()
+            .map(|d| d.code)
+            .collect()
+    }
+
+    #[test]
+    fn test_fragment_entry_points() {
+        let mut parser = Parser::new("object Dot { shape: [(0,0), (1,1)], color: 4 };");
+        assert!(parser.parse_object().is_ok());
+        assert!(codes(&parser).is_empty());
+
+        let mut parser = Parser::new("[(0,0), Dot(1, 2), (x, y) | x <- [0..2], y <- [0..2]]");
+        assert_eq!(3, parser.parse_shape_list().len());
+        assert!(codes(&parser).is_empty());
+
+        let mut parser = Parser::new("Dot(offset_x: 1)");
+        assert!(parser.parse_shape().is_ok());
+        assert!(codes(&parser).is_empty());
+
+        let mut parser = Parser::new("-(1 + x) * 2");
+        assert!(parser.parse_expr().is_ok());
+        assert!(codes(&parser).is_empty());
+    }
+
+    #[test]
+    fn test_fragment_followed_by_more_input() {
+        let mut parser = Parser::new("object Dot { shape: [(0,0)], color: 4 }; def p () { }");
+        assert!(parser.parse_object().is_ok());
+        assert_eq!(vec![Code::E0013], codes(&parser));
+
+        let mut parser = Parser::new("1 + 2 3");
+        assert!(parser.parse_expr().is_ok());
+        assert_eq!(vec![Code::E0013], codes(&parser));
+    }
+
+    #[test]
+    fn test_broken_fragment_reports_once() {
+        let mut parser = Parser::new("[(0,0), (1 1), (2,2)]");
+        assert!(parser.parse_shape_list().iter().any(|shape| shape.is_err()));
+        assert_eq!(vec![Code::E0001], codes(&parser));
+    }
+
+    #[test]
+    fn test_partial_parse_inside_statement() {
+        let partial =
+            Parser::new("def p () { let a: object = Dot(1, 2); let b: int = (1 +").parse_partial();
+
+        assert!(!partial.is_complete());
+        assert!(partial.diagnostics.get(CompilerPass::ALL).is_empty());
+        assert_eq!(
+            vec![
+                TokenKind::Ident,
+                TokenKind::Int,
+                TokenKind::LeftPar,
+                TokenKind::Minus,
+                TokenKind::Not
+            ],
+            partial.expected
+        );
+
+        // The finished statement is kept, the one the input ended in is poisoned
+        let stmts = partial.ast.children()[0].children();
+        assert!(stmts[0].is_ok());
+        assert!(stmts[1].is_err());
+    }
+
+    #[test]
+    fn test_partial_parse_after_expression() {
+        let partial = Parser::new("def p () { let a: int = 1").parse_partial();
+
+        assert!(partial.expected.contains(&TokenKind::SemiColon));
+        assert!(partial.expected.contains(&TokenKind::Plus));
+        assert!(!partial.expected.contains(&TokenKind::RightBrace));
+    }
+
+    #[test]
+    fn test_partial_parse_of_complete_program() {
+        let partial = Parser::new("object Dot { shape: [(0,0)], color: 1 };").parse_partial();
+
+        assert!(partial.is_valid());
+        assert!(partial.is_complete());
+        assert!(partial.expected.contains(&TokenKind::ProblemDef));
+    }
+
+    #[test]
+    fn test_partial_parse_of_erroneous_prefix() {
+        let partial = Parser::new("object A { shape: [(0,0)], colr: 1 ").parse_partial();
+
+        assert!(!partial.is_valid());
+        assert!(!partial.is_complete());
+
+        let partial = Parser::new("object A { shape: [(0,0)], colr: 1 };").parse_partial();
+
+        assert!(!partial.is_complete());
+    }
+
+    #[test]
+    fn test_partial_parse_keeps_real_errors() {
+        let partial = Parser::new("def p () { let b: @ = 1; let a: int =").parse_partial();
+
+        assert!(!partial.is_complete());
+        assert_eq!(
+            vec![Code::E0014],
+            partial
+                .diagnostics
+                .get(CompilerPass::ALL)
+                .iter()
+                .map(|d| d.code)
+                .collect::<Vec<_>>()
+        );
+    }
 }
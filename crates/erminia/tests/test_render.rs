@@ -0,0 +1,171 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::{create_diagnostic, Code, SourceMap, Span};
+use erminia::lexer::lex::Lexer;
+use erminia::syntax::{parse, Parser};
+
+#[cfg(test)]
+mod test_render {
+    use super::*;
+
+    fn render(text: &str) -> String {
+        let mut parser = Parser::new(text);
+        parser.parse();
+
+        let diagnostics = parser.get_diagnostics().get(CompilerPass::Parser);
+        assert!(
+            !diagnostics.is_empty(),
+            "no parser diagnostic for {:?}",
+            text
+        );
+
+        diagnostics[0].render(false)
+    }
+
+    #[test]
+    fn test_caret_under_span() {
+        let rendered = render("def p () {\n  let a: int = 1 +;\n}");
+
+        assert_eq!(
+            "error[E0023]: Expected identifier or integer constant but something else was found\n \
+             --> 2:19\n  \
+             |\n\
+             2 |   let a: int = 1 +;\n  \
+             |                   ^\n  \
+             |\n  \
+             = pass: Parser\n  \
+             = note: Expected an identifier or integer constant, but found ';'.\n",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_secondary_label() {
+        let rendered = render("object A { shape: [Dot(x: 1, y: 2, x: 3)], color: 1 };");
+
+        let expected = [
+            "1 | object A { shape: [Dot(x: 1, y: 2, x: 3)], color: 1 };",
+            "  |                        ----        ^^^^",
+            "  |                        |",
+            "  |                        first given here",
+        ];
+
+        assert!(rendered.contains(&expected.join("\n")), "{}", rendered);
+    }
+
+    #[test]
+    fn test_multiline_span() {
+        let mut tokens = Lexer::new("def p () {\n  let d: int = 4\n}");
+
+        // From `let` to the closing brace
+        tokens.advance();
+        while tokens.token.text != "let" {
+            tokens.advance();
+        }
+        let start = tokens.get_previous_position();
+
+        while tokens.token.text != "}" {
+            tokens.advance();
+        }
+        let span = Span::new(start, tokens.get_position());
+
+        let diagnostic = create_diagnostic(CompilerPass::Parser, &mut tokens, Code::E0001, span);
+        let rendered = diagnostic.render(false);
+
+        let expected = ["2 | /   let d: int = 4", "3 | | }", "  | |_^"];
+
+        assert!(rendered.contains(&expected.join("\n")), "{}", rendered);
+    }
+
+    #[test]
+    fn test_long_multiline_span_elided() {
+        let body: String = (0..10)
+            .map(|i| format!("  let v{i}: int = {i};\n"))
+            .collect();
+        let text = format!("def p () {{\n{body}}}");
+        let mut tokens = Lexer::new(&text);
+
+        // From the first `let` to the closing brace
+        tokens.advance();
+        while tokens.token.text != "let" {
+            tokens.advance();
+        }
+        let start = tokens.get_previous_position();
+
+        while tokens.token.text != "}" {
+            tokens.advance();
+        }
+        let span = Span::new(start, tokens.get_position());
+
+        let diagnostic = create_diagnostic(CompilerPass::Parser, &mut tokens, Code::E0001, span);
+        let rendered = diagnostic.render(false);
+
+        let expected = [
+            " 2 | /   let v0: int = 0;",
+            " 3 | |   let v1: int = 1;",
+            " 4 | |   let v2: int = 2;",
+            "...",
+            "10 | |   let v8: int = 8;",
+            "11 | |   let v9: int = 9;",
+            "12 | | }",
+            "   | |_^",
+        ];
+
+        assert!(rendered.contains(&expected.join("\n")), "{}", rendered);
+    }
+
+    #[test]
+    fn test_gutter_uses_real_line_numbers() {
+        let text = format!("{}def p () {{ let a: int = ; }}", "\n".repeat(11));
+        let rendered = render(&text);
+
+        assert!(rendered.contains("  --> 12:25\n"), "{}", rendered);
+        assert!(rendered.contains("12 | def p () {"), "{}", rendered);
+        assert!(
+            rendered.contains("   |                         ^\n"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_location_with_path() {
+        let parsed = parse("def p () {\n  let a: int = 1 +;\n}").unwrap();
+        let diagnostic = &parsed.diagnostics.diagnostics[0];
+
+        let rendered = diagnostic.render_with(&parsed.source_map, false);
+        assert!(rendered.contains(" --> <input>:2:19\n"), "{}", rendered);
+
+        // Columns count characters, not bytes
+        let mut source_map = SourceMap::new();
+        let file = source_map.add_file("task.erm", "// é\nobject".to_string());
+
+        assert_eq!(Some((1, 5)), source_map[file].position(5));
+        assert_eq!(Some((2, 1)), source_map[file].position(6));
+        assert_eq!(None, source_map[file].position(4));
+        assert_eq!(None, source_map[file].position(40));
+    }
+
+    #[test]
+    fn test_no_color_mode() {
+        let text = "def p () { let a: int = ; }";
+        let mut tokens = Lexer::new(text);
+        tokens.advance();
+
+        let span = Span::new(tokens.get_position(), tokens.get_position());
+        let diagnostic = create_diagnostic(CompilerPass::Parser, &mut tokens, Code::E0001, span);
+
+        assert!(!diagnostic.render(false).contains('\u{1b}'));
+
+        colored::control::set_override(true);
+        assert!(diagnostic.render(true).contains('\u{1b}'));
+
+        // Printing a diagnostic or a fatal parser error leaves the colors out
+        assert!(!diagnostic.to_string().contains('\u{1b}'));
+
+        let nested = format!("def p () {{ let a: int = {}1; }}", "(".repeat(100_000));
+        let err = parse(&nested).err().unwrap();
+        assert!(!err.to_string().contains('\u{1b}'));
+        assert!(err.render(true).contains('\u{1b}'));
+        colored::control::unset_override();
+    }
+}
@@ -0,0 +1,182 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::{Code, Diagnostic};
+use erminia::loader::Loader;
+
+#[cfg(test)]
+mod test_semantics {
+    use super::*;
+
+    fn check(text: &str) -> Vec<Diagnostic> {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", text);
+        loader.load("main.erm").unwrap();
+
+        loader.get_diagnostics().get(CompilerPass::ALL)
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<Code> {
+        diagnostics.iter().map(|d| d.code).collect()
+    }
+
+    fn problem(size: &str, placements: &str) -> String {
+        format!(
+            "def p () {{
+                object Dot {{ shape: [(0, 0)], color: 1 }};
+                object Line {{ shape: [(x, 0) | x <- [0..2]], color: 2 }};
+                object Pair {{ shape: [Dot(0, 0), Dot(0, 1)], color: 3 }};
+
+                input i1 {} {{
+                    {}
+                }};
+            }}",
+            size, placements
+        )
+    }
+
+    #[test]
+    fn test_placements_within_grid() {
+        let text = problem(
+            "(3, 3)",
+            "let l: object = Line(0, 0); let p: object = Pair(2, 1);",
+        );
+
+        assert!(check(&text).is_empty(), "{:?}", codes(&check(&text)));
+    }
+
+    #[test]
+    fn test_cells_outside_grid() {
+        let diagnostics = check(&problem("(3, 3)", "let l: object = Line(1, 0);"));
+
+        assert_eq!(vec![Code::E0016], codes(&diagnostics));
+        assert_eq!(CompilerPass::Semantics, diagnostics[0].pass);
+        assert_eq!("Line(1, 0)", diagnostics[0].window.snippet);
+        assert_eq!(
+            "'Line' covers 1 cell(s) outside the 3x3 grid, such as (3, 0).",
+            diagnostics[0].note
+        );
+        assert_eq!(
+            "grid size given here",
+            diagnostics[0].window.labels[0].message
+        );
+    }
+
+    #[test]
+    fn test_nested_cells_outside_grid() {
+        // Only the second dot of the pair is off the grid
+        let diagnostics = check(&problem("(3, 3)", "let p: object = Pair(0, 2);"));
+
+        assert_eq!(vec![Code::E0016], codes(&diagnostics));
+
+        let text = problem(
+            "(3, 3)",
+            "object Left { shape: [(x, 0) | x <- [-1..0]], color: 1 };",
+        );
+        let diagnostics = check(&text);
+
+        assert_eq!(vec![Code::E0016], codes(&diagnostics));
+        assert!(diagnostics[0].note.ends_with("such as (-1, 0)."));
+    }
+
+    #[test]
+    fn test_negative_offsets() {
+        let diagnostics = check(&problem("(3, 3)", "let d: object = Dot(-1, 0);"));
+
+        assert_eq!(vec![Code::E0016], codes(&diagnostics));
+        assert!(diagnostics[0].note.ends_with("such as (-1, 0)."));
+    }
+
+    #[test]
+    fn test_offsets_past_integer_limits() {
+        let text = problem(
+            "(3, 3)",
+            "let d: object = Dot(2147483647, 0); let l: object = Line(2147483647, 0);",
+        );
+        let diagnostics = check(&text);
+
+        assert_eq!(vec![Code::E0016, Code::E0016], codes(&diagnostics));
+        assert_eq!(
+            "'Line' covers 3 cell(s) outside the 3x3 grid, such as (2147483647, 0).",
+            diagnostics[1].note
+        );
+    }
+
+    #[test]
+    fn test_grid_size_limits() {
+        for size in ["(0, 3)", "(3, 31)", "(31, 31)"] {
+            let diagnostics = check(&problem(size, "let d: object = Dot(0, 0);"));
+
+            assert_eq!(vec![Code::E0017], codes(&diagnostics), "{}", size);
+            assert_eq!(size, diagnostics[0].window.snippet);
+        }
+
+        let text = problem(
+            "(30, 2)",
+            "let l: object = Line(27, 0); let p: object = Pair(0, 0);",
+        );
+        assert!(check(&text).is_empty());
+    }
+
+    #[test]
+    fn test_empty_ranges() {
+        for range in ["(0..0)", "[0..0)", "(0..0]", "(0..1)", "(3..2)"] {
+            let text = format!(
+                "object Row {{ shape: [(x, 0) | x <- {}], color: 1 }};",
+                range
+            );
+            let diagnostics = check(&text);
+
+            assert_eq!(vec![Code::E0018], codes(&diagnostics), "{}", range);
+            assert_eq!(range, diagnostics[0].window.snippet);
+        }
+
+        for range in ["[0..0]", "(0..2)", "[1..0)", "(0..0] U [0..0]"] {
+            let text = format!(
+                "object Row {{ shape: [(x, 0) | x <- {}], color: 1 }};",
+                range
+            );
+            let diagnostics = check(&text);
+
+            if range.contains('U') {
+                assert_eq!(vec![Code::E0018], codes(&diagnostics), "{}", range);
+            } else {
+                assert!(
+                    diagnostics.is_empty(),
+                    "{}: {:?}",
+                    range,
+                    codes(&diagnostics)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_positive_steps() {
+        for (step, note) in [
+            ("0", "The range steps by 0, but a step has to be 1 or more."),
+            (
+                "-1",
+                "The range steps by -1, but a step has to be 1 or more.",
+            ),
+        ] {
+            let text = format!(
+                "object Row {{ shape: [(x, 0) | x <- [0..4 by {}]], color: 1 }};",
+                step
+            );
+            let diagnostics = check(&text);
+
+            assert_eq!(vec![Code::E0021], codes(&diagnostics), "{}", step);
+            assert_eq!(step, diagnostics[0].window.snippet);
+            assert_eq!(note, diagnostics[0].note);
+        }
+
+        let text = "object Row { shape: [(x, 0) | x <- (0..4 by 2]], color: 1 };";
+        assert!(check(text).is_empty(), "{:?}", codes(&check(text)));
+    }
+
+    #[test]
+    fn test_ranges_bound_by_variables_are_not_checked() {
+        let text = "object Steps { shape: [(x, y) | x <- [0..2], y <- [0..x)], color: 1 };";
+
+        assert!(check(text).is_empty(), "{:?}", codes(&check(text)));
+    }
+}
@@ -0,0 +1,139 @@
+use erminia::ast::ast::BoxAST;
+use erminia::ast::stmt::VarDef;
+use erminia::ast::visit::*;
+use erminia::config::CompilerPass;
+use erminia::diagnostics::DiagnosticAccumulator;
+use erminia::lexer::lex::Lexer;
+use erminia::syntax::parse::*;
+
+#[cfg(test)]
+mod test_span {
+    use super::*;
+
+    // Every node met, as its name and the source text its span covers
+    struct Snippets<'t, 'input> {
+        tokens: &'t Lexer<'input>,
+        nodes: Vec<(String, String)>,
+        syntax: Vec<String>,
+    }
+
+    impl<'a> Visitor<'a> for Snippets<'_, '_> {
+        fn visit_ast(&mut self, node: &BoxAST<'a>) {
+            let snippet = self.tokens.get_snippet(node.get_span()).to_string();
+
+            self.nodes.push((node.to_string(), snippet));
+
+            walk_ast(self, node)
+        }
+
+        fn visit_var_def(&mut self, node: &VarDef<'a>) {
+            for token in &node.syntax {
+                let snippet = self.tokens.get_snippet(token.span).to_string();
+                self.syntax.push(snippet);
+            }
+
+            walk_var_def(self, node)
+        }
+    }
+
+    fn snippets(text: &str) -> (Vec<(String, String)>, Vec<String>) {
+        let mut tokens = Lexer::new(text);
+        let mut diag = DiagnosticAccumulator::new();
+
+        let program = parse_program(&mut tokens, &mut diag);
+
+        let mut snippets = Snippets {
+            tokens: &tokens,
+            nodes: vec![],
+            syntax: vec![],
+        };
+
+        snippets.visit_ast(&program);
+
+        (snippets.nodes, snippets.syntax)
+    }
+
+    fn check_spans(text: &str, expected: Vec<(&str, &str)>) {
+        let (nodes, _) = snippets(text);
+
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(name, snippet)| (name.to_string(), snippet.to_string()))
+            .collect();
+
+        assert_eq!(expected, nodes);
+    }
+
+    #[test]
+    fn test_expression_spans() {
+        let text = "def p () { let a: int = -(1 + x) * 2; }";
+
+        check_spans(
+            text,
+            vec![
+                ("Program", "def p () { let a: int = -(1 + x) * 2; }"),
+                ("ProblemDecl", "def p () { let a: int = -(1 + x) * 2; }"),
+                ("VarDef", "let a: int = -(1 + x) * 2;"),
+                ("BinaryExpr", "-(1 + x) * 2"),
+                ("UnaryExpr", "-(1 + x)"),
+                ("BinaryExpr", "1 + x"),
+                ("RValue", "1"),
+                ("RValue", "x"),
+                ("RValue", "2"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_spans_stop_at_last_token() {
+        let text = "object Dot { shape: [(0,0), (2, 1)], color: 1 };   \n\ndef p () { }";
+
+        check_spans(
+            text,
+            vec![
+                ("Program", text),
+                (
+                    "ObjectDecl",
+                    "object Dot { shape: [(0,0), (2, 1)], color: 1 };",
+                ),
+                ("ObjectDesc", "shape: [(0,0), (2, 1)], color: 1"),
+                ("ObjectShape", "shape: [(0,0), (2, 1)]"),
+                ("Tuple", "(0,0)"),
+                ("Tuple", "(2, 1)"),
+                ("ObjectColor", "color: 1"),
+                ("ProblemDecl", "def p () { }"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_syntax_token_spans() {
+        let (_, syntax) = snippets("def p () { let a: int = 1 + 2; }");
+
+        assert_eq!(vec!["let", ":", "=", ";"], syntax);
+    }
+
+    #[test]
+    fn test_error_spans_point_at_offending_token() {
+        let cases = [
+            ("def p () { let a: strng = 1; }", "strng"),
+            ("def p () { let : int = 1; }", ":"),
+            ("object A { shape: [(0,0)], color: x };", "x"),
+            ("object A { shape: [(x, 0) | x <- {0..2]], color: 1 };", "{"),
+            ("object A { shape: [(x, 0) | x <- [0..2}], color: 1 };", "}"),
+            ("def p () { let a: int = 1 ) }", ")"),
+        ];
+
+        for (text, offending) in cases {
+            let mut tokens = Lexer::new(text);
+            let mut diag = DiagnosticAccumulator::new();
+
+            let _ = parse_program(&mut tokens, &mut diag);
+
+            let diagnostics = diag.get(CompilerPass::ALL);
+            let snippet = tokens.get_snippet(diagnostics[0].window.span);
+
+            assert_eq!(offending, snippet, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use erminia::config::CompilerPass;
+use erminia::diagnostics::{
+    apply_suggestions, edit_distance, similar_names, Applicability, Span, Suggestion,
+};
+use erminia::lexer::lex::Lexer;
+use erminia::lexer::token::TokenKind;
+use erminia::loader::Loader;
+use erminia::syntax::consumers::current_span;
+use erminia::syntax::Parser;
+
+#[cfg(test)]
+mod test_suggestion {
+    use super::*;
+
+    fn token_spans(text: &str) -> Vec<Span> {
+        let mut tokens = Lexer::new(text);
+        let mut spans = vec![];
+
+        tokens.advance();
+        while tokens.token.get_kind() != TokenKind::EOF {
+            spans.push(current_span(&tokens));
+            tokens.advance();
+        }
+
+        spans
+    }
+
+    fn insert_after(span: Span) -> Span {
+        Span::new(span.end, span.end)
+    }
+
+    fn suggestions(text: &str) -> Vec<Suggestion> {
+        let mut parser = Parser::new(text);
+        parser.parse();
+
+        parser
+            .get_diagnostics()
+            .get(CompilerPass::ALL)
+            .into_iter()
+            .flat_map(|d| d.suggestions)
+            .collect()
+    }
+
+    fn fix(text: &str) -> String {
+        let fixes: Vec<Suggestion> = suggestions(text)
+            .into_iter()
+            .filter(|s| s.is_machine_applicable())
+            .collect();
+
+        apply_suggestions(text, &fixes).0
+    }
+
+    // What `erminia-cli fix` writes back for `text`, with the suggestions of every pass
+    fn fix_file(text: &str) -> String {
+        let mut loader = Loader::new();
+        loader.add_virtual_file("main.erm", text);
+        loader.load("main.erm").unwrap();
+
+        let fixes: Vec<Suggestion> = loader
+            .get_diagnostics()
+            .get(CompilerPass::ALL)
+            .into_iter()
+            .flat_map(|d| d.suggestions)
+            .filter(|s| s.is_machine_applicable())
+            .collect();
+
+        apply_suggestions(text, &fixes).0
+    }
+
+    fn has_errors(text: &str) -> bool {
+        let mut parser = Parser::new(text);
+        parser.parse();
+
+        !parser.get_diagnostics().get(CompilerPass::ALL).is_empty()
+    }
+
+    #[test]
+    fn test_apply_suggestions() {
+        let source = "let a = 1 let b = 2";
+        let spans = token_spans(source);
+        let fixes = [
+            Suggestion::new(
+                insert_after(spans[3]),
+                ";",
+                Applicability::MachineApplicable,
+            ),
+            Suggestion::new(spans[0], "var", Applicability::MachineApplicable),
+            // Overlaps the one above, left out
+            Suggestion::new(spans[0], "x", Applicability::MachineApplicable),
+            Suggestion::new(
+                insert_after(spans[7]),
+                ";",
+                Applicability::MachineApplicable,
+            ),
+        ];
+
+        let (fixed, applied) = apply_suggestions(source, &fixes);
+
+        assert_eq!("var a = 1; let b = 2;", fixed);
+        assert_eq!(3, applied);
+    }
+
+    #[test]
+    fn test_missing_terminator() {
+        let text = "def p () {\n    let a: int = 1\n    let b: int = 2;\n}";
+
+        let found = suggestions(text);
+        assert_eq!(1, found.len());
+        assert!(found[0].is_machine_applicable());
+
+        let fixed = fix(text);
+        assert_eq!(
+            "def p () {\n    let a: int = 1;\n    let b: int = 2;\n}",
+            fixed
+        );
+        assert!(!has_errors(&fixed));
+    }
+
+    #[test]
+    fn test_range_bracket() {
+        let text = "object A { shape: [(x, 0) | x <- [1..3}], color: 1 };";
+
+        let fixed = fix(text);
+        assert_eq!(
+            "object A { shape: [(x, 0) | x <- [1..3]], color: 1 };",
+            fixed
+        );
+        assert!(!has_errors(&fixed));
+    }
+
+    #[test]
+    fn test_misspelled_keyword() {
+        let text = "def p () {\n    lett a: int = 1;\n}";
+        let fixed = fix(text);
+
+        assert_eq!("def p () {\n    let a: int = 1;\n}", fixed);
+        assert!(!has_errors(&fixed));
+
+        // As close to 'offset_x' as to 'offset_y', so neither is applied
+        let text = "def p () { let a: object = Dot(offset_z: 1); }";
+        assert_eq!(text, fix(text));
+    }
+
+    #[test]
+    fn test_fix_keeps_names_differing_in_case() {
+        // 'b' is a variable, not an object 'B' was misspelled from
+        let text = "object A { shape: [(0, 0)], color: 1 };\ndef p () { let b = B(0, 0); }";
+        assert_eq!(text, fix_file(text));
+
+        let text = "object b { shape: [(0, 0)], color: 1 };\ndef p () { let c = B(0, 0); }";
+        assert_eq!(text, fix_file(text));
+
+        let text = "object Dot { shape: [(0, 0)], color: 1 };\ndef p () { let c = Dto(0, 0); }";
+        assert_eq!(text.replace("Dto", "Dot"), fix_file(text));
+    }
+
+    #[test]
+    fn test_rendered_fix() {
+        let mut parser = Parser::new("def p () {\n    let a: int = 1\n}");
+        parser.parse();
+
+        let rendered = parser.get_diagnostics().get(CompilerPass::Parser)[0].render(false);
+
+        assert!(
+            rendered.contains("= fix: insert `;` at 2:19"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("shape", "shape"));
+        assert_eq!(1, edit_distance("shpae", "shape"));
+        assert_eq!(1, edit_distance("colour", "color"));
+        assert_eq!(3, edit_distance("", "let"));
+        assert_eq!(2, edit_distance("offest_y", "offset_x"));
+    }
+
+    #[test]
+    fn test_similar_names_ranked() {
+        let candidates = ["objects", "object", "Object", "func", "let"];
+
+        assert_eq!(
+            vec!["objects", "Object", "object"],
+            similar_names("Objects", &candidates)
+        );
+        assert_eq!(vec!["object"], similar_names("objet", &candidates));
+        assert!(similar_names("lett", &["func", "def"]).is_empty());
+        assert!(similar_names(";", &[":"]).is_empty());
+    }
+
+    #[test]
+    fn test_did_you_mean_keyword() {
+        for (text, typo, keyword) in [
+            ("object A { shpae: [(0, 0)], color: 1 };", "shpae", "shape"),
+            (
+                "object A { colour: 1, shape: [(0, 0)] };",
+                "colour",
+                "color",
+            ),
+            ("objet A { shape: [(0, 0)], color: 1 };", "objet", "object"),
+            ("def p () {\n    lett a: int = 1;\n}", "lett", "let"),
+            ("def p () {\n    let a: itn = 1;\n}", "itn", "int"),
+        ] {
+            let mut parser = Parser::new(text);
+            parser.parse();
+
+            let diagnostics = parser.get_diagnostics().get(CompilerPass::Parser);
+            let diagnostic = &diagnostics[0];
+
+            assert_eq!(format!("Did you mean '{}'?", keyword), diagnostic.help);
+            assert_eq!(1, diagnostic.suggestions.len());
+
+            let fixed = apply_suggestions(text, &diagnostic.suggestions).0;
+            assert_eq!(text.replacen(typo, keyword, 1), fixed);
+        }
+    }
+}
@@ -41,6 +41,46 @@ mod test_token {
         check_tk_eq("def", Ok(TokenKind::ProblemDef))
     }
 
+    #[test]
+    fn test_tk_func() {
+        check_tk_eq("func", Ok(TokenKind::FuncKwd))
+    }
+
+    #[test]
+    fn test_tk_include() {
+        check_tk_eq("include", Ok(TokenKind::IncludeKwd))
+    }
+
+    #[test]
+    fn test_tk_use() {
+        check_tk_eq("use", Ok(TokenKind::UseKwd))
+    }
+
+    #[test]
+    fn test_tk_path_sep() {
+        check_tk_eq("::", Ok(TokenKind::PathSep))
+    }
+
+    #[test]
+    fn test_tk_int_type() {
+        check_tk_eq("int", Ok(TokenKind::IntType))
+    }
+
+    #[test]
+    fn test_tk_string_type() {
+        check_tk_eq("string", Ok(TokenKind::StringType))
+    }
+
+    #[test]
+    fn test_tk_void_type() {
+        check_tk_eq("void", Ok(TokenKind::VoidType))
+    }
+
+    #[test]
+    fn test_tk_right_arrow() {
+        check_tk_eq("->", Ok(TokenKind::RightArrow))
+    }
+
     #[test]
     fn test_tk_let() {
         check_tk_eq("let", Ok(TokenKind::LetKwd))
@@ -86,6 +126,41 @@ mod test_token {
         check_tk_eq("=", Ok(TokenKind::Equals))
     }
 
+    #[test]
+    fn test_tk_range_step() {
+        check_tk_eq("by", Ok(TokenKind::RangeStep))
+    }
+
+    #[test]
+    fn test_tk_range_union() {
+        check_tk_eq("U", Ok(TokenKind::RangeUnion))
+    }
+
+    #[test]
+    fn test_tk_double_equals() {
+        check_tk_eq("==", Ok(TokenKind::DoubleEquals))
+    }
+
+    #[test]
+    fn test_tk_lesser_equals() {
+        check_tk_eq("<=", Ok(TokenKind::LesserEquals))
+    }
+
+    #[test]
+    fn test_tk_greater_equals() {
+        check_tk_eq(">=", Ok(TokenKind::GreaterEquals))
+    }
+
+    #[test]
+    fn test_tk_and() {
+        check_tk_eq("&&", Ok(TokenKind::And))
+    }
+
+    #[test]
+    fn test_tk_or() {
+        check_tk_eq("||", Ok(TokenKind::Or))
+    }
+
     #[test]
     fn test_tk_leftpar() {
         check_tk_eq("(", Ok(TokenKind::LeftPar))
@@ -0,0 +1,111 @@
+use erminia::ast::ast::BoxAST;
+use erminia::ast::expr::{ObjectCall, RValue, RValueKind};
+use erminia::ast::node::NodeId;
+use erminia::ast::stmt::VarDef;
+use erminia::ast::visit::*;
+use erminia::diagnostics::Span;
+use erminia::syntax::Parser;
+use erminia::types::ErminiaType;
+
+#[cfg(test)]
+mod test_visit {
+    use super::*;
+
+    fn parse(text: &str) -> BoxAST<'_> {
+        Parser::new(text).parse()
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        nodes: usize,
+        object_calls: Vec<String>,
+    }
+
+    impl<'a> Visitor<'a> for Counter {
+        fn visit_ast(&mut self, node: &BoxAST<'a>) {
+            self.nodes += 1;
+            walk_ast(self, node)
+        }
+
+        fn visit_object_call(&mut self, node: &ObjectCall) {
+            self.object_calls.push(node.id.to_id());
+        }
+    }
+
+    struct Rename {
+        from: &'static str,
+        to: &'static str,
+    }
+
+    impl<'a> VisitorMut<'a> for Rename {
+        fn visit_object_call_mut(&mut self, node: &mut ObjectCall) {
+            if node.id.to_id() == self.from {
+                node.id = ErminiaType::Ident(self.to.to_string());
+            }
+        }
+
+        fn visit_rvalue_mut(&mut self, node: &mut RValue) {
+            if matches!(&node.value, RValueKind::Id(id) if id == self.from) {
+                node.value = RValueKind::Id(self.to.to_string());
+            }
+        }
+    }
+
+    // Replaces the expression of every variable by the constant 0
+    struct Zero;
+
+    impl<'a> VisitorMut<'a> for Zero {
+        fn visit_var_def_mut(&mut self, node: &mut VarDef<'a>) {
+            node.expr = RValue::boxed_int(NodeId::UNASSIGNED, 0, Span::default());
+        }
+    }
+
+    #[test]
+    fn test_visitor_reaches_every_node() {
+        let program = parse(
+            "object Dot { shape: [(0,0)], color: 1 };
+            def p () { let a: object = Dot(1, 2) + Line + Dot(3, 4); }",
+        );
+
+        let mut counter = Counter::default();
+        counter.visit_ast(&program);
+
+        // Program, ObjectDecl, ObjectDesc, ObjectShape, Tuple, ObjectColor, ProblemDecl,
+        // VarDef, two BinaryExprs, two ObjectCalls and the RValue
+        assert_eq!(13, counter.nodes);
+        assert_eq!(vec!["Dot", "Dot"], counter.object_calls);
+    }
+
+    #[test]
+    fn test_visitor_mut_renames() {
+        let mut program = parse("def p () { let a: object = Dot(1, 2) + Dot; }");
+
+        Rename {
+            from: "Dot",
+            to: "Point",
+        }
+        .visit_ast_mut(&mut program);
+
+        let mut counter = Counter::default();
+        counter.visit_ast(&program);
+
+        let printed = format!("{:?}", program);
+
+        assert_eq!(vec!["Point"], counter.object_calls);
+        assert!(printed.contains("Point"));
+        assert!(!printed.contains("Dot"));
+    }
+
+    #[test]
+    fn test_visitor_mut_replaces_nodes() {
+        let mut program = parse("def p () { let a: object = Dot(1, 2); let b: int = x; }");
+
+        Zero.visit_ast_mut(&mut program);
+
+        let mut counter = Counter::default();
+        counter.visit_ast(&program);
+
+        assert!(counter.object_calls.is_empty());
+        assert_eq!(6, counter.nodes);
+    }
+}